@@ -0,0 +1,166 @@
+//! Parsing, exporting, rendering and checking a project with 10,000 requirements, run with
+//! `cargo bench`
+
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use regex::Regex;
+use req::format::{parse_with_format, Format};
+use req::{check, demo_project, export, reqif, trace, Project};
+
+const TOPICS: usize = 100;
+const REQUIREMENTS_PER_TOPIC: usize = 100;
+
+/// The demo project with its topics replaced by many topics of linked requirements
+fn project() -> Project {
+    let mut project = demo_project();
+    let mut template = project
+        .topics
+        .values()
+        .next()
+        .cloned()
+        .expect("Demo has topics");
+    let requirement = template
+        .requirements
+        .values()
+        .next()
+        .cloned()
+        .expect("Demo topics have requirements");
+    template.requirements.clear();
+    template.subtopics.clear();
+    project.topics.clear();
+    for t in 0..TOPICS {
+        let mut topic = template.clone();
+        topic.name = format!("Topic {t}");
+        for r in 0..REQUIREMENTS_PER_TOPIC {
+            let n = t * REQUIREMENTS_PER_TOPIC + r + 1;
+            let mut requirement = requirement.clone();
+            requirement.name = format!("Requirement {n}");
+            requirement.description =
+                format!("The system shall handle case {n} within {} ms.", n % 500);
+            requirement.links = if n > 1 {
+                vec![format!("REQ-{:05}", n - 1)]
+            } else {
+                Vec::new()
+            };
+            topic
+                .requirements
+                .insert(format!("REQ-{n:05}"), requirement);
+        }
+        project.topics.insert(format!("TOPIC-{t}"), topic);
+    }
+    project
+}
+
+fn parse(c: &mut Criterion) {
+    let project = project();
+    let mut group = c.benchmark_group("parse");
+    group.sample_size(10);
+    for (name, format) in [("yaml", Format::Yaml), ("json", Format::Json)] {
+        let text = format.serialize(&project).unwrap();
+        group.bench_function(name, |b| {
+            b.iter(|| parse_with_format::<Project>(black_box(&text)).unwrap());
+        });
+    }
+    group.finish();
+}
+
+fn export(c: &mut Criterion) {
+    let project = project();
+    let mut group = c.benchmark_group("export");
+    group.sample_size(10);
+    group.bench_function("yaml", |b| {
+        b.iter(|| Format::Yaml.serialize(black_box(&project)).unwrap());
+    });
+    let attributes = reqif::AttributeMap::preset(reqif::Preset::Generic);
+    group.bench_function("reqif", |b| {
+        b.iter(|| reqif::to_reqif(black_box(&project), &attributes, 0));
+    });
+    group.bench_function("jsonl", |b| {
+        b.iter(|| export::jsonl(&export::records(black_box(&project), None)));
+    });
+    group.finish();
+}
+
+/// The commands rendering documents, run as a whole since the renderers are part of the binary
+fn render(c: &mut Criterion) {
+    let dir = std::env::temp_dir().join(format!("req-bench-render-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let requirements = dir.join("requirements.yml");
+    std::fs::write(&requirements, Format::Yaml.serialize(&project()).unwrap()).unwrap();
+    let mut group = c.benchmark_group("render");
+    group.sample_size(10);
+    for command in ["markdown", "html"] {
+        group.bench_function(command, |b| {
+            b.iter(|| {
+                let output = Command::new(env!("CARGO_BIN_EXE_req"))
+                    .arg(command)
+                    .arg(&requirements)
+                    .output()
+                    .unwrap();
+                assert!(output.status.success());
+                output.stdout
+            });
+        });
+    }
+    group.finish();
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+/// Test output files reporting on every requirement and a source file mentioning every tenth
+fn evidence(dir: &Path) -> (PathBuf, PathBuf) {
+    let tests = dir.join("tests");
+    std::fs::create_dir_all(&tests).unwrap();
+    let count = TOPICS * REQUIREMENTS_PER_TOPIC;
+    for file in 0..5 {
+        let lines: Vec<_> = (1..=count)
+            .filter(|n| n % 5 == file)
+            .map(|n| match n % 3 {
+                0 => format!("REQ-{n:05}: failed - error {n}"),
+                _ => format!("REQ-{n:05}: passed"),
+            })
+            .collect();
+        std::fs::write(tests.join(format!("{file}.txt")), lines.join("\n")).unwrap();
+    }
+    let source = dir.join("source.rs");
+    let lines: Vec<_> = (1..=count)
+        .step_by(10)
+        .map(|n| format!("// Implements REQ-{n:05}"))
+        .collect();
+    std::fs::write(&source, lines.join("\n")).unwrap();
+    (tests, source)
+}
+
+fn check(c: &mut Criterion) {
+    let project = project();
+    let dir = std::env::temp_dir().join(format!("req-bench-{}", std::process::id()));
+    let (tests, source) = evidence(&dir);
+    let contents: Vec<_> = trace::files(std::slice::from_ref(&tests))
+        .unwrap()
+        .iter()
+        .map(|file| std::fs::read_to_string(file).unwrap())
+        .collect();
+    let allowed = [Regex::new("REQ-.*").unwrap()];
+    let mut group = c.benchmark_group("check");
+    group.sample_size(10);
+    group.bench_function("report", |b| {
+        b.iter(|| check::report(black_box(&project), &contents, &allowed, 2));
+    });
+    group.bench_function("trace", |b| {
+        b.iter(|| {
+            trace::trace(
+                black_box(&project),
+                std::slice::from_ref(&tests),
+                std::slice::from_ref(&source),
+            )
+            .unwrap()
+        });
+    });
+    group.finish();
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+criterion_group!(benches, parse, export, render, check);
+criterion_main!(benches);
@@ -0,0 +1,140 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::trace::{self, Status};
+use crate::{oslc, Project, Requirement};
+
+/// An answer of the API, JSON unless a rendering was requested
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    fn json(status: u16, value: &impl Serialize) -> Response {
+        Response {
+            status,
+            content_type: "application/json",
+            body: serde_json::to_string_pretty(value).expect("Responses serialize to JSON") + "\n",
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Response {
+        Response::json(status, &serde_json::json!({ "error": message }))
+    }
+}
+
+#[derive(Serialize)]
+struct Entry<'a> {
+    id: &'a str,
+    topic_path: Vec<String>,
+    #[serde(flatten)]
+    requirement: &'a Requirement,
+}
+
+#[derive(Serialize)]
+struct Details<'a> {
+    id: &'a str,
+    topic_path: Vec<String>,
+    topic_names: Vec<String>,
+    #[serde(flatten)]
+    requirement: &'a Requirement,
+    linked_from: Vec<String>,
+}
+
+fn requirements(project: &Project) -> Response {
+    let mut entries = Vec::new();
+    project.visit_requirements(|r| {
+        let entry = Entry {
+            id: r.id,
+            topic_path: r.topic_path.iter().map(|p| p.to_string()).collect(),
+            requirement: r.requirement,
+        };
+        entries.push(serde_json::to_value(entry).expect("Requirements serialize to JSON"));
+    });
+    Response::json(200, &entries)
+}
+
+fn requirement(project: &Project, id: &str) -> Response {
+    let Some((topic_path, requirement)) = project.find_requirement(id) else {
+        return Response::error(404, &format!("No requirement with ID {id}"));
+    };
+    let topic_names = (1..=topic_path.len())
+        .filter_map(|i| project.topic(&topic_path[..i]))
+        .map(|t| t.name.trim().to_string())
+        .collect();
+    Response::json(
+        200,
+        &Details {
+            id,
+            topic_path,
+            topic_names,
+            requirement,
+            linked_from: project.linking_to(&[id]),
+        },
+    )
+}
+
+fn check(project: &Project, tests: &[PathBuf]) -> anyhow::Result<Response> {
+    if tests.is_empty() {
+        return Ok(Response::error(
+            404,
+            "No test output files given or configured",
+        ));
+    }
+    let traces = trace::trace(project, tests, &[])?;
+    let count = |status| traces.iter().filter(|t| t.status == status).count();
+    let requirements: Vec<_> = traces
+        .iter()
+        .map(|t| {
+            serde_json::json!({
+                "id": t.id,
+                "name": t.name,
+                "status": t.status,
+                "tests": t.tests,
+            })
+        })
+        .collect();
+    Ok(Response::json(
+        200,
+        &serde_json::json!({
+            "passed": count(Status::Passed),
+            "failed": count(Status::Failed),
+            "untested": count(Status::Untested),
+            "requirements": requirements,
+        }),
+    ))
+}
+
+/// Answers a request to the API
+///
+/// The endpoints are `/requirements`, `/requirements/{id}`, `/check` with the status of every
+/// requirement in the output of `tests`, and `/render/markdown` with the output of `markdown`.
+pub fn handle(
+    project: &Project,
+    method: &str,
+    url: &str,
+    tests: &[PathBuf],
+    markdown: impl FnOnce(&Project) -> String,
+) -> Response {
+    if method != "GET" {
+        return Response::error(405, "Only GET requests are supported");
+    }
+    let path = url.split_once('?').map_or(url, |(path, _)| path);
+    let result = match path.trim_end_matches('/') {
+        "/requirements" => Ok(requirements(project)),
+        "/check" => check(project, tests),
+        "/render/markdown" => Ok(Response {
+            status: 200,
+            content_type: "text/markdown; charset=utf-8",
+            body: markdown(project),
+        }),
+        path => match path.strip_prefix("/requirements/") {
+            Some(id) => Ok(requirement(project, oslc::percent_decode_path(id).trim())),
+            None => Ok(Response::error(404, &format!("No endpoint {path}"))),
+        },
+    };
+    result.unwrap_or_else(|e| Response::error(500, &format!("{e}")))
+}
@@ -0,0 +1,91 @@
+use clap::ValueEnum;
+use indexmap::IndexMap;
+use regex::Regex;
+
+use crate::{Priority, Requirement, Topic};
+
+/// A change to apply to every selected requirement
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// Replaces a field, given as `field=value`
+    Set { field: String, value: String },
+    /// Adds a line of additional information, unless the requirement has it already
+    AppendInfo(String),
+}
+
+/// The fields `--set` can change
+pub const FIELDS: [&str; 4] = ["name", "description", "links", "priority"];
+
+impl Change {
+    /// Parses a `field=value` assignment and checks that the field exists
+    pub fn set(assignment: &str) -> anyhow::Result<Change> {
+        let Some((field, value)) = assignment.split_once('=') else {
+            anyhow::bail!("Expected field=value, got '{assignment}'");
+        };
+        let field = field.trim();
+        if !FIELDS.contains(&field) {
+            anyhow::bail!(
+                "Unknown field '{field}', expected one of {}",
+                FIELDS.join(", ")
+            );
+        }
+        if field == "priority" && !value.trim().is_empty() {
+            Priority::from_str(value.trim(), true)
+                .map_err(|e| anyhow::anyhow!("Invalid priority '{}': {e}", value.trim()))?;
+        }
+        Ok(Change::Set {
+            field: field.to_string(),
+            value: value.to_string(),
+        })
+    }
+
+    fn apply(&self, requirement: &mut Requirement) {
+        match self {
+            Change::Set { field, value } => match field.as_str() {
+                "name" => requirement.name.clone_from(value),
+                "description" => requirement.description.clone_from(value),
+                "links" => {
+                    requirement.links = value
+                        .split(',')
+                        .map(|l| l.trim().to_string())
+                        .filter(|l| !l.is_empty())
+                        .collect();
+                }
+                // An empty value removes the priority
+                "priority" => requirement.priority = Priority::from_str(value.trim(), true).ok(),
+                field => unreachable!("Field {field} is checked when parsing"),
+            },
+            Change::AppendInfo(info) => {
+                if !requirement.additional_info.iter().any(|i| i.trim() == info) {
+                    requirement.additional_info.push(info.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Applies the changes to every requirement whose ID matches `filter`, returning the IDs of the
+/// requirements that changed
+pub fn apply(
+    topics: &mut IndexMap<String, Topic>,
+    filter: &Regex,
+    changes: &[Change],
+) -> Vec<String> {
+    let mut changed = Vec::new();
+    for topic in topics.values_mut() {
+        for (id, requirement) in &mut topic.requirements {
+            if !filter.is_match(id.trim()) {
+                continue;
+            }
+            let before = requirement.clone();
+            for change in changes {
+                change.apply(requirement);
+            }
+            if *requirement != before {
+                changed.push(id.trim().to_string());
+            }
+        }
+        changed.extend(apply(&mut topic.subtopics, filter, changes));
+    }
+    changed
+}
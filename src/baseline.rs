@@ -0,0 +1,75 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::lock::{self, Change, Lock};
+use crate::Project;
+
+/// A frozen snapshot of the requirements with their content hashes
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Baseline {
+    pub name: String,
+    pub lock: Lock,
+    pub project: Project,
+}
+
+/// The directory baselines of `requirements` are stored in
+#[must_use]
+pub fn directory(requirements: &Path) -> PathBuf {
+    requirements
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(".baselines")
+}
+
+/// The file the baseline `name` of `requirements` is stored in
+#[must_use]
+pub fn path(requirements: &Path, name: &str) -> PathBuf {
+    directory(requirements).join(format!("{name}.json"))
+}
+
+#[must_use]
+pub fn create(name: &str, project: &Project) -> Baseline {
+    Baseline {
+        name: name.to_string(),
+        lock: lock::lock(project),
+        project: project.clone(),
+    }
+}
+
+/// Loads the baseline `name` and checks that its snapshot still matches its hashes
+pub fn load(requirements: &Path, name: &str) -> anyhow::Result<Baseline> {
+    let path = path(requirements, name);
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| anyhow::anyhow!("Cannot read baseline {name} at {}: {e}", path.display()))?;
+    let baseline: Baseline = serde_json::from_str(&content)?;
+    if lock::lock(&baseline.project) != baseline.lock {
+        anyhow::bail!("Baseline {name} was modified after it was created");
+    }
+    Ok(baseline)
+}
+
+/// Loads all baselines of `requirements`, ordered by the version of their snapshot
+pub fn all(requirements: &Path) -> anyhow::Result<Vec<Baseline>> {
+    let directory = directory(requirements);
+    if !directory.exists() {
+        return Ok(Vec::new());
+    }
+    let mut baselines = Vec::new();
+    for entry in std::fs::read_dir(directory)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|e| e == "json") {
+            if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                baselines.push(load(requirements, name)?);
+            }
+        }
+    }
+    baselines.sort_by(|a, b| (&a.project.version, &a.name).cmp(&(&b.project.version, &b.name)));
+    Ok(baselines)
+}
+
+/// Lists the requirements that drifted from `baseline`
+#[must_use]
+pub fn compare(baseline: &Baseline, project: &Project) -> Vec<Change> {
+    lock::changes(&baseline.lock, &lock::lock(project))
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::format::{self, parse_with_format};
+use crate::{fingerprint, Fragment, Project, Topic};
+
+/// The commit that last changed a requirement
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Provenance {
+    /// The hash of the commit, empty if the change is not committed yet
+    pub commit: String,
+    pub author: String,
+    /// The author date in strict ISO 8601 format
+    pub date: String,
+    pub summary: String,
+}
+
+impl Provenance {
+    fn uncommitted() -> Provenance {
+        Provenance {
+            commit: String::new(),
+            author: "Not committed yet".to_string(),
+            date: String::new(),
+            summary: String::new(),
+        }
+    }
+
+    /// Describes the change in one line, like `2024-05-01 by Jane Doe (1a2b3c4)`
+    #[must_use]
+    pub fn describe(&self) -> String {
+        if self.commit.is_empty() {
+            return self.author.clone();
+        }
+        format!(
+            "{} by {} ({})",
+            self.date.get(..10).unwrap_or(&self.date),
+            self.author,
+            &self.commit[..self.commit.len().min(7)]
+        )
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| anyhow::anyhow!("Failed to run git: {e}"))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn collect(topics: &IndexMap<String, Topic>, fingerprints: &mut HashMap<String, String>) {
+    for topic in topics.values() {
+        for (id, requirement) in &topic.requirements {
+            fingerprints.insert(id.trim().to_string(), fingerprint::fingerprint(requirement));
+        }
+        collect(&topic.subtopics, fingerprints);
+    }
+}
+
+/// Fingerprints the requirements defined in one file, `None` if it does not parse
+fn file_fingerprints(content: &str) -> Option<HashMap<String, String>> {
+    // RSN checks struct names, so the root file only parses as a project
+    let fragment = parse_with_format::<Fragment>(content)
+        .map(|(f, _)| f)
+        .or_else(|_| parse_with_format::<Project>(content).map(|(p, _)| p.into()))
+        .ok()?;
+    let mut fingerprints = HashMap::new();
+    collect(&fragment.topics, &mut fingerprints);
+    for (id, requirement) in &fragment.archive {
+        fingerprints.insert(id.trim().to_string(), fingerprint::fingerprint(requirement));
+    }
+    Some(fingerprints)
+}
+
+/// Finds the commit that last changed each requirement in the history of the requirements file
+/// and the files it includes
+///
+/// A requirement counts as changed when its fingerprint changed, so reformatting the file
+/// does not move the blame.
+pub fn blame(requirements: &Path) -> anyhow::Result<HashMap<String, Provenance>> {
+    let mut provenance = HashMap::new();
+    for file in format::source_files(requirements)? {
+        let dir = file.parent().unwrap_or(Path::new("."));
+        let name = file
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("Invalid path {}", file.display()))?
+            .to_string_lossy()
+            .to_string();
+        let Some(current) = file_fingerprints(&std::fs::read_to_string(&file)?) else {
+            anyhow::bail!("Failed to parse {}", file.display());
+        };
+        let log = git(
+            dir,
+            &["log", "--format=%H%x1f%an%x1f%aI%x1f%s", "--", &name],
+        )?;
+        let commits: Vec<Provenance> = log
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split('\x1f').map(str::to_string);
+                Some(Provenance {
+                    commit: fields.next()?,
+                    author: fields.next()?,
+                    date: fields.next()?,
+                    summary: fields.next().unwrap_or_default(),
+                })
+            })
+            .collect();
+        tracing::info!(
+            "Found {} commits changing {}",
+            commits.len(),
+            file.display()
+        );
+        // The newest state first, the working tree before all commits
+        let mut states = vec![(Provenance::uncommitted(), Some(current.clone()))];
+        for commit in commits {
+            let content = git(dir, &["show", &format!("{}:./{name}", commit.commit)])?;
+            let fingerprints = file_fingerprints(&content);
+            if fingerprints.is_none() {
+                tracing::warn!("Skipping {} at {}, it does not parse", name, commit.commit);
+            }
+            states.push((commit, fingerprints));
+        }
+        for (id, hash) in &current {
+            // The oldest state in the unbroken run of states with the current content
+            let unchanged = states
+                .iter()
+                .take_while(|(_, fingerprints)| {
+                    fingerprints
+                        .as_ref()
+                        .is_none_or(|f| f.get(id) == Some(hash))
+                })
+                .filter(|(_, fingerprints)| fingerprints.is_some())
+                .last();
+            if let Some((commit, _)) = unchanged {
+                provenance.insert(id.clone(), commit.clone());
+            }
+        }
+    }
+    Ok(provenance)
+}
+
+/// Adds the last change of every requirement to its additional information
+pub fn annotate(project: &mut Project, provenance: &HashMap<String, Provenance>) {
+    fn annotate_topics(
+        topics: &mut IndexMap<String, Topic>,
+        provenance: &HashMap<String, Provenance>,
+    ) {
+        for topic in topics.values_mut() {
+            for (id, requirement) in &mut topic.requirements {
+                if let Some(provenance) = provenance.get(id.trim()) {
+                    requirement
+                        .additional_info
+                        .push(format!("Last changed: {}", provenance.describe()));
+                }
+            }
+            annotate_topics(&mut topic.subtopics, provenance);
+        }
+    }
+    annotate_topics(&mut project.topics, provenance);
+}
@@ -0,0 +1,254 @@
+use clap::ValueEnum;
+use indexmap::IndexMap;
+
+use crate::baseline::Baseline;
+use crate::trace::{Status, Trace};
+use crate::{diff, lint, lock, Project};
+
+/// The standard whose work product names the documents of a package follow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Standard {
+    /// Plain document names without references to a standard
+    Generic,
+    /// ISO 26262, road vehicles functional safety
+    #[value(name = "iso26262")]
+    Iso26262,
+    /// DO-178C, airborne software
+    #[value(name = "do178c")]
+    Do178c,
+}
+
+impl std::fmt::Display for Standard {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            Standard::Generic => "generic",
+            Standard::Iso26262 => "ISO 26262",
+            Standard::Do178c => "DO-178C",
+        })
+    }
+}
+
+/// The documents of a certification package
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Document {
+    Specification,
+    Traceability,
+    Verification,
+    OpenItems,
+    ChangeHistory,
+}
+
+impl Document {
+    pub const ALL: [Document; 5] = [
+        Document::Specification,
+        Document::Traceability,
+        Document::Verification,
+        Document::OpenItems,
+        Document::ChangeHistory,
+    ];
+
+    #[must_use]
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Document::Specification => "01-requirements-specification.md",
+            Document::Traceability => "02-traceability-matrix.md",
+            Document::Verification => "03-verification-report.md",
+            Document::OpenItems => "04-open-items.md",
+            Document::ChangeHistory => "05-change-history.md",
+        }
+    }
+
+    /// The title of the document and the clause of the standard it is the work product of
+    #[must_use]
+    pub fn title(self, standard: Standard) -> (&'static str, Option<&'static str>) {
+        match (standard, self) {
+            (Standard::Generic, Document::Specification) => ("Requirements Specification", None),
+            (Standard::Generic, Document::Traceability) => ("Traceability Matrix", None),
+            (Standard::Generic, Document::Verification) => ("Verification Report", None),
+            (Standard::Generic, Document::OpenItems) => ("Open Items", None),
+            (Standard::Generic, Document::ChangeHistory) => ("Change History", None),
+            (Standard::Iso26262, Document::Specification) => (
+                "Software Safety Requirements Specification",
+                Some("ISO 26262-6, Clause 6"),
+            ),
+            (Standard::Iso26262, Document::Traceability) => (
+                "Safety Requirements Traceability",
+                Some("ISO 26262-8, Clause 6"),
+            ),
+            (Standard::Iso26262, Document::Verification) => {
+                ("Verification Report", Some("ISO 26262-8, Clause 9"))
+            }
+            (Standard::Iso26262, Document::OpenItems) => {
+                ("Open Verification Items", Some("ISO 26262-8, Clause 9"))
+            }
+            (Standard::Iso26262, Document::ChangeHistory) => {
+                ("Change Management Records", Some("ISO 26262-8, Clause 8"))
+            }
+            (Standard::Do178c, Document::Specification) => {
+                ("Software Requirements Data", Some("DO-178C, Section 11.9"))
+            }
+            (Standard::Do178c, Document::Traceability) => {
+                ("Trace Data", Some("DO-178C, Section 11.21"))
+            }
+            (Standard::Do178c, Document::Verification) => (
+                "Software Verification Results",
+                Some("DO-178C, Section 11.14"),
+            ),
+            (Standard::Do178c, Document::OpenItems) => {
+                ("Problem Reports", Some("DO-178C, Section 11.17"))
+            }
+            (Standard::Do178c, Document::ChangeHistory) => (
+                "Software Configuration Management Records",
+                Some("DO-178C, Section 11.18"),
+            ),
+        }
+    }
+
+    /// Renders the title and a table identifying the document, to put above its content
+    #[must_use]
+    pub fn header(self, standard: Standard, project: &Project) -> String {
+        let (title, clause) = self.title(standard);
+        let mut output = vec![
+            format!("# {title}"),
+            String::new(),
+            "| | |".to_string(),
+            "| --- | --- |".to_string(),
+            format!("| Project | {} |", project.name.trim()),
+            format!("| Version | {} |", project.version),
+        ];
+        if let Some(clause) = clause {
+            output.push(format!("| Work product of | {clause} |"));
+        }
+        output.join("\n")
+    }
+}
+
+/// Lists what keeps the requirements from being complete: structural problems, failed and
+/// untested requirements and, if code was scanned, requirements no code refers to
+#[must_use]
+pub fn open_items(project: &Project, traces: &[Trace], tested: bool, scanned: bool) -> String {
+    let mut output = Vec::new();
+    let findings = lint::structure(project);
+    if !findings.is_empty() {
+        output.push("## Structural Problems".to_string());
+        output.push(String::new());
+        output.extend(findings.iter().map(|f| format!("- {f}")));
+        output.push(String::new());
+    }
+    let sections = [
+        ("Failed Verification", tested, Status::Failed),
+        ("Not Verified", tested, Status::Untested),
+    ];
+    for (title, enabled, status) in sections {
+        let open: Vec<_> = traces.iter().filter(|t| t.status == status).collect();
+        if enabled && !open.is_empty() {
+            output.push(format!("## {title}"));
+            output.push(String::new());
+            output.extend(open.iter().map(|t| format!("- _{}_ - {}", t.id, t.name)));
+            output.push(String::new());
+        }
+    }
+    let unimplemented: Vec<_> = traces.iter().filter(|t| t.code.is_empty()).collect();
+    if scanned && !unimplemented.is_empty() {
+        output.push("## Not Implemented".to_string());
+        output.push(String::new());
+        output.extend(
+            unimplemented
+                .iter()
+                .map(|t| format!("- _{}_ - {}", t.id, t.name)),
+        );
+        output.push(String::new());
+    }
+    if output.is_empty() {
+        output.push("There are no open items.".to_string());
+    }
+    output.join("\n").trim_end().to_string()
+}
+
+/// Renders the changes between consecutive baselines, and since the last one, newest first
+#[must_use]
+pub fn change_history(baselines: &[Baseline], project: &Project) -> String {
+    if baselines.is_empty() {
+        return "No baselines were created, so there is no recorded history.".to_string();
+    }
+    let mut sections = Vec::new();
+    for pair in baselines.windows(2) {
+        let (old, new) = (&pair[0], &pair[1]);
+        let changes = diff::changelog(
+            &old.project,
+            &new.project,
+            &diff::diff(&old.project, &new.project),
+        );
+        sections.push(format!(
+            "{changes}\n\nBaseline {} (hash `{}`)",
+            new.name, new.lock.hash
+        ));
+    }
+    let last = &baselines[baselines.len() - 1];
+    let current = diff::diff(&last.project, project);
+    if !current.is_empty() {
+        sections.push(format!(
+            "{}\n\nNot baselined yet",
+            diff::changelog(&last.project, project, &current)
+        ));
+    }
+    sections.reverse();
+    sections.push(format!(
+        "## Baseline {}\n\nThe first baseline, version {} (hash `{}`)",
+        baselines[0].name, baselines[0].project.version, baselines[0].lock.hash
+    ));
+    sections.join("\n\n")
+}
+
+/// Lists the files with their hashes, to prove a package is complete and unchanged
+#[must_use]
+pub fn manifest(project: &Project, files: &[(String, String)]) -> serde_json::Value {
+    let hashes: IndexMap<_, _> = files
+        .iter()
+        .map(|(name, content)| (name.clone(), lock::hash(content.as_bytes())))
+        .collect();
+    serde_json::json!({
+        "name": project.name.trim(),
+        "version": project.version.to_string(),
+        "files": hashes,
+    })
+}
+
+/// Writes a numeric field of a tar header as zero padded octal
+fn octal(field: &mut [u8], value: u64) {
+    let digits = format!("{value:0width$o}", width = field.len() - 1);
+    field[..digits.len()].copy_from_slice(digits.as_bytes());
+}
+
+/// Packs the files into an uncompressed tar archive
+///
+/// All entries get the modification time `mtime`, in seconds since the Unix epoch, and no
+/// owner, so packing the same files with the same time again gives the same archive.
+pub fn tar(files: &[(String, String)], mtime: u64) -> anyhow::Result<Vec<u8>> {
+    let mut archive = Vec::new();
+    for (name, content) in files {
+        if name.len() > 100 {
+            anyhow::bail!("File name {name} is too long for the archive");
+        }
+        let mut header = [0u8; 512];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        octal(&mut header[100..108], 0o644);
+        octal(&mut header[108..116], 0);
+        octal(&mut header[116..124], 0);
+        octal(&mut header[124..136], content.len() as u64);
+        octal(&mut header[136..148], mtime);
+        header[156] = b'0';
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+        // The checksum is calculated with its own field filled with spaces
+        header[148..156].fill(b' ');
+        let checksum: u64 = header.iter().map(|b| u64::from(*b)).sum();
+        octal(&mut header[148..155], checksum);
+        archive.extend_from_slice(&header);
+        archive.extend_from_slice(content.as_bytes());
+        archive.resize(archive.len().next_multiple_of(512), 0);
+    }
+    // The end of the archive is marked by two empty blocks
+    archive.resize(archive.len() + 1024, 0);
+    Ok(archive)
+}
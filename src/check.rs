@@ -0,0 +1,202 @@
+use std::collections::{HashMap, HashSet};
+
+use indexmap::map::{Keys, Values};
+use indexmap::IndexMap;
+use regex::Regex;
+
+use crate::{Project, Requirement, Topic};
+
+/// The results in a test output file, indexed so checking a requirement does not search the
+/// whole file
+struct TestResults<'a> {
+    /// The IDs ending the text before a `: failed`, a requirement failed if its ID is one
+    failed: HashSet<&'a str>,
+    /// The IDs ending the text before a `: passed`
+    passed: HashSet<&'a str>,
+    /// The lines starting with `<ID>: failed`, by ID
+    failures: HashMap<&'a str, Vec<&'a str>>,
+}
+
+impl<'a> TestResults<'a> {
+    /// Only looks at the endings as long as one of the `ids`, so long lines stay cheap
+    fn new(test_results: &'a str, ids: &HashSet<&str>, lengths: &[usize]) -> TestResults<'a> {
+        let endings = |before: &'a str| {
+            lengths
+                .iter()
+                .filter_map(move |length| before.len().checked_sub(*length))
+                .filter(move |start| before.is_char_boundary(*start))
+                .map(move |start| &before[start..])
+                .filter(|ending| ids.contains(ending))
+        };
+        let mut results = TestResults {
+            failed: HashSet::new(),
+            passed: HashSet::new(),
+            failures: HashMap::new(),
+        };
+        for line in test_results.lines() {
+            for (position, _) in line.match_indices(": failed") {
+                results.failed.extend(endings(&line[..position]));
+                results
+                    .failures
+                    .entry(&line[..position])
+                    .or_default()
+                    .push(line);
+            }
+            for (position, _) in line.match_indices(": passed") {
+                results.passed.extend(endings(&line[..position]));
+            }
+        }
+        results
+    }
+}
+
+fn check_requirements(
+    test_results: &TestResults,
+    output: &mut IndexMap<String, (bool, Vec<String>)>,
+    requirements: &IndexMap<String, Requirement>,
+    allowed_requirements: &[Regex],
+) {
+    for (id, _) in requirements {
+        if allowed_requirements.iter().any(|r| r.is_match(id)) {
+            if test_results.failed.contains(id.trim()) {
+                let errors = test_results
+                    .failures
+                    .get(id.trim())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|l| {
+                        l.split_once(":")
+                            .map(|(_, txt)| txt)
+                            .and_then(|txt| txt.split_once("-").map(|(_, err)| err.to_string()))
+                    });
+                tracing::debug!("{}: failed", id.trim());
+                // Keep the errors of earlier files, so the report does not depend on their order
+                let (passed, all_errors) = output
+                    .entry(id.trim().to_string())
+                    .or_insert((false, Vec::new()));
+                *passed = false;
+                all_errors.extend(errors);
+            } else if test_results.passed.contains(id.trim()) {
+                tracing::debug!("{}: passed", id.trim());
+                output
+                    .entry(id.trim().to_string())
+                    .or_insert((true, Vec::new()));
+            };
+        }
+    }
+}
+
+fn has_valid_requirements(
+    mut requirements: Keys<String, Requirement>,
+    allowed_requirements: &[Regex],
+) -> bool {
+    requirements.any(|id| allowed_requirements.iter().any(|r| r.is_match(id)))
+}
+
+fn has_valid_topics(mut topics: Values<String, Topic>, allowed_requirements: &[Regex]) -> bool {
+    topics.any(|topic| {
+        has_valid_requirements(topic.requirements.keys(), allowed_requirements)
+            || has_valid_topics(topic.subtopics.values(), allowed_requirements)
+    })
+}
+
+fn check_topics(
+    test_results: &[TestResults],
+    output: &mut Vec<String>,
+    topics: &IndexMap<String, Topic>,
+    allowed_requirements: &[Regex],
+    level: usize,
+) {
+    if !has_valid_topics(topics.values(), allowed_requirements) {
+        return;
+    }
+    for (id, topic) in topics {
+        if !has_valid_topics(topic.subtopics.values(), allowed_requirements)
+            && !has_valid_requirements(topic.requirements.keys(), allowed_requirements)
+        {
+            continue;
+        }
+        output.push(format!(
+            "{} _{}_ - {}",
+            "#".repeat(level),
+            id.trim(),
+            topic.name
+        ));
+
+        let mut test_status = IndexMap::new();
+        for test_result in test_results {
+            if !topic.requirements.is_empty() {
+                check_requirements(
+                    test_result,
+                    &mut test_status,
+                    &topic.requirements,
+                    allowed_requirements,
+                );
+            }
+        }
+
+        if !topic.requirements.is_empty() {
+            for (id, req) in &topic.requirements {
+                let (status, errors) = if let Some((status, errors)) = test_status.get(id.trim()) {
+                    if *status {
+                        (":white_check_mark:", errors.to_owned())
+                    } else {
+                        (":x:", errors.to_owned())
+                    }
+                } else {
+                    (":warning:", Vec::new())
+                };
+                output.push(format!("- _{}_ - {}: {status}", id.trim(), req.name));
+                for err in errors {
+                    output.push(format!("  - {}", err.trim()));
+                }
+            }
+
+            output.push(String::new());
+        }
+
+        if !topic.subtopics.is_empty() {
+            check_topics(
+                test_results,
+                output,
+                &topic.subtopics,
+                allowed_requirements,
+                level + 1,
+            );
+            output.push(String::new());
+        }
+    }
+}
+
+/// Renders the test status of the requirements matching `allowed_requirements` in the contents
+/// of test output files, with the topics at heading `level`
+///
+/// A requirement failed if any file reports it as failed, and passed if a file reports it as
+/// passed and none as failed.
+#[must_use]
+pub fn report(
+    project: &Project,
+    test_results: &[String],
+    allowed_requirements: &[Regex],
+    level: usize,
+) -> Vec<String> {
+    let mut owned = Vec::new();
+    project.visit_requirements(|r| owned.push(r.id.to_string()));
+    let ids: HashSet<_> = owned.iter().map(String::as_str).collect();
+    let mut lengths: Vec<_> = ids.iter().map(|id| id.len()).collect();
+    lengths.sort_unstable();
+    lengths.dedup();
+    let test_results: Vec<_> = test_results
+        .iter()
+        .map(|content| TestResults::new(content, &ids, &lengths))
+        .collect();
+    let mut output = Vec::new();
+    check_topics(
+        &test_results,
+        &mut output,
+        &project.topics,
+        allowed_requirements,
+        level,
+    );
+    output
+}
@@ -0,0 +1,146 @@
+use crate::{ConfigDefault, Project};
+
+fn describe(default: &ConfigDefault) -> Vec<String> {
+    let mut description = format!("{} ({}", default.name.trim(), default.typ.trim());
+    if let Some(unit) = &default.unit {
+        description.push_str(&format!(" in {}", unit.trim()));
+    }
+    description.push(')');
+    if let Some(hint) = &default.hint {
+        description.push_str(&format!(": {}", hint.trim()));
+    }
+    let mut lines = vec![description];
+    if let Some(values) = &default.valid_values {
+        let values: Vec<_> = values.iter().map(|v| v.trim()).collect();
+        lines.push(format!("Valid values: {}", values.join(", ")));
+    }
+    if default.default_value.is_none() {
+        lines.push("Required, there is no default".to_string());
+    }
+    lines
+}
+
+fn quote(value: &str) -> String {
+    if value.contains(|c: char| c.is_whitespace() || "#\"'$\\`".contains(c)) {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Renders a `.env.example` with every config default, leaving required values empty
+#[must_use]
+pub fn env_example(project: &Project) -> String {
+    let mut output = vec![
+        format!(
+            "# Configuration of {} {}",
+            project.name.trim(),
+            project.version
+        ),
+        "# Generated by `req codegen env`".to_string(),
+    ];
+    for default in &project.config_defaults {
+        output.push(String::new());
+        output.extend(describe(default).iter().map(|l| format!("# {l}")));
+        let value = default.default_value.as_deref().unwrap_or_default();
+        output.push(format!("{}={}", default.env_var(), quote(value.trim())));
+    }
+    output.join("\n") + "\n"
+}
+
+/// Renders a docker-compose `environment:` section passing every config default through
+///
+/// Values with a default fall back to it, required values make compose fail when they are unset.
+#[must_use]
+pub fn compose_environment(project: &Project) -> String {
+    let mut output = vec!["environment:".to_string()];
+    for default in &project.config_defaults {
+        let var = default.env_var();
+        let value = match &default.default_value {
+            Some(value) => format!("${{{var}:-{}}}", value.trim()),
+            None => format!("${{{var}:?{var} is required}}"),
+        };
+        output.extend(describe(default).iter().map(|l| format!("  # {l}")));
+        output.push(format!(
+            "  {var}: \"{}\"",
+            value.replace('\\', "\\\\").replace('"', "\\\"")
+        ));
+    }
+    output.join("\n") + "\n"
+}
+
+/// Maps the free-form type of a config default to a JSON Schema type
+fn json_type(default: &ConfigDefault) -> &'static str {
+    match default.typ.trim().to_lowercase().as_str() {
+        // A duration with a unit is a plain number of that unit
+        "duration" if default.unit.is_some() => "number",
+        "bool" | "boolean" => "boolean",
+        "int" | "integer" | "long" | "u8" | "u16" | "u32" | "u64" | "usize" | "i8" | "i16"
+        | "i32" | "i64" | "isize" => "integer",
+        "float" | "double" | "number" | "decimal" | "f32" | "f64" => "number",
+        "list" | "array" | "vec" => "array",
+        _ => "string",
+    }
+}
+
+/// Converts a textual value into a JSON value of `json_type`, keeping it a string if it does not parse
+fn json_value(value: &str, json_type: &str) -> serde_json::Value {
+    let value = value.trim();
+    let parsed = match json_type {
+        "boolean" => value.parse::<bool>().ok().map(serde_json::Value::from),
+        "integer" => value.parse::<i64>().ok().map(serde_json::Value::from),
+        "number" => value
+            .parse::<i64>()
+            .map(serde_json::Value::from)
+            .or_else(|_| value.parse::<f64>().map(serde_json::Value::from))
+            .ok(),
+        "array" => Some(
+            value
+                .split(',')
+                .map(|v| serde_json::Value::from(v.trim()))
+                .collect(),
+        ),
+        _ => None,
+    };
+    parsed.unwrap_or_else(|| value.into())
+}
+
+/// Creates a JSON Schema for the configuration file of the application described by the config defaults
+#[must_use]
+pub fn config_schema(project: &Project) -> serde_json::Value {
+    let mut properties = serde_json::Map::new();
+    let mut required = Vec::new();
+    for default in &project.config_defaults {
+        let typ = json_type(default);
+        let mut property = serde_json::Map::new();
+        property.insert("type".to_string(), typ.into());
+        let mut description = Vec::new();
+        if let Some(hint) = &default.hint {
+            description.push(hint.trim().to_string());
+        }
+        if let Some(unit) = &default.unit {
+            description.push(format!("Unit: {}", unit.trim()));
+        }
+        if !description.is_empty() {
+            property.insert("description".to_string(), description.join(". ").into());
+        }
+        if let Some(values) = &default.valid_values {
+            let values: Vec<_> = values.iter().map(|v| json_value(v, typ)).collect();
+            property.insert("enum".to_string(), values.into());
+        }
+        match &default.default_value {
+            Some(value) => {
+                property.insert("default".to_string(), json_value(value, typ));
+            }
+            None => required.push(default.name.trim().to_string()),
+        }
+        properties.insert(default.name.trim().to_string(), property.into());
+    }
+    serde_json::json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": format!("{} configuration", project.name.trim()),
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    })
+}
@@ -0,0 +1,90 @@
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::ids::IdScheme;
+use crate::profile::Profile;
+
+/// The file names a project configuration is looked up under, in order of precedence
+pub const FILE_NAMES: [&str; 4] = [
+    ".easyreq.toml",
+    "easyreq.toml",
+    ".easyreq.yml",
+    "easyreq.yml",
+];
+
+/// Project wide defaults for command line arguments
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The requirements file used when none is given
+    pub requirements: Option<PathBuf>,
+    /// Regexes selecting the requirements that `check` and `release` report on
+    pub allowed_requirements: Vec<String>,
+    /// Test output files used by `check` when none are given
+    pub test_results: Vec<PathBuf>,
+    /// An HTML template with a `{{content}}` placeholder replacing the built-in one
+    pub template: Option<PathBuf>,
+    /// The keywords the `missing-keyword` and `compound` lint rules look for
+    pub keywords: Vec<String>,
+    /// The directory `release` writes to when none is given
+    pub out_dir: Option<PathBuf>,
+    /// The workspace file used by `--workspace` when none is given
+    pub workspace: Option<PathBuf>,
+    /// How `add` allocates IDs for requirements added with the ID `auto`
+    pub id_scheme: Option<IdScheme>,
+    /// Rendering options for different audiences by name, selected with `--profile`
+    pub profiles: IndexMap<String, Profile>,
+}
+
+impl Config {
+    /// Reads a configuration file and makes its paths relative to the current directory
+    pub fn read(path: &Path) -> anyhow::Result<Config> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", path.display()))?;
+        let parsed = if path.extension().is_some_and(|e| e == "toml") {
+            toml::from_str(&content).map_err(anyhow::Error::from)
+        } else {
+            serde_yaml::from_str(&content).map_err(anyhow::Error::from)
+        };
+        let mut config: Config =
+            parsed.map_err(|e| anyhow::anyhow!("Invalid config {}: {e}", path.display()))?;
+        let base = path.parent().unwrap_or(Path::new(""));
+        for path in [
+            &mut config.requirements,
+            &mut config.template,
+            &mut config.out_dir,
+            &mut config.workspace,
+        ]
+        .into_iter()
+        .chain(config.profiles.values_mut().map(|p| &mut p.template))
+        .flatten()
+        {
+            *path = base.join(&*path);
+        }
+        for path in &mut config.test_results {
+            *path = base.join(&*path);
+        }
+        Ok(config)
+    }
+}
+
+/// Finds the configuration file in `start` or the closest parent directory containing one
+#[must_use]
+pub fn find(start: &Path) -> Option<PathBuf> {
+    start.ancestors().find_map(|dir| {
+        FILE_NAMES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    })
+}
+
+/// Reads the configuration that applies to `start`, or the default one if there is none
+pub fn discover(start: &Path) -> anyhow::Result<Config> {
+    match find(start) {
+        Some(path) => Config::read(&path),
+        None => Ok(Config::default()),
+    }
+}
@@ -0,0 +1,245 @@
+use std::path::Path;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::lock;
+
+/// The key of the index page in the page IDs
+pub const INDEX: &str = "_index";
+
+/// A rendered page to publish
+#[derive(Debug, Clone)]
+pub struct Page {
+    /// Identifies the page across runs, the topic ID or [`INDEX`]
+    pub key: String,
+    pub title: String,
+    /// The content in the Confluence storage format
+    pub body: String,
+    /// The key of the parent page, `None` for the page the publication goes below
+    pub parent: Option<String>,
+}
+
+impl Page {
+    fn hash(&self) -> String {
+        lock::hash(format!("{}\n{}", self.title, self.body).as_bytes())
+    }
+}
+
+/// A page created by an earlier publication
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishedPage {
+    pub id: String,
+    /// The hash of title and body as last published
+    pub hash: String,
+}
+
+/// The Confluence page IDs of the published pages by page key
+pub type PageIds = IndexMap<String, PublishedPage>;
+
+/// Reads the page IDs, none if the file does not exist yet
+pub fn read_page_ids(path: &Path) -> anyhow::Result<PageIds> {
+    if !path.exists() {
+        return Ok(PageIds::new());
+    }
+    serde_json::from_str(&std::fs::read_to_string(path)?)
+        .map_err(|e| anyhow::anyhow!("Invalid page IDs in {}: {e}", path.display()))
+}
+
+pub fn write_page_ids(path: &Path, ids: &PageIds) -> anyhow::Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(ids)? + "\n")?;
+    Ok(())
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut output = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | u32::from(*b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+#[derive(Deserialize)]
+struct Version {
+    number: u64,
+}
+
+#[derive(Deserialize)]
+struct Content {
+    id: String,
+    version: Version,
+}
+
+/// A Confluence space, accessed through the REST API
+pub struct Confluence {
+    agent: ureq::Agent,
+    base_url: String,
+    space: String,
+    authorization: String,
+}
+
+impl Confluence {
+    /// Connects to the space with key `space` of the site at `base_url`, e.g. `https://example.atlassian.net/wiki`
+    ///
+    /// With a user, the token is an API token of Confluence Cloud, without it a personal access
+    /// token of Confluence Data Center.
+    #[must_use]
+    pub fn new(base_url: &str, space: &str, user: Option<&str>, token: &str) -> Confluence {
+        let authorization = match user {
+            Some(user) => format!("Basic {}", base64(format!("{user}:{token}").as_bytes())),
+            None => format!("Bearer {token}"),
+        };
+        Confluence {
+            agent: crate::sync::agent(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            space: space.to_string(),
+            authorization,
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/rest/api/content{path}", self.base_url)
+    }
+
+    fn content(&self, page: &Page, parent: Option<&str>) -> serde_json::Value {
+        let mut content = serde_json::json!({
+            "type": "page",
+            "title": page.title,
+            "space": { "key": self.space },
+            "body": { "storage": { "value": page.body, "representation": "storage" } },
+        });
+        if let Some(parent) = parent {
+            content["ancestors"] = serde_json::json!([{ "id": parent }]);
+        }
+        content
+    }
+
+    fn send(
+        &self,
+        request: ureq::RequestBuilder<ureq::typestate::WithBody>,
+        content: &serde_json::Value,
+    ) -> anyhow::Result<Content> {
+        let response = request
+            .header("Authorization", &self.authorization)
+            .content_type("application/json")
+            .send(content.to_string())?;
+        Ok(serde_json::from_str(
+            &response.into_body().read_to_string()?,
+        )?)
+    }
+
+    /// The current version of a page, `None` if it was deleted
+    fn version(&self, id: &str) -> anyhow::Result<Option<u64>> {
+        let response = self
+            .agent
+            .get(self.url(&format!("/{id}")))
+            .query("expand", "version")
+            .header("Authorization", &self.authorization)
+            .config()
+            .http_status_as_error(false)
+            .build()
+            .call()
+            .map_err(|e| anyhow::anyhow!("Failed to get page {id}: {e}"))?;
+        match response.status().as_u16() {
+            404 => Ok(None),
+            200 => {
+                let content: Content =
+                    serde_json::from_str(&response.into_body().read_to_string()?)?;
+                Ok(Some(content.version.number))
+            }
+            status => anyhow::bail!("Failed to get page {id}: status {status}"),
+        }
+    }
+
+    /// Creates a page and returns its ID
+    fn create(&self, page: &Page, parent: Option<&str>) -> anyhow::Result<String> {
+        let created = self
+            .send(self.agent.post(self.url("")), &self.content(page, parent))
+            .map_err(|e| anyhow::anyhow!("Failed to create page '{}': {e}", page.title))?;
+        Ok(created.id)
+    }
+
+    fn update(
+        &self,
+        id: &str,
+        version: u64,
+        page: &Page,
+        parent: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let mut content = self.content(page, parent);
+        content["id"] = id.into();
+        content["version"] = serde_json::json!({ "number": version + 1 });
+        self.send(self.agent.put(self.url(&format!("/{id}"))), &content)
+            .map_err(|e| anyhow::anyhow!("Failed to update page '{}': {e}", page.title))?;
+        Ok(())
+    }
+}
+
+/// Creates or updates the pages below the page with ID `parent` and describes what was done
+///
+/// Pages are found again by their key in `ids`, so they can be renamed or moved in Confluence,
+/// and pages that did not change since the last publication are left alone. Pages have to come
+/// after their parent.
+pub fn publish(
+    confluence: &Confluence,
+    pages: &[Page],
+    parent: Option<&str>,
+    ids: &mut PageIds,
+    dry_run: bool,
+) -> anyhow::Result<Vec<String>> {
+    let mut output = Vec::new();
+    for page in pages {
+        let parent_id = match &page.parent {
+            Some(key) => ids.get(key).map(|p| p.id.clone()),
+            None => parent.map(str::to_string),
+        };
+        let hash = page.hash();
+        let id = match ids.get(&page.key) {
+            Some(published) if published.hash == hash => {
+                output.push(format!("{}: unchanged", page.title));
+                continue;
+            }
+            Some(published) if dry_run => {
+                output.push(format!("{}: update page {}", page.title, published.id));
+                continue;
+            }
+            Some(published) => match confluence.version(&published.id)? {
+                Some(version) => {
+                    confluence.update(&published.id, version, page, parent_id.as_deref())?;
+                    output.push(format!("{}: updated page {}", page.title, published.id));
+                    published.id.clone()
+                }
+                None => {
+                    let id = confluence.create(page, parent_id.as_deref())?;
+                    output.push(format!(
+                        "{}: page was deleted, created page {id}",
+                        page.title
+                    ));
+                    id
+                }
+            },
+            None if dry_run => {
+                output.push(format!("{}: create page", page.title));
+                continue;
+            }
+            None => {
+                let id = confluence.create(page, parent_id.as_deref())?;
+                output.push(format!("{}: created page {id}", page.title));
+                id
+            }
+        };
+        ids.insert(page.key.clone(), PublishedPage { id, hash });
+    }
+    Ok(output)
+}
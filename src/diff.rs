@@ -0,0 +1,123 @@
+use serde::Serialize;
+
+use crate::merge::same_requirement;
+use crate::{Project, Requirement};
+
+/// A requirement that only exists in one of the compared projects
+#[derive(Debug, Clone, Serialize)]
+pub struct Entry {
+    pub id: String,
+    pub topic_path: Vec<String>,
+    pub requirement: Requirement,
+}
+
+/// A requirement whose content or topic changed between the compared projects
+#[derive(Debug, Clone, Serialize)]
+pub struct Modification {
+    pub id: String,
+    pub old_topic_path: Vec<String>,
+    pub new_topic_path: Vec<String>,
+    pub old: Requirement,
+    pub new: Requirement,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Diff {
+    pub added: Vec<Entry>,
+    pub removed: Vec<Entry>,
+    pub modified: Vec<Modification>,
+}
+
+impl Diff {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+fn entries(project: &Project) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    project.visit_requirements(|r| {
+        entries.push(Entry {
+            id: r.id.to_string(),
+            topic_path: r.topic_path.iter().map(|p| p.to_string()).collect(),
+            requirement: r.requirement.clone(),
+        });
+    });
+    entries
+}
+
+/// Compares the requirements of two projects by ID
+#[must_use]
+pub fn diff(old: &Project, new: &Project) -> Diff {
+    let old = entries(old);
+    let new = entries(new);
+    let mut diff = Diff::default();
+    for entry in &new {
+        match old.iter().find(|o| o.id == entry.id) {
+            None => diff.added.push(entry.clone()),
+            Some(o)
+                if o.topic_path != entry.topic_path
+                    || !same_requirement(&o.requirement, &entry.requirement) =>
+            {
+                diff.modified.push(Modification {
+                    id: entry.id.clone(),
+                    old_topic_path: o.topic_path.clone(),
+                    new_topic_path: entry.topic_path.clone(),
+                    old: o.requirement.clone(),
+                    new: entry.requirement.clone(),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    diff.removed = old
+        .into_iter()
+        .filter(|o| !new.iter().any(|n| n.id == o.id))
+        .collect();
+    diff
+}
+
+fn entry_line(entry: &Entry) -> String {
+    format!("- _{}_ - {}", entry.id, entry.requirement.name.trim())
+}
+
+/// Renders the diff as a Markdown change log section
+#[must_use]
+pub fn changelog(old: &Project, new: &Project, diff: &Diff) -> String {
+    let mut output = vec![if old.version == new.version {
+        format!("## Changes in {}", new.version)
+    } else {
+        format!("## Changes from {} to {}", old.version, new.version)
+    }];
+    if diff.is_empty() {
+        output.push(String::new());
+        output.push("No requirements changed.".to_string());
+    }
+    if !diff.added.is_empty() {
+        output.push(String::new());
+        output.push("### Added".to_string());
+        output.extend(diff.added.iter().map(entry_line));
+    }
+    if !diff.modified.is_empty() {
+        output.push(String::new());
+        output.push("### Modified".to_string());
+        for modification in &diff.modified {
+            let mut line = format!("- _{}_ - {}", modification.id, modification.new.name.trim());
+            if modification.old_topic_path != modification.new_topic_path {
+                line.push_str(&format!(
+                    " (moved from {} to {})",
+                    modification.old_topic_path.join("/"),
+                    modification.new_topic_path.join("/")
+                ));
+            }
+            output.push(line);
+        }
+    }
+    if !diff.removed.is_empty() {
+        output.push(String::new());
+        output.push("### Removed".to_string());
+        output.extend(diff.removed.iter().map(entry_line));
+    }
+    output.join("\n")
+}
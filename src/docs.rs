@@ -0,0 +1,57 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::trace::{self, Reference};
+use crate::Project;
+
+/// The extensions of Markdown and AsciiDoc files
+pub const EXTENSIONS: [&str; 5] = ["md", "markdown", "adoc", "asciidoc", "asc"];
+
+/// The places in the documentation mentioning a requirement
+#[derive(Debug, Clone, Serialize)]
+pub struct Coverage {
+    pub id: String,
+    pub name: String,
+    pub mentions: Vec<Reference>,
+}
+
+fn is_documentation(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|e| EXTENSIONS.contains(&e.to_string_lossy().to_lowercase().as_str()))
+}
+
+/// Lists the lines of the Markdown and AsciiDoc files in `paths` mentioning each requirement,
+/// in document order
+pub fn coverage(project: &Project, paths: &[PathBuf]) -> anyhow::Result<Vec<Coverage>> {
+    let mut documents = Vec::new();
+    for file in trace::files(paths)?
+        .into_iter()
+        .filter(|f| is_documentation(f))
+    {
+        let content = std::fs::read_to_string(&file)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", file.display()))?;
+        documents.push((file, content));
+    }
+    tracing::info!("Scanning {} documentation files", documents.len());
+    let mut coverage = Vec::new();
+    project.visit_requirements(|r| {
+        let mut mentions = Vec::new();
+        for (file, content) in &documents {
+            for (index, line) in content.lines().enumerate() {
+                if trace::mentions(line, r.id).next().is_some() {
+                    mentions.push(Reference {
+                        file: file.clone(),
+                        line: index + 1,
+                    });
+                }
+            }
+        }
+        coverage.push(Coverage {
+            id: r.id.to_string(),
+            name: r.requirement.name.trim().to_string(),
+            mentions,
+        });
+    });
+    Ok(coverage)
+}
@@ -0,0 +1,316 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::import::{self, empty_project};
+use crate::{Project, Requirement, Topic};
+
+/// The file marking a directory as a Doorstop document
+pub const CONFIG_FILE: &str = ".doorstop.yml";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Settings {
+    digits: usize,
+    prefix: String,
+    #[serde(default)]
+    sep: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DocumentConfig {
+    settings: Settings,
+}
+
+fn yes() -> bool {
+    true
+}
+
+/// A Doorstop item file, with the fields of the requirements file Doorstop has no place for
+#[derive(Debug, Serialize, Deserialize)]
+struct Item {
+    #[serde(default = "yes")]
+    active: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    additional_info: Vec<String>,
+    #[serde(default)]
+    derived: bool,
+    #[serde(default)]
+    header: String,
+    /// The ID in the requirements file, which does not follow the UID scheme of Doorstop
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    level: serde_yaml::Value,
+    #[serde(default)]
+    links: Vec<serde_yaml::Value>,
+    #[serde(default = "yes")]
+    normative: bool,
+    #[serde(default, rename = "ref")]
+    reference: String,
+    #[serde(default)]
+    reviewed: Option<serde_yaml::Value>,
+    #[serde(default)]
+    text: String,
+}
+
+fn level_value(level: &[usize], heading: bool) -> serde_yaml::Value {
+    let mut parts: Vec<_> = level.iter().map(usize::to_string).collect();
+    if heading {
+        parts.push("0".to_string());
+    }
+    serde_yaml::Value::String(parts.join("."))
+}
+
+fn requirement_item(id: &str, requirement: &Requirement, level: &[usize], active: bool) -> Item {
+    Item {
+        active,
+        additional_info: requirement
+            .additional_info
+            .iter()
+            .map(|i| i.trim().to_string())
+            .collect(),
+        derived: false,
+        header: requirement.name.trim().to_string(),
+        id: Some(id.trim().to_string()),
+        level: level_value(level, false),
+        links: requirement
+            .links
+            .iter()
+            .map(|l| serde_yaml::Value::String(l.trim().to_string()))
+            .collect(),
+        normative: true,
+        reference: String::new(),
+        reviewed: None,
+        text: requirement.description.trim().to_string() + "\n",
+    }
+}
+
+fn items(
+    topics: &IndexMap<String, Topic>,
+    parent_level: &[usize],
+    first: usize,
+    items: &mut Vec<Item>,
+) {
+    for (index, (id, topic)) in topics.iter().enumerate() {
+        let mut level = parent_level.to_vec();
+        level.push(first + index);
+        items.push(Item {
+            active: true,
+            additional_info: Vec::new(),
+            derived: false,
+            header: topic.name.trim().to_string(),
+            id: Some(id.trim().to_string()),
+            level: level_value(&level, true),
+            links: Vec::new(),
+            normative: false,
+            reference: String::new(),
+            reviewed: None,
+            text: String::new(),
+        });
+        for (index, (id, requirement)) in topic.requirements.iter().enumerate() {
+            let mut requirement_level = level.clone();
+            requirement_level.push(index + 1);
+            items.push(requirement_item(id, requirement, &requirement_level, true));
+        }
+        self::items(
+            &topic.subtopics,
+            &level,
+            topic.requirements.len() + 1,
+            items,
+        );
+    }
+}
+
+/// Writes the project as a Doorstop document into `dir`, one item file per topic and requirement
+///
+/// Items are numbered `<prefix><sep><number>` in document order, topics become headings, archived
+/// requirements inactive items and the original IDs are kept in an `id` attribute so the document
+/// can be imported again.
+pub fn write(
+    project: &Project,
+    dir: &Path,
+    prefix: &str,
+    sep: &str,
+    digits: usize,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let mut all = Vec::new();
+    items(&project.topics, &[], 1, &mut all);
+    let archive_level = project.topics.len() + 1;
+    for (index, (id, requirement)) in project.archive.iter().enumerate() {
+        all.push(requirement_item(
+            id,
+            requirement,
+            &[archive_level, index + 1],
+            false,
+        ));
+    }
+    let uid = |number: usize| format!("{prefix}{sep}{number:0digits$}");
+    let uids: HashMap<String, String> = all
+        .iter()
+        .enumerate()
+        .filter_map(|(i, item)| Some((item.id.clone()?, uid(i + 1))))
+        .collect();
+    std::fs::create_dir_all(dir)?;
+    let config = DocumentConfig {
+        settings: Settings {
+            digits,
+            prefix: prefix.to_string(),
+            sep: sep.to_string(),
+        },
+    };
+    let config_path = dir.join(CONFIG_FILE);
+    std::fs::write(&config_path, serde_yaml::to_string(&config)?)?;
+    let mut files = vec![config_path];
+    for (index, mut item) in all.into_iter().enumerate() {
+        for link in &mut item.links {
+            if let Some(uid) = link.as_str().and_then(|l| uids.get(l)) {
+                *link = serde_yaml::Value::String(uid.clone());
+            }
+        }
+        let path = dir.join(format!("{}.yml", uid(index + 1)));
+        std::fs::write(&path, serde_yaml::to_string(&item)?)?;
+        files.push(path);
+    }
+    Ok(files)
+}
+
+/// Parses a level like `1.2.0` into its parts and whether it is a heading
+fn parse_level(level: &serde_yaml::Value) -> Option<(Vec<usize>, bool)> {
+    let level = match level {
+        serde_yaml::Value::String(level) => level.clone(),
+        serde_yaml::Value::Number(level) => level.to_string(),
+        _ => return None,
+    };
+    let mut parts: Vec<usize> = level
+        .split('.')
+        .map(|p| p.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    let heading = parts.len() > 1 && parts.last() == Some(&0);
+    if heading {
+        parts.pop();
+    }
+    Some((parts, heading))
+}
+
+fn link_uid(link: &serde_yaml::Value) -> Option<String> {
+    match link {
+        serde_yaml::Value::String(uid) => Some(uid.clone()),
+        // Links with a review stamp are written as `UID: stamp`
+        serde_yaml::Value::Mapping(map) => map.keys().next()?.as_str().map(str::to_string),
+        _ => None,
+    }
+}
+
+/// Reads a Doorstop document directory into a project
+///
+/// Headings become topics nested by their level, normative items become requirements of the
+/// heading above them and inactive items are archived.
+pub fn read(dir: &Path) -> anyhow::Result<Project> {
+    let config_path = dir.join(CONFIG_FILE);
+    let config: DocumentConfig = serde_yaml::from_str(
+        &std::fs::read_to_string(&config_path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", config_path.display()))?,
+    )?;
+    let mut items = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|e| e != "yml") || path == config_path {
+            continue;
+        }
+        let Some(uid) = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        let item: Item = serde_yaml::from_str(&std::fs::read_to_string(&path)?)
+            .map_err(|e| anyhow::anyhow!("Invalid item {}: {e}", path.display()))?;
+        let Some(level) = parse_level(&item.level) else {
+            anyhow::bail!("Invalid level of item {uid}");
+        };
+        items.push((level, uid, item));
+    }
+    // Items with the same level are ordered by UID, not by the order the directory lists them in
+    items.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+    let ids: HashMap<String, String> = items
+        .iter()
+        .map(|(_, uid, item)| (uid.clone(), item.id.clone().unwrap_or_else(|| uid.clone())))
+        .collect();
+
+    let mut project = empty_project(&config.settings.prefix);
+    let mut topic_paths: HashMap<Vec<usize>, Vec<String>> = HashMap::new();
+    for ((level, heading), uid, item) in items {
+        let id = ids[&uid].clone();
+        let first_line = item.text.lines().next().unwrap_or_default().trim();
+        if heading {
+            let mut path = topic_paths
+                .get(&level[..level.len() - 1])
+                .cloned()
+                .unwrap_or_default();
+            let topics = match project.topic_mut(&path) {
+                Some(parent) => &mut parent.subtopics,
+                None => &mut project.topics,
+            };
+            let name = if item.header.trim().is_empty() {
+                first_line
+            } else {
+                item.header.trim()
+            };
+            topics.insert(
+                id.clone(),
+                Topic {
+                    name: name.to_string(),
+                    requirements: IndexMap::new(),
+                    subtopics: IndexMap::new(),
+                    classification: None,
+                },
+            );
+            path.push(id);
+            topic_paths.insert(level, path);
+            continue;
+        }
+        if !item.normative && item.active {
+            continue;
+        }
+        let description = item.text.trim().to_string();
+        let mut additional_info = item.additional_info;
+        if !item.reference.trim().is_empty() {
+            additional_info.push(format!("Reference: {}", item.reference.trim()));
+        }
+        let requirement = Requirement {
+            name: if item.header.trim().is_empty() {
+                import::name_from_text(&description)
+            } else {
+                item.header.trim().to_string()
+            },
+            description,
+            additional_info,
+            links: item
+                .links
+                .iter()
+                .filter_map(link_uid)
+                .map(|uid| ids.get(&uid).cloned().unwrap_or(uid))
+                .collect(),
+            classification: None,
+            priority: None,
+            state: None,
+        };
+        if !item.active {
+            project.archive.insert(id, requirement);
+            continue;
+        }
+        let path = topic_paths
+            .get(&level[..level.len() - 1])
+            .cloned()
+            .unwrap_or_default();
+        let topic = match project.topic_mut(&path) {
+            Some(topic) => topic,
+            None => import::topic_by_names(&mut project.topics, &["General"])
+                .expect("Path is not empty"),
+        };
+        topic.requirements.insert(id, requirement);
+    }
+    Ok(project)
+}
@@ -0,0 +1,91 @@
+use std::path::{Path, PathBuf};
+
+use crate::format::{self, Format};
+use crate::{import, Fragment, Project};
+
+/// A file of a project opened for editing
+pub struct Source {
+    pub path: PathBuf,
+    pub format: Format,
+    /// The content of the file, an included fragment has no name, version or description
+    pub project: Project,
+    root: bool,
+    original: Project,
+}
+
+impl Source {
+    /// Whether this is the file of the project itself, rather than one it includes
+    #[must_use]
+    pub fn is_root(&self) -> bool {
+        self.root
+    }
+
+    /// Whether the content was edited since the file was read
+    #[must_use]
+    pub fn is_changed(&self) -> bool {
+        self.project != self.original
+    }
+}
+
+fn fragment_project(fragment: Fragment) -> Project {
+    Project {
+        topics: fragment.topics,
+        definitions: fragment.definitions,
+        config_defaults: fragment.config_defaults,
+        includes: fragment.includes,
+        archive: fragment.archive,
+        ..import::empty_project("")
+    }
+}
+
+/// The file of a project and all files it includes, each read on its own so edits are written
+/// back to the file that defines what they change
+pub struct Sources {
+    /// The project with all includes merged, as it was read
+    pub merged: Project,
+    /// The files in include order, the project file first
+    pub files: Vec<Source>,
+}
+
+impl Sources {
+    /// Reads the project at `path` and every file it includes
+    pub fn open(path: &Path) -> anyhow::Result<Sources> {
+        let merged = format::load(path)?;
+        let mut files = Vec::new();
+        for (index, path) in format::source_files(path)?.into_iter().enumerate() {
+            let (project, format) = if index == 0 {
+                format::parse_file_with_format::<Project>(&path)?
+            } else {
+                let (fragment, format) = format::parse_file_with_format::<Fragment>(&path)?;
+                (fragment_project(fragment), format)
+            };
+            files.push(Source {
+                path,
+                format,
+                original: project.clone(),
+                project,
+                root: index == 0,
+            });
+        }
+        Ok(Sources { merged, files })
+    }
+
+    /// The first file defining the topic at `path`
+    pub fn topic_file<S: AsRef<str>>(&mut self, path: &[S]) -> Option<&mut Source> {
+        self.files
+            .iter_mut()
+            .find(|f| f.project.topic(path).is_some())
+    }
+
+    /// The first file defining the requirement `id`
+    pub fn requirement_file(&mut self, id: &str) -> Option<&mut Source> {
+        self.files
+            .iter_mut()
+            .find(|f| f.project.find_requirement(id).is_some())
+    }
+
+    /// The files that were edited since they were read
+    pub fn changed(&self) -> impl Iterator<Item = &Source> {
+        self.files.iter().filter(|f| f.is_changed())
+    }
+}
@@ -0,0 +1,272 @@
+use serde::Serialize;
+
+use crate::import::ColumnMap;
+use crate::Project;
+
+/// Writes one Jira story per requirement as CSV for Jira's CSV importer
+///
+/// Fields without a column in `columns` are left out, additional information
+/// is appended to the description as a Jira markup list.
+pub fn jira_csv(
+    project: &Project,
+    columns: &ColumnMap,
+    topic_separator: &str,
+) -> anyhow::Result<String> {
+    let optional = [
+        columns.topic.as_ref(),
+        columns.additional_info.as_ref(),
+        columns.links.as_ref(),
+    ];
+    let mut header = vec![
+        columns.id.as_str(),
+        columns.name.as_str(),
+        columns.description.as_str(),
+    ];
+    header.extend(optional.iter().flatten().map(|c| c.as_str()));
+    header.extend(["Issue Type", "Labels"]);
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(&header)?;
+
+    let mut rows = Vec::new();
+    project.visit_requirements(|r| {
+        let mut description = r.requirement.description.trim().to_string();
+        if columns.additional_info.is_none() && !r.requirement.additional_info.is_empty() {
+            description.push('\n');
+            for info in &r.requirement.additional_info {
+                description.push_str(&format!("\n* {}", info.trim()));
+            }
+        }
+        let topic_names: Vec<_> = (1..=r.topic_path.len())
+            .filter_map(|depth| project.topic(&r.topic_path[..depth]))
+            .map(|t| t.name.trim())
+            .collect();
+        let mut row = vec![
+            r.id.to_string(),
+            r.requirement.name.trim().to_string(),
+            description,
+        ];
+        if columns.topic.is_some() {
+            row.push(topic_names.join(topic_separator));
+        }
+        if columns.additional_info.is_some() {
+            let info: Vec<_> = r
+                .requirement
+                .additional_info
+                .iter()
+                .map(|i| i.trim())
+                .collect();
+            row.push(info.join("\n"));
+        }
+        if columns.links.is_some() {
+            let links: Vec<_> = r.requirement.links.iter().map(|l| l.trim()).collect();
+            row.push(links.join(", "));
+        }
+        row.extend(["Story".to_string(), "requirement".to_string()]);
+        rows.push(row);
+    });
+    for row in rows {
+        writer.write_record(&row)?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// The characters underlining RST section titles, by depth
+const RST_UNDERLINES: [char; 5] = ['=', '-', '~', '^', '"'];
+
+fn rst_title(title: &str, depth: usize) -> Vec<String> {
+    let underline = RST_UNDERLINES[depth.min(RST_UNDERLINES.len() - 1)];
+    vec![
+        title.to_string(),
+        underline.to_string().repeat(title.chars().count().max(1)),
+        String::new(),
+    ]
+}
+
+fn indent_block(text: &str, indent: &str) -> Vec<String> {
+    let is_item = |line: &str| line.trim_start().starts_with(['-', '*']);
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.trim().lines() {
+        if line.trim().is_empty() {
+            lines.push(String::new());
+            continue;
+        }
+        // RST only starts a list after a blank line
+        if is_item(line) && lines.last().is_some_and(|l| !l.is_empty() && !is_item(l)) {
+            lines.push(String::new());
+        }
+        lines.push(format!("{indent}{}", line.trim_end()));
+    }
+    lines
+}
+
+fn sphinx_topics(
+    topics: &indexmap::IndexMap<String, crate::Topic>,
+    directive: &str,
+    depth: usize,
+    output: &mut Vec<String>,
+) {
+    for topic in topics.values() {
+        output.extend(rst_title(topic.name.trim(), depth));
+        for (id, requirement) in &topic.requirements {
+            output.push(format!(".. {directive}:: {}", requirement.name.trim()));
+            output.push(format!("   :id: {}", id.trim()));
+            if !requirement.links.is_empty() {
+                let links: Vec<_> = requirement.links.iter().map(|l| l.trim()).collect();
+                output.push(format!("   :links: {}", links.join(", ")));
+            }
+            output.push(String::new());
+            output.extend(indent_block(&requirement.description, "   "));
+            if !requirement.additional_info.is_empty() {
+                output.push(String::new());
+                for info in &requirement.additional_info {
+                    output.push(format!("   - {}", info.trim()));
+                }
+            }
+            output.push(String::new());
+        }
+        sphinx_topics(&topic.subtopics, directive, depth + 1, output);
+    }
+}
+
+/// Renders the project as reStructuredText with a Sphinx-needs directive per requirement
+///
+/// Topics become sections, links the `:links:` option and definitions a glossary.
+#[must_use]
+pub fn sphinx_needs(project: &Project, directive: &str) -> String {
+    let title = project.name.trim();
+    let mut output = vec![
+        "#".repeat(title.chars().count().max(1)),
+        title.to_string(),
+        "#".repeat(title.chars().count().max(1)),
+        String::new(),
+        format!("Version {}", project.version),
+        String::new(),
+    ];
+    if !project.description.trim().is_empty() {
+        output.extend(indent_block(&project.description, ""));
+        output.push(String::new());
+    }
+    sphinx_topics(&project.topics, directive, 0, &mut output);
+    if !project.definitions.is_empty() {
+        output.extend(rst_title("Definitions", 0));
+        output.push(".. glossary::".to_string());
+        output.push(String::new());
+        for definition in &project.definitions {
+            output.push(format!("   {}", definition.name.trim()));
+            output.extend(indent_block(&definition.value, "      "));
+            for info in &definition.additional_info {
+                output.push(format!("      - {}", info.trim()));
+            }
+            output.push(String::new());
+        }
+    }
+    output.join("\n")
+}
+
+/// A requirement, or a part of its description, as one line of `export jsonl`
+///
+/// The field names are part of the output format and must not change.
+#[derive(Serialize)]
+pub struct Record {
+    /// Unique per line, the requirement ID followed by `#` and the chunk number if the
+    /// description was split
+    pub record_id: String,
+    pub id: String,
+    pub project: String,
+    pub version: String,
+    pub topic_path: Vec<String>,
+    pub topic_names: Vec<String>,
+    pub name: String,
+    /// The name and the description or chunk with collapsed whitespace, the text to index
+    pub text: String,
+    pub additional_info: Vec<String>,
+    pub links: Vec<String>,
+    pub linked_from: Vec<String>,
+    /// The 1-based number of the chunk and the number of chunks of the description
+    pub chunk: usize,
+    pub chunks: usize,
+}
+
+/// Splits text into chunks of at most `size` characters at whitespace, only words longer
+/// than `size` make longer chunks
+fn chunks(text: &str, size: usize) -> Vec<String> {
+    let mut chunks: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > size {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() || chunks.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Normalizes every requirement into records, one per chunk of its description if
+/// `chunk_size` is given
+#[must_use]
+pub fn records(project: &Project, chunk_size: Option<usize>) -> Vec<Record> {
+    let mut records = Vec::new();
+    let linked_from = project.linked_from();
+    project.visit_requirements(|r| {
+        let name = r
+            .requirement
+            .name
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let description = chunks(&r.requirement.description, chunk_size.unwrap_or(usize::MAX));
+        let topic_path: Vec<String> = r.topic_path.iter().map(|p| p.to_string()).collect();
+        let topic_names = (1..=topic_path.len())
+            .filter_map(|depth| project.topic(&topic_path[..depth]))
+            .map(|t| t.name.trim().to_string())
+            .collect::<Vec<_>>();
+        let count = description.len();
+        for (index, chunk) in description.into_iter().enumerate() {
+            records.push(Record {
+                record_id: if count > 1 {
+                    format!("{}#{}", r.id, index + 1)
+                } else {
+                    r.id.to_string()
+                },
+                id: r.id.to_string(),
+                project: project.name.trim().to_string(),
+                version: project.version.to_string(),
+                topic_path: topic_path.clone(),
+                topic_names: topic_names.clone(),
+                name: name.clone(),
+                text: format!("{name}: {chunk}"),
+                additional_info: r
+                    .requirement
+                    .additional_info
+                    .iter()
+                    .map(|i| i.trim().to_string())
+                    .collect(),
+                links: r
+                    .requirement
+                    .links
+                    .iter()
+                    .map(|l| l.trim().to_string())
+                    .collect(),
+                linked_from: linked_from.get(r.id).cloned().unwrap_or_default(),
+                chunk: index + 1,
+                chunks: count,
+            });
+        }
+    });
+    records
+}
+
+/// Writes the records as JSON Lines, one compact JSON object per line
+#[must_use]
+pub fn jsonl(records: &[Record]) -> String {
+    records
+        .iter()
+        .map(|r| serde_json::to_string(r).expect("Records serialize to JSON") + "\n")
+        .collect()
+}
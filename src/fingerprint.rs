@@ -0,0 +1,61 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{lock, Project, Requirement, Version};
+
+/// The content hashes of all requirements and a digest over them
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Fingerprints {
+    #[serde(
+        serialize_with = "crate::serialize_version",
+        deserialize_with = "crate::deserialize_version"
+    )]
+    pub version: Version,
+    /// The hash of all requirement fingerprints, independent of the order of the requirements
+    pub digest: String,
+    pub requirements: IndexMap<String, String>,
+}
+
+fn collapse_whitespace(text: &mut String) {
+    *text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+}
+
+/// Hashes the content of a requirement, ignoring how its text is wrapped and the order of its links
+#[must_use]
+pub fn fingerprint(requirement: &Requirement) -> String {
+    let mut requirement = requirement.clone();
+    collapse_whitespace(&mut requirement.name);
+    collapse_whitespace(&mut requirement.description);
+    requirement
+        .additional_info
+        .iter_mut()
+        .for_each(collapse_whitespace);
+    requirement.links.iter_mut().for_each(collapse_whitespace);
+    requirement.links.sort();
+    lock::hash(&serde_json::to_vec(&requirement).expect("Requirements serialize to JSON"))
+}
+
+/// Fingerprints every requirement in document order
+#[must_use]
+pub fn fingerprints(project: &Project) -> Fingerprints {
+    let mut requirements = IndexMap::new();
+    project.visit_requirements(|r| {
+        requirements.insert(r.id.to_string(), fingerprint(r.requirement));
+    });
+    let mut sorted: Vec<_> = requirements
+        .iter()
+        .map(|(id, hash)| format!("{id} {hash}"))
+        .collect();
+    sorted.sort();
+    Fingerprints {
+        version: project.version.clone(),
+        digest: lock::hash(sorted.join("\n").as_bytes()),
+        requirements,
+    }
+}
+
+/// Lists the requirements whose fingerprint differs between `old` and `new`
+#[must_use]
+pub fn changes(old: &Fingerprints, new: &Fingerprints) -> Vec<lock::Change> {
+    lock::changed_hashes(&old.requirements, &new.requirements)
+}
@@ -0,0 +1,451 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use indexmap::IndexMap;
+use regex::Regex;
+
+use crate::{merge, Fragment, Project, Requirement, Topic};
+
+/// The text formats requirements can be read from and written to
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Yaml,
+    Json,
+    Toml,
+    Rsn,
+}
+
+impl Format {
+    /// Guesses the format from the file extension
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Format> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "yml" | "yaml" => Some(Format::Yaml),
+            "json" => Some(Format::Json),
+            "toml" => Some(Format::Toml),
+            "rsn" => Some(Format::Rsn),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Yaml => "yml",
+            Format::Json => "json",
+            Format::Toml => "toml",
+            Format::Rsn => "rsn",
+        }
+    }
+
+    pub fn serialize<T: Serialize>(self, value: &T) -> anyhow::Result<String> {
+        Ok(match self {
+            Format::Yaml => serde_yaml::to_string(value)?,
+            Format::Json => serde_json::to_string_pretty(value)? + "\n",
+            Format::Toml => toml::to_string(value)?,
+            Format::Rsn => rsn::to_string_pretty(value) + "\n",
+        })
+    }
+
+    pub fn parse<T: DeserializeOwned>(self, value: &str) -> anyhow::Result<T> {
+        Ok(match self {
+            Format::Yaml => serde_yaml::from_str(value)?,
+            Format::Json => serde_json::from_str(value)?,
+            Format::Toml => toml::from_str(value)?,
+            Format::Rsn => rsn::from_str(value)?,
+        })
+    }
+}
+
+/// A file that could not be parsed, with the location of the problem if the parser reports one
+#[derive(Debug, miette::Diagnostic)]
+#[diagnostic(code(req::parse))]
+pub struct ParseError {
+    path: PathBuf,
+    /// The format the file was read as, none if it parsed but its includes could not be resolved
+    format: Option<Format>,
+    message: String,
+    #[source_code]
+    source_code: miette::NamedSource<String>,
+    #[label("{message}")]
+    span: Option<miette::SourceSpan>,
+    #[help]
+    help: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.format {
+            Some(format) => write!(
+                f,
+                "Failed to parse {} as {}",
+                self.path.display(),
+                format_name(format)
+            )?,
+            None => write!(f, "Failed to include the files of {}", self.path.display())?,
+        }
+        if self.span.is_none() {
+            write!(f, ": {}", self.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn format_name(format: Format) -> String {
+    format!("{format:?}").to_uppercase()
+}
+
+/// Converts a 1-based line and column into a byte offset
+fn offset(source: &str, line: usize, column: usize) -> usize {
+    let start: usize = source
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    (start + column.saturating_sub(1)).min(source.len())
+}
+
+/// Extracts the message and location from an error returned by [`Format::parse`]
+fn describe(error: &anyhow::Error, source: &str) -> (String, Option<(usize, usize)>) {
+    let location = Regex::new(r" at line \d+ column \d+").expect("Valid regex");
+    if let Some(e) = error.downcast_ref::<serde_json::Error>() {
+        let message = location.replace_all(&e.to_string(), "").to_string();
+        (message, Some((offset(source, e.line(), e.column()), 1)))
+    } else if let Some(e) = error.downcast_ref::<serde_yaml::Error>() {
+        let message = location.replace_all(&e.to_string(), "").to_string();
+        (message, e.location().map(|l| (l.index(), 1)))
+    } else if let Some(e) = error.downcast_ref::<toml::de::Error>() {
+        let span = e.span().map(|s| (s.start, s.len().max(1)));
+        (e.message().to_string(), span)
+    } else if let Some(e) = error.downcast_ref::<rsn::de::Error>() {
+        let span = (e.location.start, e.location.len().max(1));
+        (e.kind.to_string(), Some(span))
+    } else {
+        (error.to_string(), None)
+    }
+}
+
+impl ParseError {
+    fn new(path: &Path, format: Format, source: String, error: &anyhow::Error) -> ParseError {
+        let (message, span) = describe(error, &source);
+        let span = span.map(|(start, len)| {
+            let start = start.min(source.len());
+            (start, len.min(source.len() - start)).into()
+        });
+        ParseError {
+            path: path.to_path_buf(),
+            format: Some(format),
+            message,
+            source_code: miette::NamedSource::new(path.display().to_string(), source),
+            span,
+            help: format!(
+                "The file is read as {} because of its extension, run `req schema` to see the expected structure",
+                format_name(format)
+            ),
+        }
+    }
+
+    /// An include in the file at `path` of a file already on the include `chain`, pointing at the
+    /// include if it is written out in `source`
+    fn circular_include(
+        path: &Path,
+        source: String,
+        include: &str,
+        chain: &[PathBuf],
+    ) -> ParseError {
+        let chain: Vec<_> = chain.iter().map(|p| p.display().to_string()).collect();
+        let span = source
+            .find(include)
+            .map(|start| (start, include.len().max(1)).into());
+        ParseError {
+            path: path.to_path_buf(),
+            format: None,
+            message: format!("Circular include {}", chain.join(" -> ")),
+            source_code: miette::NamedSource::new(path.display().to_string(), source),
+            span,
+            help: "A file must not include itself, directly or through other files".to_string(),
+        }
+    }
+
+    /// The path of the file that could not be parsed
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The message of the parser, without the location
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The byte offset of the problem in the file, if the parser reports one
+    #[must_use]
+    pub fn offset(&self) -> Option<usize> {
+        self.span.map(|s| s.offset())
+    }
+}
+
+fn trim(value: &mut String) {
+    let trimmed = value.trim();
+    if trimmed.len() != value.len() {
+        *value = trimmed.to_string();
+    }
+}
+
+fn canonicalize_requirements(requirements: &mut IndexMap<String, Requirement>) {
+    *requirements = std::mem::take(requirements)
+        .into_iter()
+        .map(|(id, mut requirement)| {
+            trim(&mut requirement.name);
+            trim(&mut requirement.description);
+            requirement.additional_info.iter_mut().for_each(trim);
+            requirement.links.iter_mut().for_each(trim);
+            (id.trim().to_string(), requirement)
+        })
+        .collect();
+}
+
+fn canonicalize_topics(topics: &mut IndexMap<String, Topic>) {
+    *topics = std::mem::take(topics)
+        .into_iter()
+        .map(|(id, mut topic)| {
+            trim(&mut topic.name);
+            canonicalize_requirements(&mut topic.requirements);
+            canonicalize_topics(&mut topic.subtopics);
+            (id.trim().to_string(), topic)
+        })
+        .collect();
+}
+
+/// Trims all keys and text values so equal content is always serialized the same way
+pub fn canonicalize(project: &mut Project) {
+    trim(&mut project.name);
+    trim(&mut project.description);
+    let mut fragment = Fragment {
+        topics: std::mem::take(&mut project.topics),
+        definitions: std::mem::take(&mut project.definitions),
+        config_defaults: std::mem::take(&mut project.config_defaults),
+        includes: std::mem::take(&mut project.includes),
+        archive: std::mem::take(&mut project.archive),
+    };
+    canonicalize_fragment(&mut fragment);
+    project.topics = fragment.topics;
+    project.definitions = fragment.definitions;
+    project.config_defaults = fragment.config_defaults;
+    project.includes = fragment.includes;
+    project.archive = fragment.archive;
+}
+
+/// Like [`canonicalize`], for the content of an included file
+pub fn canonicalize_fragment(fragment: &mut Fragment) {
+    canonicalize_topics(&mut fragment.topics);
+    for definition in &mut fragment.definitions {
+        trim(&mut definition.name);
+        trim(&mut definition.value);
+        definition.additional_info.iter_mut().for_each(trim);
+    }
+    for default in &mut fragment.config_defaults {
+        trim(&mut default.name);
+        trim(&mut default.typ);
+        for value in [
+            &mut default.unit,
+            &mut default.default_value,
+            &mut default.hint,
+            &mut default.env,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            trim(value);
+        }
+        default.valid_values.iter_mut().flatten().for_each(trim);
+    }
+    fragment.includes.iter_mut().for_each(trim);
+    canonicalize_requirements(&mut fragment.archive);
+}
+
+/// Parses a value from any of the supported formats and reports which one matched
+pub fn parse_with_format<T: DeserializeOwned>(value: &str) -> anyhow::Result<(T, Format)> {
+    for format in [Format::Json, Format::Yaml, Format::Rsn] {
+        match format.parse(value) {
+            Ok(parsed) => {
+                tracing::debug!("Detected format {format:?}");
+                return Ok((parsed, format));
+            }
+            Err(e) => tracing::trace!("Not {format:?}: {e}"),
+        }
+    }
+    tracing::debug!("Falling back to format Toml");
+    Ok((Format::Toml.parse(value)?, Format::Toml))
+}
+
+pub fn parse(value: &str) -> anyhow::Result<Project> {
+    Ok(parse_with_format(value)?.0)
+}
+
+/// The contents to use instead of the files on disk, by canonical path
+pub type Overrides = HashMap<PathBuf, String>;
+
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn read(path: &Path, overrides: &Overrides) -> anyhow::Result<String> {
+    match overrides.get(&canonical(path)) {
+        Some(value) => Ok(value.clone()),
+        None => std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", path.display())),
+    }
+}
+
+fn parse_file<T: DeserializeOwned>(path: &Path) -> anyhow::Result<T> {
+    parse_file_with(path, &Overrides::new())
+}
+
+fn parse_file_with<T: DeserializeOwned>(path: &Path, overrides: &Overrides) -> anyhow::Result<T> {
+    Ok(parse_contents(path, read(path, overrides)?)?.0)
+}
+
+/// Parses a file by its extension, or detects the format if it has no known one, without
+/// resolving its includes, and reports the format
+pub fn parse_file_with_format<T: DeserializeOwned>(path: &Path) -> anyhow::Result<(T, Format)> {
+    parse_contents(path, read(path, &Overrides::new())?)
+}
+
+fn parse_contents<T: DeserializeOwned>(path: &Path, value: String) -> anyhow::Result<(T, Format)> {
+    match Format::from_path(path) {
+        Some(format) => {
+            tracing::debug!("Parsing {} as {format:?} by its extension", path.display());
+            match format.parse(&value) {
+                Ok(parsed) => Ok((parsed, format)),
+                Err(e) => Err(ParseError::new(path, format, value, &e).into()),
+            }
+        }
+        None => {
+            tracing::debug!("Detecting the format of {}", path.display());
+            parse_with_format(&value)
+        }
+    }
+}
+
+/// Fails if `include` of the last file on the include `stack` leads back to a file on it
+fn check_cycle(
+    stack: &[PathBuf],
+    include: &str,
+    path: &Path,
+    overrides: &Overrides,
+) -> anyhow::Result<()> {
+    let included = canonical(path);
+    if let Some(start) = stack.iter().position(|p| canonical(p) == included) {
+        let including = stack
+            .last()
+            .expect("Includes come from a file on the stack");
+        let mut chain = stack[start..].to_vec();
+        chain.push(path.to_path_buf());
+        let source = read(including, overrides)?;
+        return Err(ParseError::circular_include(including, source, include, &chain).into());
+    }
+    Ok(())
+}
+
+/// Merges the `includes` of the last file on the include `stack` into the project
+fn resolve_includes(
+    project: &mut Project,
+    stack: &mut Vec<PathBuf>,
+    includes: Vec<String>,
+    overrides: &Overrides,
+) -> anyhow::Result<()> {
+    let including = stack
+        .last()
+        .expect("Includes come from a file on the stack");
+    let base = including.parent().unwrap_or(Path::new(".")).to_path_buf();
+    for include in includes {
+        let path = base.join(&include);
+        check_cycle(stack, &include, &path, overrides)?;
+        tracing::info!("Including {}", path.display());
+        let mut fragment: Fragment = parse_file_with(&path, overrides)?;
+        let nested = std::mem::take(&mut fragment.includes);
+        let conflicts = merge::merge_fragment(project, fragment);
+        if !conflicts.is_empty() {
+            let conflicts: Vec<_> = conflicts.iter().map(|c| c.to_string()).collect();
+            anyhow::bail!("Conflicts in {}: {}", path.display(), conflicts.join(", "));
+        }
+        stack.push(path);
+        resolve_includes(project, stack, nested, overrides)?;
+        stack.pop();
+    }
+    Ok(())
+}
+
+fn collect_source_files(stack: &mut Vec<PathBuf>, files: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+    let path = stack.last().expect("The file to collect is on the stack");
+    // RSN checks struct names, so the root file only parses as a project
+    let includes = match parse_file::<Fragment>(path) {
+        Ok(fragment) => fragment.includes,
+        Err(_) => parse_file::<Project>(path)?.includes,
+    };
+    files.push(path.clone());
+    let base = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+    for include in includes {
+        let path = base.join(&include);
+        check_cycle(stack, &include, &path, &Overrides::new())?;
+        stack.push(path);
+        collect_source_files(stack, files)?;
+        stack.pop();
+    }
+    Ok(())
+}
+
+/// Returns the path of the project file and of all files it includes
+pub fn source_files(path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    collect_source_files(&mut vec![path.to_path_buf()], &mut files)?;
+    Ok(files)
+}
+
+/// Finds the 1-based line where `id` is used as a key in YAML, JSON, TOML or RSN content
+#[must_use]
+pub fn key_line(content: &str, id: &str) -> Option<usize> {
+    content.lines().enumerate().find_map(|(index, line)| {
+        crate::trace::mentions(line, id)
+            .any(|end| {
+                let before = line[..end - id.len()].trim_start();
+                let after = line[end..].trim_start_matches(['"', '\'']).trim_start();
+                let key_start = before.is_empty()
+                    || before == "\""
+                    || before == "'"
+                    || before.ends_with(['.', '['])
+                    || before.ends_with(".\"");
+                let key_end = after.starts_with([':', '=']) || after.starts_with(']');
+                key_start && key_end
+            })
+            .then_some(index + 1)
+    })
+}
+
+/// Reads a project from a file and merges all included files into it
+pub fn load(path: &Path) -> anyhow::Result<Project> {
+    load_with(path, &Overrides::new())
+}
+
+/// Like [`load`], but reads the files in `overrides` from there, like unsaved editor buffers
+pub fn load_with(path: &Path, overrides: &Overrides) -> anyhow::Result<Project> {
+    let start = std::time::Instant::now();
+    let mut project: Project = parse_file_with(path, overrides)?;
+    let includes = std::mem::take(&mut project.includes);
+    resolve_includes(
+        &mut project,
+        &mut vec![path.to_path_buf()],
+        includes,
+        overrides,
+    )?;
+    tracing::info!("Loaded {} in {:?}", path.display(), start.elapsed());
+    Ok(project)
+}
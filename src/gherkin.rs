@@ -0,0 +1,156 @@
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::{trace, Project, Topic};
+
+/// Turns an ID into a Gherkin tag, tags cannot contain whitespace
+#[must_use]
+pub fn tag(id: &str) -> String {
+    let id: Vec<_> = id.split_whitespace().collect();
+    format!("@{}", id.join("_"))
+}
+
+fn feature(id: &str, topic: &Topic) -> String {
+    let mut output = vec![tag(id), format!("Feature: {}", topic.name.trim())];
+    for (id, requirement) in &topic.requirements {
+        output.push(String::new());
+        output.push(format!("  {}", tag(id)));
+        output.push(format!("  Scenario: {}", requirement.name.trim()));
+        for line in requirement.description.trim().lines() {
+            output.push(format!("    # {}", line.trim()).trim_end().to_string());
+        }
+        for info in &requirement.additional_info {
+            output.push(format!("    # - {}", info.trim()));
+        }
+        output.push("    Given <precondition>".to_string());
+        output.push("    When <action>".to_string());
+        output.push("    Then <expected result>".to_string());
+    }
+    output.join("\n") + "\n"
+}
+
+fn collect(topics: &IndexMap<String, Topic>, features: &mut Vec<(String, String)>) {
+    for (id, topic) in topics {
+        if !topic.requirements.is_empty() {
+            let file_name = format!("{}.feature", id.trim());
+            features.push((file_name, feature(id.trim(), topic)));
+        }
+        collect(&topic.subtopics, features);
+    }
+}
+
+/// Creates a feature file per topic with requirements, returned as file name and content
+///
+/// Every requirement becomes a scenario skeleton tagged with its ID.
+#[must_use]
+pub fn features(project: &Project) -> Vec<(String, String)> {
+    let mut features = Vec::new();
+    collect(&project.topics, &mut features);
+    features
+}
+
+/// A scenario in a feature file with the tags that apply to it
+#[derive(Debug, Clone, Serialize)]
+pub struct Scenario {
+    pub file: PathBuf,
+    pub line: usize,
+    pub name: String,
+    /// The tags of the scenario, its rule and its feature, without `@`
+    pub tags: Vec<String>,
+}
+
+impl Scenario {
+    /// Returns whether the scenario is tagged with the requirement ID
+    #[must_use]
+    pub fn covers(&self, id: &str) -> bool {
+        let tag = tag(id);
+        self.tags.iter().any(|t| t == &tag[1..])
+    }
+}
+
+fn parse_tags(line: &str) -> impl Iterator<Item = String> + '_ {
+    line.split('#')
+        .next()
+        .unwrap_or_default()
+        .split_whitespace()
+        .filter_map(|t| t.strip_prefix('@'))
+        .map(str::to_string)
+}
+
+/// Lists the scenarios of a feature file
+#[must_use]
+pub fn parse_scenarios(file: &Path, content: &str) -> Vec<Scenario> {
+    let mut scenarios = Vec::new();
+    let mut pending = Vec::new();
+    let mut feature_tags = Vec::new();
+    let mut rule_tags = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('@') {
+            pending.extend(parse_tags(line));
+            continue;
+        }
+        let Some((keyword, name)) = line.split_once(':') else {
+            continue;
+        };
+        match keyword.trim() {
+            "Feature" => {
+                feature_tags = std::mem::take(&mut pending);
+                rule_tags.clear();
+            }
+            "Rule" => rule_tags = std::mem::take(&mut pending),
+            "Scenario" | "Scenario Outline" | "Scenario Template" | "Example" => {
+                let mut tags = std::mem::take(&mut pending);
+                tags.extend(rule_tags.iter().cloned());
+                tags.extend(feature_tags.iter().cloned());
+                scenarios.push(Scenario {
+                    file: file.to_path_buf(),
+                    line: index + 1,
+                    name: name.trim().to_string(),
+                    tags,
+                });
+            }
+            // Tags of examples select rows, not requirements
+            _ => pending.clear(),
+        }
+    }
+    scenarios
+}
+
+/// Lists the scenarios of all `.feature` files in `paths`, descending into directories
+pub fn scenarios(paths: &[PathBuf]) -> anyhow::Result<Vec<Scenario>> {
+    let mut scenarios = Vec::new();
+    for file in trace::files(paths)? {
+        if file.extension().is_some_and(|e| e == "feature") {
+            let content = std::fs::read_to_string(&file)
+                .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", file.display()))?;
+            scenarios.extend(parse_scenarios(&file, &content));
+        }
+    }
+    tracing::info!("Found {} scenarios", scenarios.len());
+    Ok(scenarios)
+}
+
+/// The scenarios declared for a requirement
+#[derive(Debug, Clone, Serialize)]
+pub struct Coverage<'a> {
+    pub id: String,
+    pub name: String,
+    pub scenarios: Vec<&'a Scenario>,
+}
+
+/// Lists the scenarios tagged with each requirement, in document order
+#[must_use]
+pub fn coverage<'a>(project: &Project, scenarios: &'a [Scenario]) -> Vec<Coverage<'a>> {
+    let mut coverage = Vec::new();
+    project.visit_requirements(|r| {
+        coverage.push(Coverage {
+            id: r.id.to_string(),
+            name: r.requirement.name.trim().to_string(),
+            scenarios: scenarios.iter().filter(|s| s.covers(r.id)).collect(),
+        });
+    });
+    coverage
+}
@@ -0,0 +1,163 @@
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::{Project, Topic};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    Topic,
+    Requirement,
+    /// A link target that is not defined in the project
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EdgeKind {
+    Contains,
+    Links,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Node {
+    pub id: String,
+    pub name: String,
+    pub kind: NodeKind,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Graph {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+fn add_topics(graph: &mut Graph, topics: &IndexMap<String, Topic>, parent: Option<&str>) {
+    for (id, topic) in topics {
+        let id = id.trim();
+        graph.nodes.push(Node {
+            id: id.to_string(),
+            name: topic.name.trim().to_string(),
+            kind: NodeKind::Topic,
+        });
+        if let Some(parent) = parent {
+            graph.edges.push(Edge {
+                from: parent.to_string(),
+                to: id.to_string(),
+                kind: EdgeKind::Contains,
+            });
+        }
+        for (req_id, requirement) in &topic.requirements {
+            graph.nodes.push(Node {
+                id: req_id.trim().to_string(),
+                name: requirement.name.trim().to_string(),
+                kind: NodeKind::Requirement,
+            });
+            graph.edges.push(Edge {
+                from: id.to_string(),
+                to: req_id.trim().to_string(),
+                kind: EdgeKind::Contains,
+            });
+        }
+        add_topics(graph, &topic.subtopics, Some(id));
+    }
+}
+
+/// Builds a graph of the topic hierarchy and the links between requirements
+#[must_use]
+pub fn graph(project: &Project) -> Graph {
+    let mut graph = Graph::default();
+    add_topics(&mut graph, &project.topics, None);
+    let mut links = Vec::new();
+    project.visit_requirements(|r| {
+        for link in &r.requirement.links {
+            links.push((r.id.to_string(), link.trim().to_string()));
+        }
+    });
+    for (from, to) in links {
+        if !graph.nodes.iter().any(|n| n.id == to) {
+            graph.nodes.push(Node {
+                id: to.clone(),
+                name: String::new(),
+                kind: NodeKind::Unknown,
+            });
+        }
+        graph.edges.push(Edge {
+            from,
+            to,
+            kind: EdgeKind::Links,
+        });
+    }
+    graph
+}
+
+fn label(node: &Node) -> String {
+    if node.name.is_empty() {
+        node.id.clone()
+    } else {
+        format!("{} - {}", node.id, node.name)
+    }
+}
+
+#[must_use]
+pub fn to_dot(graph: &Graph) -> String {
+    let mut output = vec!["digraph requirements {".to_string()];
+    for node in &graph.nodes {
+        let shape = match node.kind {
+            NodeKind::Topic => "folder",
+            NodeKind::Requirement => "box",
+            NodeKind::Unknown => "box, style=dashed",
+        };
+        output.push(format!(
+            "    {:?} [label={:?}, shape={shape}];",
+            node.id,
+            label(node)
+        ));
+    }
+    for edge in &graph.edges {
+        let style = match edge.kind {
+            EdgeKind::Contains => "",
+            EdgeKind::Links => " [style=dashed]",
+        };
+        output.push(format!("    {:?} -> {:?}{style};", edge.from, edge.to));
+    }
+    output.push("}".to_string());
+    output.join("\n")
+}
+
+#[must_use]
+pub fn to_mermaid(graph: &Graph) -> String {
+    let index: IndexMap<&str, usize> = graph
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.id.as_str(), i))
+        .collect();
+    let mut output = vec!["graph TD".to_string()];
+    for (i, node) in graph.nodes.iter().enumerate() {
+        let label = label(node).replace('"', "#quot;");
+        output.push(match node.kind {
+            NodeKind::Topic => format!("    n{i}[/\"{label}\"/]"),
+            NodeKind::Requirement | NodeKind::Unknown => format!("    n{i}[\"{label}\"]"),
+        });
+    }
+    for edge in &graph.edges {
+        let arrow = match edge.kind {
+            EdgeKind::Contains => "-->",
+            EdgeKind::Links => "-.->",
+        };
+        output.push(format!(
+            "    n{} {arrow} n{}",
+            index[edge.from.as_str()],
+            index[edge.to.as_str()]
+        ));
+    }
+    output.join("\n")
+}
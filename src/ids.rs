@@ -0,0 +1,64 @@
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::Project;
+
+/// How new requirement IDs are formed, configured as `id_scheme` in the project configuration
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IdScheme {
+    /// The text before the number, like `REQ-`
+    pub prefix: String,
+    /// The minimum number of digits, numbers are padded with zeros
+    #[serde(default)]
+    pub digits: usize,
+    /// Number the requirements of each topic separately, with a namespace between prefix and
+    /// number, like `REQ-SEC-001`
+    #[serde(default)]
+    pub per_topic: bool,
+    /// The namespaces of topics by topic ID, topics without one use their ID
+    #[serde(default)]
+    pub namespaces: IndexMap<String, String>,
+    /// The text between namespace and number
+    #[serde(default = "default_separator")]
+    pub separator: String,
+}
+
+fn default_separator() -> String {
+    "-".to_string()
+}
+
+impl IdScheme {
+    /// The part of the IDs in a topic before the number
+    fn stem(&self, topic_path: &[&str]) -> String {
+        match topic_path.last() {
+            Some(topic) if self.per_topic => {
+                let namespace = self.namespaces.get(*topic).map_or(*topic, String::as_str);
+                format!("{}{namespace}{}", self.prefix, self.separator)
+            }
+            _ => self.prefix.clone(),
+        }
+    }
+
+    /// Allocates the ID after the highest one of the scheme in use, counting archived IDs so
+    /// they are never reused
+    #[must_use]
+    pub fn next(&self, project: &Project, topic_path: &[&str]) -> String {
+        let stem = self.stem(topic_path);
+        let mut highest = 0;
+        let mut count = |id: &str| {
+            let number = id.trim().strip_prefix(&stem).and_then(|n| {
+                n.chars()
+                    .all(|c| c.is_ascii_digit())
+                    .then(|| n.parse::<u64>().ok())
+                    .flatten()
+            });
+            if let Some(number) = number {
+                highest = highest.max(number);
+            }
+        };
+        project.visit_requirements(|r| count(r.id));
+        project.archive.keys().for_each(|id| count(id));
+        format!("{stem}{:0digits$}", highest + 1, digits = self.digits)
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::trace::{Evidence, Reference, Trace};
+use crate::{reproducible, Project};
+
+/// A requirement affected by a change, with its references and the requirements depending on it
+#[derive(Debug, Clone, Serialize)]
+pub struct Impact {
+    pub id: String,
+    pub name: String,
+    pub tests: Vec<Evidence>,
+    pub code: Vec<Reference>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub scenarios: Vec<Reference>,
+    /// The requirements linking to this one
+    pub downstream: Vec<Impact>,
+}
+
+impl Impact {
+    /// Counts the affected requirements, test results and code references, including this one
+    #[must_use]
+    pub fn count(&self) -> (usize, usize, usize) {
+        self.downstream.iter().map(Impact::count).fold(
+            (1, self.tests.len(), self.code.len() + self.scenarios.len()),
+            |(r, t, c), (dr, dt, dc)| (r + dr, t + dt, c + dc),
+        )
+    }
+}
+
+fn walk(project: &Project, traces: &[Trace], id: &str, visited: &mut HashSet<String>) -> Impact {
+    visited.insert(id.to_string());
+    let trace = traces.iter().find(|t| t.id == id);
+    let name = match project.find_requirement(id) {
+        Some((_, requirement)) => requirement.name.trim().to_string(),
+        None => String::new(),
+    };
+    let mut downstream = Vec::new();
+    for linking in project.linking_to(&[id]) {
+        // Requirements reached before are only listed at their first occurrence, which also
+        // stops at cycles
+        if !visited.contains(&linking) {
+            downstream.push(walk(project, traces, &linking, visited));
+        }
+    }
+    Impact {
+        id: id.to_string(),
+        name,
+        tests: trace.map(|t| t.tests.clone()).unwrap_or_default(),
+        code: trace.map(|t| t.code.clone()).unwrap_or_default(),
+        scenarios: trace.map(|t| t.scenarios.clone()).unwrap_or_default(),
+        downstream,
+    }
+}
+
+/// Finds everything a change to the requirement `id` affects, following links backwards
+///
+/// A requirement linking to `id` depends on it, so it is affected too, as are the requirements
+/// linking to that one and the tests and code referring to any of them.
+pub fn impact(project: &Project, traces: &[Trace], id: &str) -> anyhow::Result<Impact> {
+    let id = id.trim();
+    if project.find_requirement(id).is_none() {
+        anyhow::bail!("Requirement {id} not found");
+    }
+    Ok(walk(project, traces, id, &mut HashSet::new()))
+}
+
+fn render(impact: &Impact, prefix: &str, output: &mut Vec<String>) {
+    let mut children: Vec<(String, Option<&Impact>)> = Vec::new();
+    for evidence in &impact.tests {
+        let result = if evidence.passed { "passed" } else { "failed" };
+        children.push((
+            format!(
+                "test {}:{} ({result})",
+                reproducible::portable_path(&evidence.file),
+                evidence.line
+            ),
+            None,
+        ));
+    }
+    for (kind, references) in [("code", &impact.code), ("scenario", &impact.scenarios)] {
+        for reference in references {
+            children.push((
+                format!(
+                    "{kind} {}:{}",
+                    reproducible::portable_path(&reference.file),
+                    reference.line
+                ),
+                None,
+            ));
+        }
+    }
+    for downstream in &impact.downstream {
+        children.push((
+            format!("{} {}", downstream.id, downstream.name),
+            Some(downstream),
+        ));
+    }
+    let count = children.len();
+    for (index, (label, downstream)) in children.into_iter().enumerate() {
+        let last = index + 1 == count;
+        output.push(format!(
+            "{prefix}{}{label}",
+            if last { "└── " } else { "├── " }
+        ));
+        if let Some(downstream) = downstream {
+            let prefix = format!("{prefix}{}", if last { "    " } else { "│   " });
+            render(downstream, &prefix, output);
+        }
+    }
+}
+
+/// Renders the impact as a tree, with the references of each requirement before the requirements
+/// depending on it
+#[must_use]
+pub fn to_tree(impact: &Impact) -> String {
+    let mut output = vec![format!("{} {}", impact.id, impact.name)];
+    render(impact, "", &mut output);
+    output.join("\n")
+}
@@ -0,0 +1,532 @@
+use std::collections::HashMap;
+use std::io::Read;
+
+use clap::ValueEnum;
+use indexmap::IndexMap;
+use regex::Regex;
+
+use crate::{ConfigDefault, Definition, Priority, Project, Requirement, Topic, Version};
+
+/// Returns the topic at the path of topic names, creating missing topics with numbered IDs
+pub fn topic_by_names<'a, S: AsRef<str>>(
+    topics: &'a mut IndexMap<String, Topic>,
+    names: &[S],
+) -> Option<&'a mut Topic> {
+    topic_by_names_with_prefix(topics, names, "TOPIC-")
+}
+
+fn topic_by_names_with_prefix<'a, S: AsRef<str>>(
+    topics: &'a mut IndexMap<String, Topic>,
+    names: &[S],
+    prefix: &str,
+) -> Option<&'a mut Topic> {
+    let (name, rest) = names.split_first()?;
+    let name = name.as_ref().trim();
+    let index = match topics.values().position(|t| t.name.trim() == name) {
+        Some(index) => index,
+        None => {
+            let id = format!("{prefix}{}", topics.len() + 1);
+            topics
+                .insert_full(
+                    id,
+                    Topic {
+                        name: name.to_string(),
+                        requirements: IndexMap::new(),
+                        subtopics: IndexMap::new(),
+                        classification: None,
+                    },
+                )
+                .0
+        }
+    };
+    let (id, topic) = topics.get_index_mut(index)?;
+    if rest.is_empty() {
+        Some(topic)
+    } else {
+        let prefix = format!("{}.", id.trim());
+        topic_by_names_with_prefix(&mut topic.subtopics, rest, &prefix)
+    }
+}
+
+/// An empty project to import requirements into
+#[must_use]
+pub fn empty_project(name: &str) -> Project {
+    Project {
+        name: name.to_string(),
+        version: Version::new(0, 1, 0),
+        description: String::new(),
+        topics: IndexMap::new(),
+        definitions: Vec::new(),
+        config_defaults: Vec::new(),
+        includes: Vec::new(),
+        archive: IndexMap::new(),
+    }
+}
+
+/// Which CSV columns hold which requirement fields
+#[derive(Debug, Clone)]
+pub struct ColumnMap {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// A column holding the topic path, e.g. `Security/Authentication`
+    pub topic: Option<String>,
+    pub additional_info: Option<String>,
+    /// A column holding comma or semicolon separated IDs of related requirements
+    pub links: Option<String>,
+}
+
+impl Default for ColumnMap {
+    fn default() -> Self {
+        ColumnMap {
+            id: "id".to_string(),
+            name: "name".to_string(),
+            description: "description".to_string(),
+            topic: Some("topic".to_string()),
+            additional_info: Some("additional_info".to_string()),
+            links: Some("links".to_string()),
+        }
+    }
+}
+
+impl ColumnMap {
+    /// The columns of a Jira CSV export, with the requirement ID in a `Requirement ID` custom field
+    #[must_use]
+    pub fn jira() -> ColumnMap {
+        ColumnMap {
+            id: "Requirement ID".to_string(),
+            name: "Summary".to_string(),
+            description: "Description".to_string(),
+            topic: Some("Component/s".to_string()),
+            additional_info: None,
+            links: None,
+        }
+    }
+
+    /// Parses mappings like `id=Column1`, starting from the default column names
+    pub fn parse<S: AsRef<str>>(mappings: &[S]) -> anyhow::Result<ColumnMap> {
+        ColumnMap::default().with_mappings(mappings)
+    }
+
+    /// Replaces the columns of the fields named by mappings like `id=Column1`
+    pub fn with_mappings<S: AsRef<str>>(mut self, mappings: &[S]) -> anyhow::Result<ColumnMap> {
+        let map = &mut self;
+        for mapping in mappings {
+            let Some((field, column)) = mapping.as_ref().split_once('=') else {
+                anyhow::bail!(
+                    "Invalid mapping '{}', expected field=column",
+                    mapping.as_ref()
+                );
+            };
+            let column = column.trim().to_string();
+            match field.trim() {
+                "id" => map.id = column,
+                "name" => map.name = column,
+                "description" => map.description = column,
+                "topic" => map.topic = Some(column),
+                "additional_info" => map.additional_info = Some(column),
+                "links" => map.links = Some(column),
+                field => anyhow::bail!("Unknown field '{field}'"),
+            }
+        }
+        Ok(self)
+    }
+}
+
+fn column_indices<R: Read>(reader: &mut csv::Reader<R>) -> anyhow::Result<HashMap<String, usize>> {
+    Ok(reader
+        .headers()?
+        .iter()
+        .enumerate()
+        .map(|(i, h)| (h.trim().to_string(), i))
+        .collect())
+}
+
+/// Builds a project from CSV rows, one requirement per row
+pub fn from_csv(
+    project: &mut Project,
+    reader: impl Read,
+    columns: &ColumnMap,
+    topic_separator: &str,
+) -> anyhow::Result<()> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let headers = column_indices(&mut reader)?;
+    let index = |column: &str| {
+        headers
+            .get(column)
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("Missing column '{column}'"))
+    };
+    let optional_index =
+        |column: &Option<String>| column.as_ref().and_then(|c| headers.get(c)).copied();
+    let id = index(&columns.id)?;
+    let name = index(&columns.name)?;
+    let description = index(&columns.description)?;
+    let topic = optional_index(&columns.topic);
+    let additional_info = optional_index(&columns.additional_info);
+    let links = optional_index(&columns.links);
+
+    for record in reader.records() {
+        let record = record?;
+        let field = |i: usize| record.get(i).unwrap_or_default().trim();
+        if field(id).is_empty() {
+            continue;
+        }
+        let topic_path: Vec<_> = topic
+            .map(field)
+            .unwrap_or_default()
+            .split(topic_separator)
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .collect();
+        let topic_path = if topic_path.is_empty() {
+            vec!["General"]
+        } else {
+            topic_path
+        };
+        let topic = topic_by_names(&mut project.topics, &topic_path).expect("Path is not empty");
+        topic.requirements.insert(
+            field(id).to_string(),
+            Requirement {
+                name: field(name).to_string(),
+                description: field(description).to_string(),
+                additional_info: additional_info
+                    .map(field)
+                    .unwrap_or_default()
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+                links: links
+                    .map(field)
+                    .unwrap_or_default()
+                    .split([',', ';'])
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+                classification: None,
+                priority: None,
+                state: None,
+            },
+        );
+    }
+    Ok(())
+}
+
+/// Which columns of a DOORS module export hold which object attributes
+#[derive(Debug, Clone)]
+pub struct DoorsColumns {
+    pub id: String,
+    pub heading: String,
+    pub text: String,
+    /// The heading level of the object, 1 for top-level headings
+    pub level: String,
+}
+
+impl Default for DoorsColumns {
+    fn default() -> Self {
+        DoorsColumns {
+            id: "ID".to_string(),
+            heading: "Object Heading".to_string(),
+            text: "Object Text".to_string(),
+            level: "Object Level".to_string(),
+        }
+    }
+}
+
+impl DoorsColumns {
+    /// Parses mappings like `id=Absolute Number`, starting from the default DOORS column names
+    pub fn parse<S: AsRef<str>>(mappings: &[S]) -> anyhow::Result<DoorsColumns> {
+        let mut columns = DoorsColumns::default();
+        for mapping in mappings {
+            let Some((field, column)) = mapping.as_ref().split_once('=') else {
+                anyhow::bail!(
+                    "Invalid mapping '{}', expected field=column",
+                    mapping.as_ref()
+                );
+            };
+            let column = column.trim().to_string();
+            match field.trim() {
+                "id" => columns.id = column,
+                "heading" => columns.heading = column,
+                "text" => columns.text = column,
+                "level" => columns.level = column,
+                field => anyhow::bail!("Unknown field '{field}'"),
+            }
+        }
+        Ok(columns)
+    }
+}
+
+/// Shortens the first sentence of `text` to a requirement name
+pub(crate) fn name_from_text(text: &str) -> String {
+    const MAX_LENGTH: usize = 60;
+    let sentence = text
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_inclusive(". ")
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .trim_end_matches('.');
+    if sentence.chars().count() <= MAX_LENGTH {
+        return sentence.to_string();
+    }
+    let mut name = String::new();
+    for word in sentence.split_whitespace() {
+        if name.chars().count() + word.chars().count() + 1 > MAX_LENGTH {
+            break;
+        }
+        if !name.is_empty() {
+            name.push(' ');
+        }
+        name.push_str(word);
+    }
+    name + "…"
+}
+
+/// Builds a project from a DOORS classic CSV export
+///
+/// Objects with a heading become topics nested by their level, objects with text become
+/// requirements of the closest heading above them, named after their first sentence.
+pub fn from_doors(
+    project: &mut Project,
+    reader: impl Read,
+    columns: &DoorsColumns,
+    id_prefix: &str,
+) -> anyhow::Result<()> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let headers = column_indices(&mut reader)?;
+    let index = |column: &str| {
+        headers
+            .get(column)
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("Missing column '{column}'"))
+    };
+    let id = index(&columns.id)?;
+    let heading = index(&columns.heading)?;
+    let text = index(&columns.text)?;
+    let level = index(&columns.level)?;
+
+    let mut path: Vec<String> = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let field = |i: usize| record.get(i).unwrap_or_default().trim();
+        if !field(heading).is_empty() {
+            let level: usize = field(level).parse().map_err(|_| {
+                anyhow::anyhow!("Invalid level '{}' of object {}", field(level), field(id))
+            })?;
+            path.truncate(level.saturating_sub(1));
+            path.push(field(heading).to_string());
+            topic_by_names(&mut project.topics, &path);
+        } else if !field(text).is_empty() {
+            if field(id).is_empty() {
+                continue;
+            }
+            let topic_path = if path.is_empty() {
+                vec!["General".to_string()]
+            } else {
+                path.clone()
+            };
+            let topic =
+                topic_by_names(&mut project.topics, &topic_path).expect("Path is not empty");
+            topic.requirements.insert(
+                format!("{id_prefix}{}", field(id)),
+                Requirement {
+                    name: name_from_text(field(text)),
+                    description: field(text).to_string(),
+                    additional_info: Vec::new(),
+                    links: Vec::new(),
+                    classification: None,
+                    priority: None,
+                    state: None,
+                },
+            );
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Preamble,
+    Description,
+    Requirements,
+    Definitions,
+    ConfigDefaults,
+}
+
+/// Parses a Markdown document structured like the output of the `markdown` command
+pub fn from_markdown(markdown: &str) -> anyhow::Result<Project> {
+    let keyword = Regex::new(
+        r"\*\*_(MUST NOT|MUST|REQUIRED|SHALL NOT|SHALL|SHOULD NOT|SHOULD|RECOMMENDED|MAY|OPTIONAL)_\*\*",
+    )?;
+    let markdown = keyword.replace_all(markdown, |c: &regex::Captures| c[1].to_lowercase());
+    let topic_re = Regex::new(r"^(#+) _(.+?)_ - (.*)$")?;
+    let requirement_re = Regex::new(r"^- \*\*_(.+?)_ - (.*?):\*\* ?(.*)$")?;
+    let link_re = Regex::new(r"_(.+?)_")?;
+    let definition_re = Regex::new(r"^- (.+?): (.*)$")?;
+    let default_re = Regex::new(r"^- \*\*(.+)\*\*$")?;
+    let default_value_re = Regex::new(r"^Default Value: _(.*?)_(?: (.*))?$")?;
+    let required_re = Regex::new(
+        r"^\*\*Required\*\*: This value must be provided as a start parameter\.(?: (.*))?$",
+    )?;
+
+    let mut project = empty_project("");
+    let mut section = Section::Preamble;
+    let mut description = Vec::new();
+    let mut base_level = None;
+    let mut path: Vec<String> = Vec::new();
+    let mut requirement: Option<String> = None;
+
+    for line in markdown.lines() {
+        if let Some(name) = line.strip_prefix("# Requirements for ") {
+            project.name = name.trim().to_string();
+            continue;
+        }
+        if let Some(version) = line
+            .strip_prefix("**VERSION: ")
+            .and_then(|v| v.strip_suffix("**"))
+        {
+            project.version = version.trim().parse().map_err(|e| anyhow::anyhow!("{e}"))?;
+            continue;
+        }
+        let heading = line
+            .strip_prefix("## ")
+            .and_then(|title| match title.trim() {
+                "Description" => Some(Section::Description),
+                "Requirements" => Some(Section::Requirements),
+                "Definitions" => Some(Section::Definitions),
+                "Config Defaults" => Some(Section::ConfigDefaults),
+                _ => None,
+            });
+        if let Some(heading) = heading {
+            section = heading;
+            continue;
+        }
+        if section == Section::Preamble && topic_re.is_match(line) {
+            section = Section::Requirements;
+        }
+        match section {
+            Section::Preamble => {}
+            Section::Description => description.push(line),
+            Section::Requirements => {
+                if let Some(captures) = topic_re.captures(line) {
+                    let level = captures[1].len();
+                    let base = *base_level.get_or_insert(level);
+                    path.truncate(level.saturating_sub(base));
+                    let topics = match project.topic_mut(&path) {
+                        Some(parent) => &mut parent.subtopics,
+                        None => &mut project.topics,
+                    };
+                    topics.insert(
+                        captures[2].to_string(),
+                        Topic {
+                            name: captures[3].trim().to_string(),
+                            requirements: IndexMap::new(),
+                            subtopics: IndexMap::new(),
+                            classification: None,
+                        },
+                    );
+                    path.push(captures[2].to_string());
+                    requirement = None;
+                } else if let Some(captures) = requirement_re.captures(line) {
+                    let Some(topic) = project.topic_mut(&path) else {
+                        anyhow::bail!("Requirement {} is not inside a topic", &captures[1]);
+                    };
+                    topic.requirements.insert(
+                        captures[1].to_string(),
+                        Requirement {
+                            name: captures[2].trim().to_string(),
+                            description: captures[3].trim().to_string(),
+                            additional_info: Vec::new(),
+                            links: Vec::new(),
+                            classification: None,
+                            priority: None,
+                            state: None,
+                        },
+                    );
+                    requirement = Some(captures[1].to_string());
+                } else if let Some(current) = requirement
+                    .as_ref()
+                    .and_then(|id| project.topic_mut(&path)?.requirements.get_mut(id))
+                {
+                    if let Some(links) = line.strip_prefix("  - **Links:** ") {
+                        current.links = link_re
+                            .captures_iter(links)
+                            .map(|c| c[1].to_string())
+                            .collect();
+                    } else if let Some(priority) = line.strip_prefix("  - **Priority:** ") {
+                        current.priority =
+                            Some(Priority::from_str(priority.trim(), true).map_err(|e| {
+                                anyhow::anyhow!("Invalid priority '{}': {e}", priority.trim())
+                            })?);
+                    } else if let Some(info) = line.strip_prefix("  - ") {
+                        // Information that starts like a labelled field is escaped
+                        let info = info.strip_prefix('\\').unwrap_or(info);
+                        current.additional_info.push(info.trim().to_string());
+                    } else if !line.trim().is_empty() {
+                        current.description.push('\n');
+                        current.description.push_str(line.trim());
+                    }
+                }
+            }
+            Section::Definitions => {
+                if let Some(captures) = definition_re.captures(line) {
+                    project.definitions.push(Definition {
+                        name: captures[1].trim().to_string(),
+                        value: captures[2].trim().to_string(),
+                        additional_info: Vec::new(),
+                    });
+                } else if let (Some(info), Some(definition)) =
+                    (line.strip_prefix("  - "), project.definitions.last_mut())
+                {
+                    definition.additional_info.push(info.trim().to_string());
+                }
+            }
+            Section::ConfigDefaults => {
+                if let Some(captures) = default_re.captures(line) {
+                    project.config_defaults.push(ConfigDefault {
+                        name: captures[1].trim().to_string(),
+                        typ: String::new(),
+                        valid_values: None,
+                        unit: None,
+                        default_value: None,
+                        hint: None,
+                        env: None,
+                    });
+                } else if let (Some(field), Some(default)) = (
+                    line.strip_prefix("  - "),
+                    project.config_defaults.last_mut(),
+                ) {
+                    if let Some(typ) = field.strip_prefix("Type: ") {
+                        default.typ = typ.trim().to_string();
+                    } else if let Some(env) = field
+                        .strip_prefix("Environment Variable: `")
+                        .and_then(|e| e.strip_suffix('`'))
+                    {
+                        default.env = Some(env.trim().to_string());
+                    } else if let Some(unit) = field.strip_prefix("Unit: ") {
+                        default.unit = Some(unit.trim().to_string());
+                    } else if let Some(values) = field
+                        .strip_prefix("Valid Values: _")
+                        .and_then(|v| v.strip_suffix('_'))
+                    {
+                        default.valid_values =
+                            Some(values.split(", ").map(|v| v.trim().to_string()).collect());
+                    } else if let Some(captures) = default_value_re.captures(field) {
+                        default.default_value = Some(captures[1].trim().to_string());
+                        default.hint = captures.get(2).map(|h| h.as_str().trim().to_string());
+                    } else if let Some(captures) = required_re.captures(field) {
+                        default.hint = captures.get(1).map(|h| h.as_str().trim().to_string());
+                    }
+                }
+            }
+        }
+    }
+    project.description = description.join("\n").trim().to_string();
+    Ok(project)
+}
@@ -1,10 +1,56 @@
 use std::fmt;
+use std::str::FromStr;
 
+use clap::ValueEnum;
 use indexmap::IndexMap;
 use schemars::JsonSchema;
 use serde::de::{self, Unexpected, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+pub mod api;
+pub mod apply;
+pub mod baseline;
+pub mod blame;
+pub mod certpack;
+pub mod check;
+pub mod codegen;
+pub mod config;
+pub mod confluence;
+pub mod diff;
+pub mod docs;
+pub mod doorstop;
+pub mod edit;
+pub mod export;
+pub mod fingerprint;
+pub mod format;
+pub mod gherkin;
+pub mod graph;
+pub mod ids;
+pub mod impact;
+pub mod import;
+pub mod lint;
+pub mod lock;
+pub mod lsp;
+pub mod merge;
+pub mod notion;
+pub mod oslc;
+pub mod preserve;
+pub mod profile;
+pub mod quality;
+pub mod query;
+pub mod redact;
+pub mod reid;
+pub mod reproducible;
+pub mod reqif;
+pub mod sdoc;
+pub mod sort;
+pub mod stats;
+pub mod stubs;
+pub mod sync;
+pub mod trace;
+pub mod urls;
+pub mod workspace;
+
 pub fn my_trim<S>(v: &str, s: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
@@ -12,25 +58,113 @@ where
     s.serialize_str(v.trim())
 }
 
-#[derive(JsonSchema, Debug, Deserialize, Serialize)]
+#[derive(JsonSchema, Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Requirement {
     pub name: String,
     #[serde(serialize_with = "my_trim")]
     pub description: String,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub additional_info: Vec<String>,
+    /// IDs of related requirements
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<String>,
+    /// How confidential the requirement is, at least the classification of its topic
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classification: Option<Classification>,
+    /// How important the requirement is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    /// Whether the work on the requirement is done, as read from its issue by `req sync`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<State>,
+}
+
+/// The state of the work on a requirement, like the one of the issue tracking it
+#[derive(JsonSchema, ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum State {
+    Open,
+    Closed,
+}
+
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = self.to_possible_value().expect("No skipped variants");
+        f.write_str(value.get_name())
+    }
+}
+
+/// How important a requirement is, from least to most
+#[derive(
+    JsonSchema,
+    ValueEnum,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Deserialize,
+    Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = self.to_possible_value().expect("No skipped variants");
+        f.write_str(value.get_name())
+    }
+}
+
+/// How confidential content is, from least to most
+#[derive(
+    JsonSchema,
+    ValueEnum,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Deserialize,
+    Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Classification {
+    Public,
+    Internal,
+    Confidential,
+    Secret,
+}
+
+impl fmt::Display for Classification {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = self.to_possible_value().expect("No skipped variants");
+        f.write_str(value.get_name())
+    }
 }
 
-#[derive(JsonSchema, Debug, Deserialize, Serialize)]
+#[derive(JsonSchema, Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Topic {
     pub name: String,
+    /// How confidential the topic is, applies to all requirements and subtopics in it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classification: Option<Classification>,
     #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
     pub requirements: IndexMap<String, Requirement>,
     #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
     pub subtopics: IndexMap<String, Topic>,
 }
 
-#[derive(JsonSchema, Debug, Deserialize, Serialize)]
+#[derive(JsonSchema, Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Definition {
     pub name: String,
     pub value: String,
@@ -38,7 +172,7 @@ pub struct Definition {
     pub additional_info: Vec<String>,
 }
 
-#[derive(JsonSchema, Debug, Deserialize, Serialize)]
+#[derive(JsonSchema, Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct ConfigDefault {
     pub name: String,
     #[serde(rename = "type")]
@@ -51,23 +185,100 @@ pub struct ConfigDefault {
     pub default_value: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub hint: Option<String>,
+    /// The environment variable the value is read from, defaults to the upper case name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<String>,
+}
+
+impl ConfigDefault {
+    /// The environment variable the value is read from, e.g. `EXAMPLE_TIMEOUT` for `example_timeout`
+    #[must_use]
+    pub fn env_var(&self) -> String {
+        if let Some(env) = &self.env {
+            return env.trim().to_string();
+        }
+        self.name
+            .trim()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    }
 }
 
-#[derive(JsonSchema, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(JsonSchema, Deserialize, Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Version {
     major: u64,
     minor: u64,
     patch: u64,
 }
 
+/// The part of a version to increment
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionPart {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl Version {
+    #[must_use]
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Version {
+        Version {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Increments `part` and resets all less significant parts
+    pub fn bump(&mut self, part: VersionPart) {
+        match part {
+            VersionPart::Major => {
+                self.major += 1;
+                self.minor = 0;
+                self.patch = 0;
+            }
+            VersionPart::Minor => {
+                self.minor += 1;
+                self.patch = 0;
+            }
+            VersionPart::Patch => self.patch += 1,
+        }
+    }
+}
+
 impl fmt::Display for Version {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
     }
 }
 
+impl FromStr for Version {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid version '{value}', expected 'major.minor.patch'");
+        let parts: Vec<&str> = value.split('.').collect();
+        if parts.len() != 3 {
+            return Err(invalid());
+        }
+        let part = |i: usize| parts[i].parse::<u64>().map_err(|_| invalid());
+        Ok(Version {
+            major: part(0)?,
+            minor: part(1)?,
+            patch: part(2)?,
+        })
+    }
+}
+
 // Serialization as before
-fn serialize_version<S>(version: &Version, serializer: S) -> Result<S::Ok, S::Error>
+pub(crate) fn serialize_version<S>(version: &Version, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
@@ -75,7 +286,7 @@ where
 }
 
 // Custom deserialization
-fn deserialize_version<'de, D>(deserializer: D) -> Result<Version, D::Error>
+pub(crate) fn deserialize_version<'de, D>(deserializer: D) -> Result<Version, D::Error>
 where
     D: Deserializer<'de>,
 {
@@ -92,40 +303,23 @@ where
         where
             E: de::Error,
         {
-            let parts: Vec<&str> = value.split('.').collect();
-            if parts.len() != 3 {
-                return Err(E::invalid_value(Unexpected::Str(value), &self));
-            }
-
-            let major = parts[0]
-                .parse::<u64>()
-                .map_err(|_| E::invalid_value(Unexpected::Str(value), &self))?;
-            let minor = parts[1]
-                .parse::<u64>()
-                .map_err(|_| E::invalid_value(Unexpected::Str(value), &self))?;
-            let patch = parts[2]
-                .parse::<u64>()
-                .map_err(|_| E::invalid_value(Unexpected::Str(value), &self))?;
-
-            Ok(Version {
-                major,
-                minor,
-                patch,
-            })
+            value
+                .parse()
+                .map_err(|_| E::invalid_value(Unexpected::Str(value), &self))
         }
     }
 
     deserializer.deserialize_str(VersionVisitor)
 }
 
-#[derive(JsonSchema, Debug, Deserialize, Serialize)]
+#[derive(JsonSchema, Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Project {
     pub name: String,
     #[serde(
         serialize_with = "serialize_version",
         deserialize_with = "deserialize_version"
     )]
-    #[schemars(with = "String", regex(pattern = r"^\d\.\d\.\d$"))]
+    #[schemars(with = "String", regex(pattern = r"^\d+\.\d+\.\d+$"))]
     pub version: Version,
     #[serde(serialize_with = "my_trim")]
     pub description: String,
@@ -135,9 +329,304 @@ pub struct Project {
     pub definitions: Vec<Definition>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub config_defaults: Vec<ConfigDefault>,
+    /// Paths of fragment files, relative to this file, whose content is merged into the project
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub includes: Vec<String>,
+    /// Obsolete requirements, kept so their IDs are never reused
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub archive: IndexMap<String, Requirement>,
+}
+
+/// The content of a file included by a project
+#[derive(JsonSchema, Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct Fragment {
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub topics: IndexMap<String, Topic>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub definitions: Vec<Definition>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub config_defaults: Vec<ConfigDefault>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub includes: Vec<String>,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub archive: IndexMap<String, Requirement>,
+}
+
+impl From<Project> for Fragment {
+    fn from(project: Project) -> Self {
+        Fragment {
+            topics: project.topics,
+            definitions: project.definitions,
+            config_defaults: project.config_defaults,
+            includes: project.includes,
+            archive: project.archive,
+        }
+    }
+}
+
+/// A requirement together with the IDs of the topics that contain it
+#[derive(Debug, Clone, Copy)]
+pub struct RequirementRef<'a> {
+    pub topic_path: &'a [&'a str],
+    pub id: &'a str,
+    pub requirement: &'a Requirement,
+}
+
+fn visit_topics<'a>(
+    topics: &'a IndexMap<String, Topic>,
+    path: &mut Vec<&'a str>,
+    visit: &mut impl FnMut(RequirementRef),
+) {
+    for (id, topic) in topics {
+        path.push(id.trim());
+        for (req_id, requirement) in &topic.requirements {
+            visit(RequirementRef {
+                topic_path: path,
+                id: req_id.trim(),
+                requirement,
+            });
+        }
+        visit_topics(&topic.subtopics, path, visit);
+        path.pop();
+    }
+}
+
+impl Topic {
+    /// Calls `visit` for every requirement in this topic and its subtopics
+    pub fn visit_requirements(&self, mut visit: impl FnMut(&str, &Requirement)) {
+        fn visit_topic(topic: &Topic, visit: &mut impl FnMut(&str, &Requirement)) {
+            for (id, requirement) in &topic.requirements {
+                visit(id.trim(), requirement);
+            }
+            for subtopic in topic.subtopics.values() {
+                visit_topic(subtopic, visit);
+            }
+        }
+        visit_topic(self, &mut visit);
+    }
+}
+
+impl Project {
+    /// Calls `visit` for every requirement in document order
+    pub fn visit_requirements(&self, mut visit: impl FnMut(RequirementRef)) {
+        visit_topics(&self.topics, &mut Vec::new(), &mut visit);
+    }
+
+    /// Returns the path of the topic containing the requirement and the requirement itself
+    #[must_use]
+    pub fn find_requirement(&self, id: &str) -> Option<(Vec<String>, &Requirement)> {
+        let mut path = Vec::new();
+        let requirement = find_requirement(&self.topics, id.trim(), &mut path)?;
+        Some((path, requirement))
+    }
+
+    /// Returns the IDs of all requirements linking to one of `ids`
+    #[must_use]
+    pub fn linking_to(&self, ids: &[&str]) -> Vec<String> {
+        let mut linking = Vec::new();
+        self.visit_requirements(|r| {
+            if !ids.contains(&r.id) && r.requirement.links.iter().any(|l| ids.contains(&l.trim())) {
+                linking.push(r.id.to_string());
+            }
+        });
+        linking
+    }
+
+    /// Returns the IDs of all requirements linking to each requirement, the same as calling
+    /// `linking_to` for every ID but in one pass
+    #[must_use]
+    pub fn linked_from(&self) -> IndexMap<String, Vec<String>> {
+        let mut linked_from: IndexMap<String, Vec<String>> = IndexMap::new();
+        self.visit_requirements(|r| {
+            for (index, link) in r.requirement.links.iter().enumerate() {
+                let link = link.trim();
+                let repeated = r.requirement.links[..index]
+                    .iter()
+                    .any(|l| l.trim() == link);
+                if link != r.id && !repeated {
+                    linked_from
+                        .entry(link.to_string())
+                        .or_default()
+                        .push(r.id.to_string());
+                }
+            }
+        });
+        linked_from
+    }
+
+    /// Removes the requirements below `min`, and the topics left empty
+    ///
+    /// Requirements without a priority count as low, so they are only kept for `min` low.
+    pub fn retain_priority(&mut self, min: Priority) {
+        fn retain(topics: &mut IndexMap<String, Topic>, min: Priority) {
+            topics.retain(|_, topic| {
+                topic
+                    .requirements
+                    .retain(|_, r| r.priority.unwrap_or(Priority::Low) >= min);
+                retain(&mut topic.subtopics, min);
+                !topic.requirements.is_empty() || !topic.subtopics.is_empty()
+            });
+        }
+        retain(&mut self.topics, min);
+    }
+
+    /// Removes a requirement from whichever topic contains it
+    pub fn remove_requirement(&mut self, id: &str) -> Option<Requirement> {
+        let (path, _) = self.find_requirement(id)?;
+        let topic = self.topic_mut(&path)?;
+        let key = topic
+            .requirements
+            .keys()
+            .find(|k| k.trim() == id.trim())?
+            .clone();
+        topic.requirements.shift_remove(&key)
+    }
+
+    /// Returns whether `id` belongs to an archived requirement and must not be reused
+    #[must_use]
+    pub fn is_archived(&self, id: &str) -> bool {
+        self.archive.keys().any(|k| k.trim() == id.trim())
+    }
+
+    /// Moves a requirement from its topic into the archive
+    pub fn archive_requirement(&mut self, id: &str) -> Option<()> {
+        let requirement = self.remove_requirement(id)?;
+        self.archive.insert(id.trim().to_string(), requirement);
+        Some(())
+    }
+
+    /// Looks up a topic by its ID, wherever it is nested
+    #[must_use]
+    pub fn find_topic(&self, id: &str) -> Option<&Topic> {
+        find_nested_topic(&self.topics, id.trim())
+    }
+
+    /// Removes a topic, wherever it is nested
+    pub fn remove_topic(&mut self, id: &str) -> Option<Topic> {
+        remove_topic(&mut self.topics, id.trim())
+    }
+
+    /// Looks up a topic by the IDs of the topics leading to it
+    #[must_use]
+    pub fn topic<S: AsRef<str>>(&self, path: &[S]) -> Option<&Topic> {
+        let (first, rest) = path.split_first()?;
+        let mut topic = find_topic(&self.topics, first.as_ref())?;
+        for id in rest {
+            topic = find_topic(&topic.subtopics, id.as_ref())?;
+        }
+        Some(topic)
+    }
+
+    /// Looks up a topic by the IDs of the topics leading to it
+    pub fn topic_mut<S: AsRef<str>>(&mut self, path: &[S]) -> Option<&mut Topic> {
+        let (first, rest) = path.split_first()?;
+        let mut topic = find_topic_mut(&mut self.topics, first.as_ref())?;
+        for id in rest {
+            topic = find_topic_mut(&mut topic.subtopics, id.as_ref())?;
+        }
+        Some(topic)
+    }
+}
+
+fn find_requirement<'a>(
+    topics: &'a IndexMap<String, Topic>,
+    id: &str,
+    path: &mut Vec<String>,
+) -> Option<&'a Requirement> {
+    for (topic_id, topic) in topics {
+        path.push(topic_id.trim().to_string());
+        if let Some((_, requirement)) = topic.requirements.iter().find(|(r, _)| r.trim() == id) {
+            return Some(requirement);
+        }
+        if let Some(requirement) = find_requirement(&topic.subtopics, id, path) {
+            return Some(requirement);
+        }
+        path.pop();
+    }
+    None
+}
+
+fn find_nested_topic<'a>(topics: &'a IndexMap<String, Topic>, id: &str) -> Option<&'a Topic> {
+    find_topic(topics, id).or_else(|| {
+        topics
+            .values()
+            .find_map(|topic| find_nested_topic(&topic.subtopics, id))
+    })
+}
+
+fn remove_topic(topics: &mut IndexMap<String, Topic>, id: &str) -> Option<Topic> {
+    if let Some(key) = topics.keys().find(|k| k.trim() == id).cloned() {
+        return topics.shift_remove(&key);
+    }
+    topics
+        .values_mut()
+        .find_map(|topic| remove_topic(&mut topic.subtopics, id))
+}
+
+fn find_topic<'a>(topics: &'a IndexMap<String, Topic>, id: &str) -> Option<&'a Topic> {
+    topics
+        .iter()
+        .find(|(topic_id, _)| topic_id.trim() == id.trim())
+        .map(|(_, t)| t)
+}
+
+fn find_topic_mut<'a>(topics: &'a mut IndexMap<String, Topic>, id: &str) -> Option<&'a mut Topic> {
+    topics
+        .iter_mut()
+        .find(|(topic_id, _)| topic_id.trim() == id.trim())
+        .map(|(_, t)| t)
 }
 
 #[must_use]
 pub fn demo_project() -> Project {
     serde_yaml::from_str(include_str!("../requirements.yml")).expect("Should never happen!")
 }
+
+#[must_use]
+pub fn starter_project(name: &str) -> Project {
+    Project {
+        name: name.to_string(),
+        version: Version {
+            major: 0,
+            minor: 1,
+            patch: 0,
+        },
+        description: format!("Requirements for {name}."),
+        topics: IndexMap::from([(
+            "TOPIC-1".to_string(),
+            Topic {
+                name: "Example Topic".to_string(),
+                requirements: IndexMap::from([(
+                    "REQ-1.1".to_string(),
+                    Requirement {
+                        name: "Example Requirement".to_string(),
+                        description: "The product must do something useful.".to_string(),
+                        additional_info: vec!["Additional details go here".to_string()],
+                        links: Vec::new(),
+                        classification: None,
+                        priority: None,
+                        state: None,
+                    },
+                )]),
+                subtopics: IndexMap::new(),
+                classification: None,
+            },
+        )]),
+        definitions: vec![Definition {
+            name: "Example Term".to_string(),
+            value: "What the term means in this project".to_string(),
+            additional_info: Vec::new(),
+        }],
+        config_defaults: vec![ConfigDefault {
+            name: "example_timeout".to_string(),
+            typ: "Duration".to_string(),
+            valid_values: None,
+            unit: Some("Seconds".to_string()),
+            default_value: Some("30".to_string()),
+            hint: None,
+            env: None,
+        }],
+        includes: Vec::new(),
+        archive: IndexMap::new(),
+    }
+}
@@ -0,0 +1,455 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use clap::ValueEnum;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::Project;
+
+/// Rules checking the wording of requirement descriptions
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TextRule {
+    /// The description contains no RFC 2119 keyword
+    MissingKeyword,
+    /// The description uses terms that cannot be verified, like "fast" or "user-friendly"
+    VagueTerm,
+    /// The description is written in passive voice
+    PassiveVoice,
+    /// The description combines several requirements, e.g. with "and/or"
+    Compound,
+    /// The description follows no EARS template, only checked when selected
+    Ears,
+}
+
+impl TextRule {
+    /// The rules checked when none are selected, all except the opt-in EARS rule
+    #[must_use]
+    pub fn defaults() -> Vec<TextRule> {
+        TextRule::value_variants()
+            .iter()
+            .copied()
+            .filter(|r| *r != TextRule::Ears)
+            .collect()
+    }
+}
+
+/// The templates of the Easy Approach to Requirements Syntax
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EarsPattern {
+    /// The <system> shall <response>
+    Ubiquitous,
+    /// When <trigger>, the <system> shall <response>
+    EventDriven,
+    /// While <precondition>, the <system> shall <response>
+    StateDriven,
+    /// If <trigger>, then the <system> shall <response>
+    UnwantedBehavior,
+    /// Where <feature is included>, the <system> shall <response>
+    OptionalFeature,
+    /// A combination of the other patterns, like While <precondition>, when <trigger>, ...
+    Complex,
+}
+
+impl fmt::Display for EarsPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = self.to_possible_value().expect("No skipped variants");
+        f.write_str(value.get_name())
+    }
+}
+
+fn ears_patterns() -> &'static [(EarsPattern, Regex)] {
+    static PATTERNS: std::sync::OnceLock<Vec<(EarsPattern, Regex)>> = std::sync::OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let response = r"the\s[^,]+?\sshall\s\S";
+        [
+            (
+                EarsPattern::Complex,
+                format!(r"^(while|where)\s[^,]+,\s*(when|if)\s[^,]+,\s*(then\s+)?{response}"),
+            ),
+            (
+                EarsPattern::EventDriven,
+                format!(r"^when\s[^,]+,\s*{response}"),
+            ),
+            (
+                EarsPattern::StateDriven,
+                format!(r"^while\s[^,]+,\s*{response}"),
+            ),
+            (
+                EarsPattern::UnwantedBehavior,
+                format!(r"^if\s[^,]+,\s*then\s+{response}"),
+            ),
+            (
+                EarsPattern::OptionalFeature,
+                format!(r"^where\s[^,]+,\s*{response}"),
+            ),
+            (EarsPattern::Ubiquitous, format!("^{response}")),
+        ]
+        .into_iter()
+        .map(|(pattern, regex)| {
+            let regex = Regex::new(&format!("(?i){regex}")).expect("Valid regex");
+            (pattern, regex)
+        })
+        .collect()
+    })
+}
+
+/// Classifies a description by the EARS template it follows, `None` if it follows none
+#[must_use]
+pub fn ears(description: &str) -> Option<EarsPattern> {
+    let description = description.split_whitespace().collect::<Vec<_>>().join(" ");
+    ears_patterns()
+        .iter()
+        .find(|(_, regex)| regex.is_match(&description))
+        .map(|(pattern, _)| *pattern)
+}
+
+impl fmt::Display for TextRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = self.to_possible_value().expect("No skipped variants");
+        f.write_str(value.get_name())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub id: String,
+    pub rule: String,
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} [{}]: {}", self.id, self.rule, self.message)
+    }
+}
+
+pub const KEYWORDS: [&str; 7] = [
+    "must",
+    "shall",
+    "should",
+    "may",
+    "required",
+    "recommended",
+    "optional",
+];
+
+pub const VAGUE_TERMS: [&str; 24] = [
+    "fast",
+    "quick",
+    "quickly",
+    "slow",
+    "user-friendly",
+    "easy",
+    "easily",
+    "simple",
+    "intuitive",
+    "efficient",
+    "flexible",
+    "robust",
+    "adequate",
+    "appropriate",
+    "sufficient",
+    "reasonable",
+    "approximately",
+    "several",
+    "some",
+    "various",
+    "normally",
+    "etc",
+    "as needed",
+    "if possible",
+];
+
+fn finding(id: &str, rule: impl fmt::Display, message: String) -> Finding {
+    Finding {
+        id: id.to_string(),
+        rule: rule.to_string(),
+        message,
+    }
+}
+
+/// Checks for duplicate or archived IDs, links to unknown requirements and empty fields
+#[must_use]
+pub fn structure(project: &Project) -> Vec<Finding> {
+    let mut ids = HashSet::new();
+    let mut findings = Vec::new();
+    project.visit_requirements(|r| {
+        if !ids.insert(r.id.to_string()) {
+            findings.push(finding(
+                r.id,
+                "duplicate-id",
+                "is defined more than once".to_string(),
+            ));
+        }
+        if project.is_archived(r.id) {
+            findings.push(finding(
+                r.id,
+                "archived-id",
+                "reuses the ID of an archived requirement".to_string(),
+            ));
+        }
+        if r.requirement.name.trim().is_empty() {
+            findings.push(finding(r.id, "empty-name", "has no name".to_string()));
+        }
+        if r.requirement.description.trim().is_empty() {
+            findings.push(finding(
+                r.id,
+                "empty-description",
+                "has no description".to_string(),
+            ));
+        }
+    });
+    project.visit_requirements(|r| {
+        for link in &r.requirement.links {
+            if !ids.contains(link.trim()) {
+                findings.push(finding(
+                    r.id,
+                    "dangling-link",
+                    format!("links to unknown requirement {}", link.trim()),
+                ));
+            }
+        }
+    });
+    findings
+}
+
+/// Matches any of `words` as a whole word, ignoring case
+pub(crate) fn word_regex(words: &[&str]) -> Regex {
+    let words: Vec<_> = words.iter().map(|w| regex::escape(w)).collect();
+    Regex::new(&format!(r"(?i)\b({})\b", words.join("|"))).expect("Valid regex")
+}
+
+/// Splits text into sentences at line breaks and at `.`, `!` and `?` followed by whitespace or
+/// the end, so IDs and versions like `REQ-1.1` or `v2.0` do not end a sentence
+fn sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let ends = match c {
+            '\n' => true,
+            '.' | '!' | '?' => chars.peek().is_none_or(|(_, next)| next.is_whitespace()),
+            _ => false,
+        };
+        if ends {
+            sentences.push(&text[start..index]);
+            start = index + c.len_utf8();
+        }
+    }
+    if start < text.len() {
+        sentences.push(&text[start..]);
+    }
+    sentences
+}
+
+/// Checks the wording of all requirement descriptions against `rules`
+///
+/// `keywords` replaces [`KEYWORDS`] unless it is empty.
+#[must_use]
+pub fn text(project: &Project, rules: &[TextRule], keywords: &[String]) -> Vec<Finding> {
+    let keywords = if keywords.is_empty() {
+        word_regex(&KEYWORDS)
+    } else {
+        word_regex(&keywords.iter().map(String::as_str).collect::<Vec<_>>())
+    };
+    let vague = word_regex(&VAGUE_TERMS);
+    let passive =
+        Regex::new(r"(?i)\b(is|are|was|were|be|been|being)\s+(\w+ed|built|done|made|given|shown|sent|kept|set|written)\b")
+            .expect("Valid regex");
+    let mut findings = Vec::new();
+    project.visit_requirements(|r| {
+        let description = r.requirement.description.trim();
+        for rule in rules {
+            match rule {
+                TextRule::MissingKeyword => {
+                    if !keywords.is_match(description) {
+                        findings.push(finding(
+                            r.id,
+                            rule,
+                            "contains no RFC 2119 keyword".to_string(),
+                        ));
+                    }
+                }
+                TextRule::VagueTerm => {
+                    for term in vague.find_iter(description) {
+                        findings.push(finding(
+                            r.id,
+                            rule,
+                            format!("uses the vague term '{}'", term.as_str()),
+                        ));
+                    }
+                }
+                TextRule::PassiveVoice => {
+                    if let Some(m) = passive.find(description) {
+                        findings.push(finding(
+                            r.id,
+                            rule,
+                            format!("uses passive voice '{}'", m.as_str()),
+                        ));
+                    }
+                }
+                TextRule::Ears => {
+                    if ears(description).is_none() {
+                        findings.push(finding(r.id, rule, "follows no EARS template".to_string()));
+                    }
+                }
+                TextRule::Compound => {
+                    if description.to_lowercase().contains("and/or") {
+                        findings.push(finding(r.id, rule, "uses 'and/or'".to_string()));
+                    }
+                    for sentence in sentences(description) {
+                        if keywords.find_iter(sentence).count() > 1 {
+                            findings.push(finding(
+                                r.id,
+                                rule,
+                                format!("combines several requirements in '{}'", sentence.trim()),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+    });
+    findings
+}
+
+/// Words that invert a requirement, ignored when comparing descriptions
+const NEGATIONS: [&str; 5] = ["not", "never", "no", "cannot", "without"];
+
+/// The pairs of adjacent words of a normalized description, without negations
+fn shingles(description: &str) -> (HashSet<(String, String)>, usize) {
+    let words: Vec<String> = description
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let negations = words
+        .iter()
+        .filter(|w| NEGATIONS.contains(&w.as_str()))
+        .count();
+    let words: Vec<_> = words
+        .into_iter()
+        .filter(|w| !NEGATIONS.contains(&w.as_str()))
+        .collect();
+    let shingles = match words.as_slice() {
+        [] => HashSet::new(),
+        [word] => HashSet::from([(word.clone(), String::new())]),
+        words => words
+            .windows(2)
+            .map(|pair| (pair[0].clone(), pair[1].clone()))
+            .collect(),
+    };
+    (shingles, negations)
+}
+
+/// Flags pairs of requirements whose descriptions have a Jaccard similarity of at least
+/// `threshold`, comparing pairs of adjacent words
+///
+/// Negations are ignored for the comparison, so "must" and "must not" versions of the same
+/// sentence are found too and reported as possible contradictions.
+#[must_use]
+pub fn similarity(project: &Project, threshold: f64) -> Vec<Finding> {
+    let mut descriptions = Vec::new();
+    project.visit_requirements(|r| {
+        let (shingles, negations) = shingles(&r.requirement.description);
+        if !shingles.is_empty() {
+            descriptions.push((r.id.to_string(), shingles, negations));
+        }
+    });
+    let mut findings = Vec::new();
+    for (index, (id, shingles, negations)) in descriptions.iter().enumerate() {
+        for (other, other_shingles, other_negations) in &descriptions[..index] {
+            let shared = shingles.intersection(other_shingles).count();
+            let similarity = shared as f64 / shingles.union(other_shingles).count() as f64;
+            if similarity < threshold {
+                continue;
+            }
+            let percent = (similarity * 100.0).round();
+            findings.push(if (negations % 2) == (other_negations % 2) {
+                finding(
+                    id,
+                    "possible-duplicate",
+                    format!("is {percent}% similar to {other}"),
+                )
+            } else {
+                finding(
+                    id,
+                    "possible-contradiction",
+                    format!("is {percent}% similar to {other} but negated"),
+                )
+            });
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+
+    use super::*;
+    use crate::{import, Requirement, Topic};
+
+    fn compound(description: &str) -> Vec<String> {
+        let mut project = import::empty_project("Lint");
+        let requirement = Requirement {
+            name: "R".to_string(),
+            description: description.to_string(),
+            additional_info: Vec::new(),
+            links: Vec::new(),
+            classification: None,
+            priority: None,
+            state: None,
+        };
+        project.topics.insert(
+            "T".to_string(),
+            Topic {
+                name: "T".to_string(),
+                requirements: IndexMap::from([("REQ-1".to_string(), requirement)]),
+                subtopics: IndexMap::new(),
+                classification: None,
+            },
+        );
+        text(&project, &[TextRule::Compound], &[])
+            .into_iter()
+            .map(|f| f.message)
+            .collect()
+    }
+
+    #[test]
+    fn sentences_end_at_punctuation_followed_by_whitespace() {
+        assert_eq!(
+            sentences("See REQ-1.1 in v2.0. Done!\nWhy? Because"),
+            ["See REQ-1.1 in v2.0", " Done", "", "Why", " Because"]
+        );
+        assert_eq!(sentences("Wait... what."), ["Wait..", " what"]);
+        assert!(sentences("").is_empty());
+    }
+
+    #[test]
+    fn compound_finds_several_keywords_in_one_sentence() {
+        assert_eq!(
+            compound("The tool must parse v2.0 files and should warn."),
+            ["combines several requirements in 'The tool must parse v2.0 files and should warn'"]
+        );
+        assert_eq!(
+            compound("Like REQ-1.1 the tool must read and may cache files"),
+            ["combines several requirements in 'Like REQ-1.1 the tool must read and may cache files'"]
+        );
+        assert_eq!(
+            compound("Input and/or output must be logged"),
+            ["uses 'and/or'"]
+        );
+    }
+
+    #[test]
+    fn compound_accepts_one_keyword_per_sentence() {
+        assert!(compound("The tool must parse files. It should warn.").is_empty());
+        assert!(compound("The tool must parse files! It should warn? It may stop").is_empty());
+        assert!(compound("The tool must parse files\nIt should warn").is_empty());
+        assert!(compound("The tool must read version 1.2 as described in REQ-3.4").is_empty());
+    }
+}
@@ -0,0 +1,97 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{format, Project, Version};
+
+/// Content hashes of a project at a version, as stored in a lockfile
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Lock {
+    #[serde(
+        serialize_with = "crate::serialize_version",
+        deserialize_with = "crate::deserialize_version"
+    )]
+    pub version: Version,
+    /// The hash of all content except the version
+    pub hash: String,
+    pub requirements: IndexMap<String, String>,
+}
+
+/// A requirement whose content differs from the lockfile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Added(String),
+    Removed(String),
+    Modified(String),
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Change::Added(id) => write!(f, "{id}: added"),
+            Change::Removed(id) => write!(f, "{id}: removed"),
+            Change::Modified(id) => write!(f, "{id}: modified"),
+        }
+    }
+}
+
+/// The hex encoded SHA-256 hash of `bytes`
+#[must_use]
+pub fn hash(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+fn sha256(value: &impl Serialize) -> String {
+    hash(&serde_json::to_vec(value).expect("Project content serializes to JSON"))
+}
+
+/// Hashes the canonical form of `project`, so formatting changes do not affect the hashes
+#[must_use]
+pub fn lock(project: &Project) -> Lock {
+    let mut project = project.clone();
+    format::canonicalize(&mut project);
+    let mut requirements = IndexMap::new();
+    project.visit_requirements(|r| {
+        requirements.insert(r.id.to_string(), sha256(r.requirement));
+    });
+    let mut content = serde_json::to_value(&project).expect("Project serializes to JSON");
+    if let Some(content) = content.as_object_mut() {
+        content.remove("version");
+    }
+    Lock {
+        version: project.version,
+        hash: sha256(&content),
+        requirements,
+    }
+}
+
+/// Lists the requirements that were added, removed or modified since `old`
+#[must_use]
+pub fn changes(old: &Lock, new: &Lock) -> Vec<Change> {
+    changed_hashes(&old.requirements, &new.requirements)
+}
+
+/// Compares two maps of requirement IDs to hashes
+#[must_use]
+pub(crate) fn changed_hashes(
+    old: &IndexMap<String, String>,
+    new: &IndexMap<String, String>,
+) -> Vec<Change> {
+    let mut changes = Vec::new();
+    for (id, hash) in new {
+        match old.get(id) {
+            None => changes.push(Change::Added(id.clone())),
+            Some(old_hash) if old_hash != hash => changes.push(Change::Modified(id.clone())),
+            Some(_) => {}
+        }
+    }
+    for id in old.keys() {
+        if !new.contains_key(id) {
+            changes.push(Change::Removed(id.clone()));
+        }
+    }
+    changes
+}
@@ -0,0 +1,406 @@
+use std::collections::HashSet;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+
+use crate::format::{self, Overrides, ParseError};
+use crate::{lint, oslc, Project};
+
+const ERROR: u8 = 1;
+const WARNING: u8 = 2;
+
+/// Reads one message framed by a `Content-Length` header, `None` at the end of the input
+fn read_message(input: &mut impl BufRead) -> anyhow::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = Some(value.trim().parse::<usize>()?);
+            }
+        }
+    }
+    let Some(length) = length else {
+        anyhow::bail!("Message without Content-Length header");
+    };
+    let mut content = vec![0; length];
+    input.read_exact(&mut content)?;
+    Ok(Some(serde_json::from_slice(&content)?))
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> anyhow::Result<()> {
+    let content = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{content}", content.len())?;
+    output.flush()?;
+    Ok(())
+}
+
+fn path_from_uri(uri: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(oslc::percent_decode_path(uri.strip_prefix("file://")?));
+    Some(std::fs::canonicalize(&path).unwrap_or(path))
+}
+
+fn uri_from_path(path: &Path) -> String {
+    let encoded: String = path
+        .to_string_lossy()
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            b => format!("%{b:02X}"),
+        })
+        .collect();
+    format!("file://{encoded}")
+}
+
+/// Converts a byte offset into a position with the character counted in UTF-16 code units
+fn position(content: &str, offset: usize) -> Value {
+    let offset = offset.min(content.len());
+    let before = &content[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    json!({
+        "line": before.matches('\n').count(),
+        "character": before[line_start..].encode_utf16().count(),
+    })
+}
+
+fn line_range(line: usize, content: &str) -> Value {
+    let length = content
+        .lines()
+        .nth(line)
+        .map_or(0, |l| l.encode_utf16().count());
+    json!({
+        "start": { "line": line, "character": 0 },
+        "end": { "line": line, "character": length },
+    })
+}
+
+fn is_id_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_' || c == '.'
+}
+
+/// Finds the requirement ID at the position, which may contain dots but not end with one
+fn word_at(content: &str, line: usize, character: usize) -> Option<String> {
+    let text = content.lines().nth(line)?;
+    let mut units = 0;
+    let index = text
+        .char_indices()
+        .find(|(_, c)| {
+            units += c.len_utf16();
+            units > character
+        })
+        .map_or(text.len(), |(i, _)| i);
+    let start = text[..index]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_id_char(*c))
+        .last()
+        .map_or(index, |(i, _)| i);
+    let end = text[index..]
+        .char_indices()
+        .find(|(_, c)| !is_id_char(*c))
+        .map_or(text.len(), |(i, _)| index + i);
+    let word = text[start..end].trim_matches('.');
+    (!word.is_empty()).then(|| word.to_string())
+}
+
+fn diagnostic(range: Value, severity: u8, code: &str, message: String) -> Value {
+    json!({
+        "range": range,
+        "severity": severity,
+        "source": "req",
+        "code": code,
+        "message": message,
+    })
+}
+
+/// A language server for requirement files, answering JSON-RPC messages from an editor
+pub struct Server {
+    root: PathBuf,
+    /// The content of the open documents, by canonical path
+    documents: Overrides,
+    /// The files diagnostics were last published for, to clear them when they are fixed
+    published: HashSet<PathBuf>,
+}
+
+impl Server {
+    /// Creates a server for the project in `root` and the files it includes
+    #[must_use]
+    pub fn new(root: &Path) -> Server {
+        Server {
+            root: std::fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf()),
+            documents: Overrides::new(),
+            published: HashSet::new(),
+        }
+    }
+
+    fn content(&self, path: &Path) -> Option<String> {
+        match self.documents.get(path) {
+            Some(content) => Some(content.clone()),
+            None => std::fs::read_to_string(path).ok(),
+        }
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = format::source_files(&self.root)
+            .unwrap_or_else(|_| vec![self.root.clone()])
+            .iter()
+            .map(|f| std::fs::canonicalize(f).unwrap_or_else(|_| f.clone()))
+            .collect();
+        for path in self.documents.keys() {
+            if !files.contains(path) {
+                files.push(path.clone());
+            }
+        }
+        files
+    }
+
+    fn project(&self) -> anyhow::Result<Project> {
+        format::load_with(&self.root, &self.documents)
+    }
+
+    /// Finds the file and 0-based line defining a requirement
+    fn definition(&self, id: &str) -> Option<(PathBuf, usize)> {
+        self.files().into_iter().find_map(|file| {
+            let line = format::key_line(&self.content(&file)?, id)?;
+            Some((file, line - 1))
+        })
+    }
+
+    /// Checks the project and lists the problems of every file, parse errors and structural
+    /// problems found by `lint`
+    fn diagnostics(&self) -> IndexMap<PathBuf, Vec<Value>> {
+        let mut diagnostics: IndexMap<_, _> =
+            self.files().into_iter().map(|f| (f, Vec::new())).collect();
+        match self.project() {
+            Ok(project) => {
+                for finding in lint::structure(&project) {
+                    if let Some((file, line)) = self.definition(&finding.id) {
+                        let content = self.content(&file).unwrap_or_default();
+                        diagnostics.entry(file).or_default().push(diagnostic(
+                            line_range(line, &content),
+                            WARNING,
+                            &finding.rule,
+                            format!("{} {}", finding.id, finding.message),
+                        ));
+                    }
+                }
+            }
+            Err(e) => {
+                let (file, offset, message) = match e.downcast_ref::<ParseError>() {
+                    Some(error) => {
+                        let file = std::fs::canonicalize(error.path())
+                            .unwrap_or_else(|_| error.path().to_path_buf());
+                        (file, error.offset(), error.message().to_string())
+                    }
+                    None => (self.root.clone(), None, format!("{e}")),
+                };
+                let content = self.content(&file).unwrap_or_default();
+                let range = match offset {
+                    Some(offset) => json!({
+                        "start": position(&content, offset),
+                        "end": position(&content, offset + 1),
+                    }),
+                    None => line_range(0, &content),
+                };
+                diagnostics
+                    .entry(file)
+                    .or_default()
+                    .push(diagnostic(range, ERROR, "parse", message));
+            }
+        }
+        diagnostics
+    }
+
+    fn publish_diagnostics(&mut self, output: &mut impl Write) -> anyhow::Result<()> {
+        let diagnostics = self.diagnostics();
+        let cleared: Vec<_> = self
+            .published
+            .iter()
+            .filter(|f| !diagnostics.contains_key(*f))
+            .cloned()
+            .collect();
+        for file in cleared {
+            write_message(
+                output,
+                &json!({
+                    "jsonrpc": "2.0",
+                    "method": "textDocument/publishDiagnostics",
+                    "params": { "uri": uri_from_path(&file), "diagnostics": [] },
+                }),
+            )?;
+        }
+        self.published.clear();
+        for (file, diagnostics) in diagnostics {
+            write_message(
+                output,
+                &json!({
+                    "jsonrpc": "2.0",
+                    "method": "textDocument/publishDiagnostics",
+                    "params": { "uri": uri_from_path(&file), "diagnostics": diagnostics },
+                }),
+            )?;
+            self.published.insert(file);
+        }
+        Ok(())
+    }
+
+    /// Finds the requirement ID at the position of a hover or definition request
+    fn id_at(&self, params: &Value) -> Option<String> {
+        let path = path_from_uri(params["textDocument"]["uri"].as_str()?)?;
+        let line = usize::try_from(params["position"]["line"].as_u64()?).ok()?;
+        let character = usize::try_from(params["position"]["character"].as_u64()?).ok()?;
+        word_at(&self.content(&path)?, line, character)
+    }
+
+    fn hover(&self, params: &Value) -> Value {
+        let Some(id) = self.id_at(params) else {
+            return Value::Null;
+        };
+        let Ok(project) = self.project() else {
+            return Value::Null;
+        };
+        let Some((_, requirement)) = project.find_requirement(&id) else {
+            return Value::Null;
+        };
+        let mut text = vec![
+            format!("**{id}** - {}", requirement.name.trim()),
+            String::new(),
+            requirement.description.trim().to_string(),
+        ];
+        if !requirement.additional_info.is_empty() {
+            text.push(String::new());
+            text.extend(
+                requirement
+                    .additional_info
+                    .iter()
+                    .map(|i| format!("- {}", i.trim())),
+            );
+        }
+        if !requirement.links.is_empty() {
+            text.push(String::new());
+            text.push(format!("Links: {}", requirement.links.join(", ")));
+        }
+        let linking = project.linking_to(&[&id]);
+        if !linking.is_empty() {
+            text.push(String::new());
+            text.push(format!("Linked from: {}", linking.join(", ")));
+        }
+        json!({ "contents": { "kind": "markdown", "value": text.join("\n") } })
+    }
+
+    fn goto_definition(&self, params: &Value) -> Value {
+        let Some((file, line)) = self.id_at(params).and_then(|id| self.definition(&id)) else {
+            return Value::Null;
+        };
+        let content = self.content(&file).unwrap_or_default();
+        json!({ "uri": uri_from_path(&file), "range": line_range(line, &content) })
+    }
+
+    /// Handles one message, returning `false` when the editor asked the server to exit
+    fn handle(&mut self, message: &Value, output: &mut impl Write) -> anyhow::Result<bool> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        tracing::debug!("Received {method}");
+        let result = match method {
+            "initialize" => json!({
+                "capabilities": {
+                    "textDocumentSync": { "openClose": true, "change": 1, "save": true },
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                },
+                "serverInfo": { "name": "req", "version": env!("CARGO_PKG_VERSION") },
+            }),
+            "shutdown" => Value::Null,
+            "exit" => return Ok(false),
+            "textDocument/hover" => self.hover(params),
+            "textDocument/definition" => self.goto_definition(params),
+            "textDocument/didOpen"
+            | "textDocument/didChange"
+            | "textDocument/didClose"
+            | "textDocument/didSave" => {
+                if let Some(path) = params["textDocument"]["uri"]
+                    .as_str()
+                    .and_then(path_from_uri)
+                {
+                    let text = match method {
+                        "textDocument/didOpen" => params["textDocument"]["text"].as_str(),
+                        // Full synchronization, so the last change has the whole content
+                        "textDocument/didChange" => params["contentChanges"]
+                            .as_array()
+                            .and_then(|c| c.last())
+                            .and_then(|c| c["text"].as_str()),
+                        _ => None,
+                    };
+                    match text {
+                        Some(text) => {
+                            self.documents.insert(path, text.to_string());
+                        }
+                        None if method == "textDocument/didClose" => {
+                            self.documents.remove(&path);
+                        }
+                        None => {}
+                    }
+                }
+                self.publish_diagnostics(output)?;
+                return Ok(true);
+            }
+            _ => {
+                if !message["id"].is_null() {
+                    write_message(
+                        output,
+                        &json!({
+                            "jsonrpc": "2.0",
+                            "id": message["id"],
+                            "error": { "code": -32601, "message": format!("Unknown method {method}") },
+                        }),
+                    )?;
+                }
+                return Ok(true);
+            }
+        };
+        if !message["id"].is_null() {
+            write_message(
+                output,
+                &json!({ "jsonrpc": "2.0", "id": message["id"], "result": result }),
+            )?;
+        }
+        Ok(true)
+    }
+
+    /// Answers messages from `input` until the editor sends `exit` or closes the input
+    pub fn run(&mut self, mut input: impl BufRead, mut output: impl Write) -> anyhow::Result<()> {
+        while let Some(message) = read_message(&mut input)? {
+            if !self.handle(&message, &mut output)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uris_keep_plus_signs() {
+        let path = Path::new("/no/such/dir/a+b c.yml");
+        assert_eq!(uri_from_path(path), "file:///no/such/dir/a%2Bb%20c.yml");
+        assert_eq!(
+            path_from_uri("file:///no/such/dir/a+b%20c.yml").unwrap(),
+            path
+        );
+        assert_eq!(path_from_uri(&uri_from_path(path)).unwrap(), path);
+    }
+}
@@ -1,21 +1,25 @@
-use std::path::PathBuf;
-
-use clap::{CommandFactory, Parser, Subcommand};
-use indexmap::{
-    map::{Keys, Values},
-    IndexMap,
-};
-use regex::Regex;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use indexmap::{IndexMap, IndexSet};
+use regex::{Regex, RegexBuilder};
+use req::config::Config;
+use req::edit::Sources;
+use req::format::Format;
+use req::lint::TextRule;
+use req::profile::{Labels, Profile, Section, SymbolStyle};
+use req::sort::SortKey;
+use req::workspace::Workspace;
 use req::*;
 use schemars::schema_for;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use stringlit::s;
 
-pub const WORD_DESCRIPTION: &str = //
-    r#"The key words "MUST", "MUST NOT", "REQUIRED", "SHALL", "SHALL NOT", "SHOULD", "SHOULD NOT", "RECOMMENDED",
-"MAY", and "OPTIONAL" in this document are to be interpreted as described in
-[RFC 2119](https://datatracker.ietf.org/doc/html/rfc2119).
-"#;
-
 pub const HIGHLIGHTED_WORDS: [&str; 10] = [
     "must not",
     "must",
@@ -33,129 +37,30 @@ fn nl() -> String {
     s!("")
 }
 
-fn check_requirements(
-    test_results: &str,
-    output: &mut IndexMap<String, (bool, Vec<String>)>,
-    requirements: &IndexMap<String, Requirement>,
-    allowed_requirements: &[Regex],
-) {
-    for (id, _) in requirements {
-        if allowed_requirements.iter().any(|r| r.is_match(id)) {
-            let search_string = format!("{}: failed", id.trim());
-            if test_results.contains(&search_string) {
-                let errors = test_results.lines().filter_map(|l| {
-                    if l.starts_with(&search_string) {
-                        l.split_once(":")
-                            .map(|(_, txt)| txt)
-                            .and_then(|txt| txt.split_once("-").map(|(_, err)| err.to_string()))
-                    } else {
-                        None
-                    }
-                });
-                output.insert(id.trim().to_string(), (false, errors.collect()));
-            } else if test_results.contains(&format!("{}: passed", id.trim())) {
-                output
-                    .entry(id.trim().to_string())
-                    .or_insert((true, Vec::new()));
-            };
-        }
-    }
-}
-
-fn has_valid_requirements(
-    mut requirements: Keys<String, Requirement>,
-    allowed_requirements: &[Regex],
-) -> bool {
-    requirements.any(|id| allowed_requirements.iter().any(|r| r.is_match(id)))
-}
-
-fn has_valid_topics(mut topics: Values<String, Topic>, allowed_requirements: &[Regex]) -> bool {
-    topics.any(|topic| {
-        has_valid_requirements(topic.requirements.keys(), allowed_requirements)
-            || has_valid_topics(topic.subtopics.values(), allowed_requirements)
-    })
-}
-
-fn check_topics(
-    test_results: &[PathBuf],
-    output: &mut Vec<String>,
-    topics: &IndexMap<String, Topic>,
-    allowed_requirements: &[Regex],
-    level: usize,
-) -> anyhow::Result<()> {
-    if !has_valid_topics(topics.values(), allowed_requirements) {
-        return Ok(());
-    }
-    for (id, topic) in topics {
-        if !has_valid_topics(topic.subtopics.values(), allowed_requirements)
-            && !has_valid_requirements(topic.requirements.keys(), allowed_requirements)
-        {
-            continue;
-        }
-        output.push(format!(
-            "{} _{}_ - {}",
-            "#".repeat(level),
-            id.trim(),
-            topic.name
-        ));
-
-        let mut test_status = IndexMap::new();
-        for test_result in test_results {
-            let test_result = std::fs::read_to_string(test_result)?;
-            if !topic.requirements.is_empty() {
-                check_requirements(
-                    &test_result,
-                    &mut test_status,
-                    &topic.requirements,
-                    allowed_requirements,
-                );
-            }
-        }
-
-        if !topic.requirements.is_empty() {
-            for (id, req) in &topic.requirements {
-                let (status, errors) = if let Some((status, errors)) = test_status.get(id) {
-                    if *status {
-                        (":white_check_mark:", errors.to_owned())
-                    } else {
-                        (":x:", errors.to_owned())
-                    }
-                } else {
-                    (":warning:", Vec::new())
-                };
-                output.push(format!("- _{}_ - {}: {status}", id.trim(), req.name));
-                for err in errors {
-                    output.push(format!("  - {}", err.trim()));
-                }
-            }
-
-            output.push(nl());
-        }
-
-        if !topic.subtopics.is_empty() {
-            check_topics(
-                test_results,
-                output,
-                &topic.subtopics,
-                allowed_requirements,
-                level + 1,
-            )?;
-            output.push(nl());
-        }
-    }
-    Ok(())
-}
-
 fn add_requirements(output: &mut Vec<String>, requirements: &IndexMap<String, Requirement>) {
     for (id, requirement) in requirements {
         output.push(format!(
-            "- **_{}_ - {}:** {}",
-            id.trim(),
-            requirement.name.trim(),
+            "- {} {}",
+            strong(&format!("{} - {}:", em(id.trim()), requirement.name.trim())),
             requirement.description.trim()
         ));
         for info in &requirement.additional_info {
-            output.push(format!("  - {}", info.trim(),));
+            let info = info.trim();
+            // Escaped so no information reads as one of the labelled fields below
+            if info.starts_with(['*', '_', '\\']) {
+                output.push(format!("  - \\{info}"));
+            } else {
+                output.push(format!("  - {info}"));
+            }
+        }
+        if let Some(priority) = requirement.priority {
+            let label = strong(&format!("{}:", labels().priority));
+            output.push(format!("  - {label} {priority}"));
+        }
+        if !requirement.links.is_empty() {
+            let links: Vec<_> = requirement.links.iter().map(|l| em(l.trim())).collect();
+            let label = strong(&format!("{}:", labels().links));
+            output.push(format!("  - {label} {}", links.join(", ")));
         }
     }
 }
@@ -163,9 +68,9 @@ fn add_requirements(output: &mut Vec<String>, requirements: &IndexMap<String, Re
 fn add_topics(output: &mut Vec<String>, topics: &IndexMap<String, Topic>, level: usize) {
     for (id, topic) in topics {
         output.push(format!(
-            "{} _{}_ - {}",
+            "{} {} - {}",
             "#".repeat(level),
-            id.trim(),
+            em(id.trim()),
             topic.name.trim()
         ));
         if !topic.requirements.is_empty() {
@@ -188,101 +93,1464 @@ enum Command {
     /// Transform requirements into Markdown
     Markdown {
         /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
         requirements: PathBuf,
+        /// Use all projects of a workspace instead, defaults to the configured or closest workspace file
+        #[arg(long, value_name = "FILE", num_args = 0..=1)]
+        workspace: Option<Option<PathBuf>>,
+        /// Add the commit that last changed each requirement, from the git history
+        #[arg(long, conflicts_with = "workspace")]
+        blame: bool,
+        /// Render with the filters, sections and styles of a profile configured under `profiles`
+        #[arg(long)]
+        profile: Option<String>,
+        /// Only include requirements with at least this priority, those without one count as low
+        #[arg(long, value_enum)]
+        min_priority: Option<Priority>,
     },
     /// Transform requirements into HTML
     Html {
         /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// Add the commit that last changed each requirement, from the git history
+        #[arg(long)]
+        blame: bool,
+        /// Render with the filters, sections, template and styles of a profile configured under
+        /// `profiles`
+        #[arg(long)]
+        profile: Option<String>,
+        /// Only include requirements with at least this priority, those without one count as low
+        #[arg(long, value_enum)]
+        min_priority: Option<Priority>,
+    },
+    /// Serve the requirements as HTML that reloads when the file changes
+    Serve {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The port to listen on
+        #[arg(short, long, default_value_t = 8080)]
+        port: u16,
+        /// Also serve the requirements as OSLC RM resources below `/oslc`, starting at `/oslc/catalog`
+        #[arg(long)]
+        oslc: bool,
+        /// Also serve a JSON API at `/requirements`, `/requirements/{id}`, `/check` and
+        /// `/render/markdown`
+        #[arg(long)]
+        api: bool,
+        /// Reload the requirements for the API when their files change instead of serving the
+        /// state at startup
+        #[arg(long, requires = "api")]
+        watch: bool,
+        /// Test output files or directories for `/check`, defaults to `test_results` of the config
+        #[arg(short, long, num_args=1.., requires = "api")]
+        tests: Vec<PathBuf>,
+    },
+    /// Run a language server on stdin and stdout, for live diagnostics, hover information and
+    /// go to definition of requirement IDs in editors
+    Lsp {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+    },
+    /// Regenerate an output file whenever the requirements change
+    Watch {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
         requirements: PathBuf,
+        /// The output to generate
+        #[arg(short, long, value_enum)]
+        cmd: WatchOutput,
+        /// The path to write the output to
+        #[arg(short, long)]
+        output: PathBuf,
     },
     /// Check test output against requirements
     Check {
-        #[arg(short, long, default_value = "REQ-.*")]
+        #[arg(short, long, default_values_t = default_allowed_requirements())]
         /// Regex to select which requirements should be checked
         allowed_requirements: Vec<String>,
         /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
         requirements: PathBuf,
-        /// The path to the test output files
-        #[arg(required=true, num_args=1..)]
+        /// The path to the test output files, defaults to `test_results` of the config
         test_results: Vec<PathBuf>,
+        /// Check all projects of a workspace against their own test results instead
+        ///
+        /// Defaults to the configured or closest workspace file.
+        #[arg(long, value_name = "FILE", num_args = 0..=1)]
+        workspace: Option<Option<PathBuf>>,
+        /// Only check requirements with at least this priority, those without one count as low
+        #[arg(long, value_enum)]
+        min_priority: Option<Priority>,
+    },
+    /// Merge several requirement files into one
+    Merge {
+        /// The paths to the requirement files, the first one provides the project metadata
+        #[arg(required=true, num_args=2..)]
+        requirements: Vec<PathBuf>,
+        /// The path to write the merged requirements to, defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Create a new requirements file to start from
+    Init {
+        /// The name of the project
+        #[arg(short, long)]
+        name: String,
+        /// The format of the requirements file
+        #[arg(short, long, value_enum, default_value = "yaml")]
+        format: Format,
+        /// The path of the requirements file, defaults to `requirements.<format>`
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Also write a `.easyreq.toml` config next to the requirements file
+        #[arg(short, long)]
+        config: bool,
+    },
+    /// Add a requirement to a requirements file
+    Add {
+        /// The ID of the new requirement, or `auto` for the next free ID of the configured
+        /// `id_scheme`
+        id: String,
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The topic to add the requirement to, nested topics are separated by `/`
+        #[arg(short, long)]
+        topic: String,
+        /// The name of the requirement, prompted for if missing
+        #[arg(short, long)]
+        name: Option<String>,
+        /// The description of the requirement, prompted for if missing
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Additional information, can be given multiple times
+        #[arg(short, long)]
+        additional_info: Vec<String>,
+        #[command(flatten)]
+        edit: EditOptions,
+    },
+    #[clap(alias = "rm")]
+    /// Remove a requirement or topic from a requirements file
+    Remove {
+        /// The ID of the requirement or topic to remove
+        id: String,
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// Remove even if other requirements link to it
+        #[arg(short, long)]
+        force: bool,
+        #[command(flatten)]
+        edit: EditOptions,
+    },
+    /// Convert requirements into another format
+    Convert {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The format to convert to
+        #[arg(short, long, value_enum)]
+        to: Format,
+        /// The path to write the converted requirements to, defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Print statistics about the requirements
+    Stats {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: ReportFormat,
+    },
+    /// Score the testability and ambiguity of every requirement and list the worst per topic
+    Quality {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// How many requirements to list per topic
+        #[arg(long, default_value_t = 5)]
+        top: usize,
+        /// The output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: ReportFormat,
+    },
+    /// Search requirements by ID, name, description and additional information
+    Search {
+        /// The text to search for, case-insensitive
+        query: String,
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// Interpret the query as a regular expression
+        #[arg(short, long)]
+        regex: bool,
+    },
+    /// Sort topics and requirements in a requirements file
+    Sort {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// What to sort by
+        #[arg(short, long, value_enum, default_value = "id")]
+        by: SortKey,
+        #[command(flatten)]
+        edit: EditOptions,
+    },
+    /// Renumber all requirement IDs and update the links pointing to them, in the requirements
+    /// file and all files it includes
+    Reid {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The prefix of the new IDs
+        #[arg(short, long, default_value = "REQ-")]
+        prefix: String,
+        /// The number of the first requirement
+        #[arg(short, long, default_value_t = 1)]
+        start: u64,
+        /// The difference between consecutive numbers
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+        step: u64,
+        /// The path to write the old to new ID mapping to, defaults to stdout
+        #[arg(short, long)]
+        mapping: Option<PathBuf>,
+        #[command(flatten)]
+        edit: EditOptions,
+    },
+    /// Write each top-level topic into its own file, included by a new root file
+    Split {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The directory to write the files to
+        #[arg(short, long)]
+        out_dir: PathBuf,
+    },
+    /// Rewrite requirement files and the files they include in a canonical style
+    Fmt {
+        /// The paths to the requirement files
+        #[arg(required=true, num_args=1..)]
+        requirements: Vec<PathBuf>,
+        /// Only check whether the files are formatted, fail if they are not
+        #[arg(short, long)]
+        check: bool,
+        #[command(flatten)]
+        edit: EditOptions,
+    },
+    /// Trace requirements to test results and source code references
+    Trace {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// Test output files or directories containing them
+        #[arg(short, long, num_args=1..)]
+        tests: Vec<PathBuf>,
+        /// Source files or directories to scan for requirement IDs
+        #[arg(short, long, num_args=1..)]
+        source: Vec<PathBuf>,
+        /// Feature files or directories containing them, whose scenarios are tagged with requirement IDs
+        #[arg(long, num_args=1..)]
+        features: Vec<PathBuf>,
+        /// The output format
+        #[arg(short, long, value_enum, default_value = "markdown")]
+        format: TraceFormat,
+        /// The path to write the output to, defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// List the requirements, tests and code affected by a change to a requirement, following
+    /// links from the requirements that depend on it
+    Impact {
+        /// The ID of the changed requirement
+        id: String,
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// Test output files or directories containing them
+        #[arg(short, long, num_args=1..)]
+        tests: Vec<PathBuf>,
+        /// Source files or directories to scan for requirement IDs
+        #[arg(short, long, num_args=1..)]
+        source: Vec<PathBuf>,
+        /// Feature files or directories containing them, whose scenarios are tagged with requirement IDs
+        #[arg(long, num_args=1..)]
+        features: Vec<PathBuf>,
+        /// The output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: ReportFormat,
+    },
+    /// Report the requirements never mentioned in the Markdown and AsciiDoc documentation
+    Docs {
+        /// Documentation files or directories containing them
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// The path to the requirements file
+        #[arg(short, long, default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The output format, JSON lists the mentions of every requirement
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: ReportFormat,
+        /// Fail if a requirement is not documented
+        #[arg(long)]
+        strict: bool,
+    },
+    /// Generate a change log section from two revisions of the requirements
+    Changelog {
+        /// The path to the old requirements file
+        old: PathBuf,
+        /// The path to the new requirements file
+        new: PathBuf,
+    },
+    /// Increment the version of the project
+    Bump {
+        /// The part of the version to increment
+        #[arg(value_enum)]
+        part: VersionPart,
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// Append the changes since `--since` to this change log file
+        #[arg(short, long, requires = "since")]
+        changelog: Option<PathBuf>,
+        /// The previous revision of the requirements file to compute the changes against
+        #[arg(short, long)]
+        since: Option<PathBuf>,
+        #[command(flatten)]
+        edit: EditOptions,
+    },
+    /// Output the topic hierarchy and requirement links as a graph
+    Graph {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The output format
+        #[arg(short, long, value_enum, default_value = "dot")]
+        format: GraphFormat,
+    },
+    /// Import requirements from other tools
+    Import {
+        #[command(subcommand)]
+        source: ImportSource,
+    },
+    /// Export requirements for other tools
+    Export {
+        #[command(subcommand)]
+        target: ExportTarget,
+    },
+    /// Extract values from the requirements with a path expression
+    ///
+    /// Paths consist of `.key`, `."quoted.key"` and `[index]` segments and can be
+    /// followed by the filters `keys`, `values` and `length`, separated by `|`,
+    /// e.g. `.topics."TOPIC-1".requirements | keys`.
+    Query {
+        /// The expression selecting the value
+        expression: String,
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The output format
+        #[arg(short, long, value_enum, default_value = "json")]
+        format: QueryFormat,
+    },
+    /// Show a single requirement
+    Show {
+        /// The ID of the requirement
+        id: String,
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// Test output files or directories to determine the status from
+        #[arg(short, long, num_args=1..)]
+        tests: Vec<PathBuf>,
+        /// The output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: ReportFormat,
+    },
+    /// List all requirements, one tab-separated line per requirement
+    List {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// Regex to select which requirement IDs are listed
+        #[arg(short, long)]
+        filter: Option<String>,
+    },
+    /// Check that all URLs mentioned in the requirements are reachable
+    VerifyLinks {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The maximum number of parallel requests
+        #[arg(short, long, default_value_t = 8)]
+        concurrency: usize,
+        /// The timeout per request in seconds
+        #[arg(short, long, default_value_t = 10)]
+        timeout: u64,
+    },
+    /// Check requirements for structural problems and, optionally, weak wording
+    Lint {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// Also check the wording of the descriptions
+        #[arg(short, long)]
+        text: bool,
+        /// The wording rules to check, defaults to all except `ears`
+        #[arg(short, long, value_enum)]
+        rule: Vec<TextRule>,
+        /// Wording rules to skip
+        #[arg(short, long, value_enum)]
+        skip: Vec<TextRule>,
+        /// Also flag pairs of requirements whose descriptions are at least this similar, from
+        /// 0 to 1, as possible duplicates or contradictions
+        #[arg(long, value_name = "THRESHOLD", num_args = 0..=1, default_missing_value = "0.8")]
+        similarity: Option<f64>,
+        /// Flag the requirements following no EARS template, see `req ears` for the pattern of
+        /// each
+        #[arg(long)]
+        ears: bool,
+    },
+    /// Print the EARS pattern of every requirement, `none` for those following no template
+    Ears {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+    },
+    /// Generate test skeletons that report results in the format `check` expects
+    Stubs {
+        /// The language to generate the tests in
+        #[arg(value_enum)]
+        language: stubs::Language,
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The path to write the tests to, defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write a Gherkin feature file with a scenario per requirement for each topic
+    Gherkin {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The directory to write the feature files to
+        #[arg(short, long)]
+        out_dir: PathBuf,
+    },
+    /// Store content hashes of the requirements in a lockfile
+    Sign {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The path to the lockfile, defaults to the requirements file with a `.lock` extension
+        #[arg(short, long)]
+        lockfile: Option<PathBuf>,
+    },
+    /// Fail if the requirements changed since they were signed without a version bump
+    Verify {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The path to the lockfile, defaults to the requirements file with a `.lock` extension
+        #[arg(short, long)]
+        lockfile: Option<PathBuf>,
+    },
+    /// Print a content hash per requirement and a digest over all of them
+    ///
+    /// Hashes ignore how text is wrapped, so they only change with the content. Compare against
+    /// the fingerprints of the previous commit to require a version bump or change log entry.
+    Fingerprint {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: ReportFormat,
+        /// Fingerprints written with `--format json` to compare against
+        #[arg(long)]
+        compare: Option<PathBuf>,
+        /// A change log that may mention the changed requirements instead of a version bump
+        #[arg(long, requires = "compare")]
+        changelog: Option<PathBuf>,
+    },
+    /// Show the commit, author and date that last changed each requirement, from the git history
+    Blame {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: ReportFormat,
+    },
+    /// Freeze the requirements under a name and compare against it later
+    Baseline {
+        #[command(subcommand)]
+        action: BaselineAction,
+    },
+    /// Write all documents and reports for a release into a directory, with a manifest
+    ///
+    /// The PDF is rendered from the HTML by the program given with `--pdf-command`. Without it
+    /// the bundle has no PDF, and the manifest lists the missing file under `omitted`.
+    Release {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The directory to write the artifacts to
+        #[arg(short, long, default_value_os_t = default_out_dir())]
+        out_dir: PathBuf,
+        /// Test output files or directories containing them, enables the check report
+        #[arg(short, long, num_args=1..)]
+        tests: Vec<PathBuf>,
+        /// Source files or directories to scan for requirement IDs
+        #[arg(short, long, num_args=1..)]
+        source: Vec<PathBuf>,
+        /// Regex to select which requirements should be checked
+        #[arg(short, long, default_values_t = default_allowed_requirements())]
+        allowed_requirements: Vec<String>,
+        /// A program converting HTML to PDF, called with the paths of the HTML file and the PDF
+        /// to write, like `weasyprint` or `wkhtmltopdf --quiet`
+        #[arg(long)]
+        pdf_command: Option<String>,
+    },
+    /// Package the documents certification assessors ask for into one hashed archive
+    ///
+    /// The archive contains the requirements specification, traceability matrix, verification
+    /// report, open items, change history from the baselines and a manifest with the hashes of
+    /// all documents, named after the work products of the chosen standard.
+    Certpack {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The directory to write the archive to
+        #[arg(short, long, default_value_os_t = default_out_dir())]
+        out_dir: PathBuf,
+        /// The standard the documents are prepared for
+        #[arg(long, value_enum, default_value = "generic")]
+        standard: certpack::Standard,
+        /// Test output files or directories containing them
+        #[arg(short, long, num_args=1..)]
+        tests: Vec<PathBuf>,
+        /// Source files or directories to scan for requirement IDs
+        #[arg(short, long, num_args=1..)]
+        source: Vec<PathBuf>,
+        /// Regex to select which requirements should be checked
+        #[arg(short, long, default_values_t = default_allowed_requirements())]
+        allowed_requirements: Vec<String>,
+    },
+    /// Open the file defining a requirement in `$VISUAL` or `$EDITOR` at its line
+    Edit {
+        /// The ID of the requirement
+        id: String,
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+    },
+    /// Move requirements into the archive so their IDs are kept but never reused
+    Archive {
+        /// The IDs of the requirements, `*` matches any text and `?` any character, like
+        /// `REQ-07*` for all IDs starting with `REQ-07`
+        #[arg(required=true, num_args=1..)]
+        ids: Vec<String>,
+        /// The path to the requirements file
+        #[arg(short, long, default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// Archive requirements even if other requirements link to them
+        #[arg(short, long)]
+        force: bool,
+        #[command(flatten)]
+        edit: EditOptions,
+    },
+    /// Change all requirements whose ID matches a regex at once, writing the file a single time
+    Apply {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// Regex the whole ID of a requirement has to match
+        #[arg(short, long)]
+        filter: String,
+        /// Replace a field, like `name=New name`, can be given multiple times
+        ///
+        /// Fields are name, description, links and priority, links are separated by commas and
+        /// an empty priority removes it.
+        #[arg(short, long, value_name = "FIELD=VALUE")]
+        set: Vec<String>,
+        /// Add a line of additional information unless it exists, can be given multiple times
+        #[arg(short, long)]
+        append_info: Vec<String>,
+        #[command(flatten)]
+        edit: EditOptions,
+    },
+    /// Generate files for the application from the requirements
+    Codegen {
+        #[command(subcommand)]
+        target: CodegenTarget,
+    },
+    /// Output a JSON schema for the application's config file, built from the config defaults
+    ConfigSchema {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The path to write the schema to, defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Create or update an issue per requirement in an issue tracker
+    Sync {
+        #[command(subcommand)]
+        target: SyncTarget,
+    },
+    /// Publish the rendered requirements to a wiki
+    Publish {
+        #[command(subcommand)]
+        target: PublishTarget,
     },
     /// Generate shell completions
+    ///
+    /// Bash, Zsh and Fish completions also complete requirement IDs for `show`, `edit`, `rm` and `archive`.
     Completions {
         /// The shell to generate the completions for
         #[arg(value_enum)]
         shell: clap_complete_command::Shell,
     },
+    /// Print all requirement IDs for shell completions
+    #[command(name = "complete-ids", hide = true)]
+    CompleteIds {
+        /// The path to the requirements file, defaults to the one of the current directory
+        requirements: Option<PathBuf>,
+    },
+    /// Generate man pages
+    Man {
+        /// The directory to write a page for the CLI and each subcommand to, defaults to the main page on stdout
+        #[arg(short, long)]
+        out_dir: Option<PathBuf>,
+    },
 }
 
-#[derive(Parser)]
-#[command(version)]
-struct Args {
-    #[clap(subcommand)]
-    command: Command,
+#[derive(Subcommand)]
+enum ImportSource {
+    /// Import requirements from a CSV file with one requirement per row
+    Csv {
+        /// The path to the CSV file
+        file: PathBuf,
+        /// Which columns hold which fields, e.g. `id=Column1,name=Title`
+        ///
+        /// Fields are id, name, description, topic, additional_info and links.
+        /// Unmapped fields are read from columns named like the field.
+        #[arg(short, long, value_delimiter = ',')]
+        map: Vec<String>,
+        /// The separator between topic names in the topic column
+        #[arg(long, default_value = "/")]
+        topic_separator: String,
+        /// The name of the project, defaults to the file name
+        #[arg(short, long)]
+        name: Option<String>,
+        /// The path to write the requirements to, defaults to YAML on stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Import requirements from a Jira CSV export, one requirement per issue
+    Jira {
+        /// The path to the CSV file
+        file: PathBuf,
+        /// Which columns hold which fields, e.g. `id=Issue key`
+        ///
+        /// Fields are id, name, description, topic, additional_info and links.
+        /// By default the ID is read from `Requirement ID`, the name from `Summary`, the
+        /// description from `Description` and the topic path from `Component/s`.
+        #[arg(short, long, value_delimiter = ',')]
+        map: Vec<String>,
+        /// The separator between topic names in the topic column
+        #[arg(long, default_value = "/")]
+        topic_separator: String,
+        /// The name of the project, defaults to the file name
+        #[arg(short, long)]
+        name: Option<String>,
+        /// The path to write the requirements to, defaults to YAML on stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Import a DOORS classic module exported as CSV, rebuilding topics from heading levels
+    Doors {
+        /// The path to the CSV file
+        file: PathBuf,
+        /// Which columns hold which object attributes, e.g. `id=Absolute Number`
+        ///
+        /// Fields are id, heading, text and level, read by default from the columns
+        /// `ID`, `Object Heading`, `Object Text` and `Object Level`.
+        #[arg(short, long, value_delimiter = ',')]
+        map: Vec<String>,
+        /// A prefix for the object IDs, e.g. `REQ-` to turn `42` into `REQ-42`
+        #[arg(long, default_value = "")]
+        prefix: String,
+        /// The name of the project, defaults to the file name
+        #[arg(short, long)]
+        name: Option<String>,
+        /// The path to write the requirements to, defaults to YAML on stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Import a Doorstop document directory
+    ///
+    /// Headings become topics, normative items requirements and inactive items are archived.
+    Doorstop {
+        /// The document directory containing `.doorstop.yml`
+        dir: PathBuf,
+        /// The name of the project, defaults to the document prefix
+        #[arg(short, long)]
+        name: Option<String>,
+        /// The path to write the requirements to, defaults to YAML on stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Import a StrictDoc document
+    ///
+    /// Sections become topics and parent and child relations links. Fields without a
+    /// counterpart, like `STATUS`, are kept as additional information.
+    #[command(alias = "strictdoc")]
+    Sdoc {
+        /// The path to the `.sdoc` file
+        file: PathBuf,
+        /// The path to write the requirements to, defaults to YAML on stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Import a ReqIF document, e.g. exported from Polarion
+    ///
+    /// Headings become topics, relations between requirements become links and attributes
+    /// without a matching field are kept as `Name: value` additional information.
+    #[command(alias = "polarion")]
+    Reqif {
+        /// The path to the ReqIF file
+        file: PathBuf,
+        /// The attribute names the requirement fields are read from
+        #[arg(short, long, value_enum, default_value = "polarion")]
+        preset: reqif::Preset,
+        /// Which attributes hold which fields instead, e.g. `id=Identifier`
+        ///
+        /// Fields are id, name, description, heading and additional_info.
+        #[arg(short, long, value_delimiter = ',')]
+        map: Vec<String>,
+        /// The path to write the requirements to, defaults to YAML on stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    #[clap(alias = "md")]
+    /// Import requirements from a Markdown document structured like the `markdown` output
+    Markdown {
+        /// The path to the Markdown file
+        file: PathBuf,
+        /// The path to write the requirements to, defaults to YAML on stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// List the scenarios of feature files tagged with requirement IDs, like `@REQ-1`, and the
+    /// requirements without a scenario
+    Gherkin {
+        /// Feature files or directories containing them
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// The path to the requirements file
+        #[arg(short, long, default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: ReportFormat,
+        /// Fail if a requirement has no scenario
+        #[arg(long)]
+        strict: bool,
+    },
 }
 
-fn parse(value: &str) -> anyhow::Result<Project> {
-    Ok(serde_yaml::from_str(value)
-        .or_else(|_| serde_json::from_str(value))
-        .or_else(|_| rsn::from_str(value))
-        .or_else(|_| toml::from_str(value))?)
+#[derive(Subcommand)]
+enum ExportTarget {
+    /// Write one story per requirement as CSV for Jira's CSV importer
+    Jira {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// Which columns to write the fields to, e.g. `id=Custom field (Req)`
+        ///
+        /// Fields are id, name, description, topic, additional_info and links.
+        /// By default the ID goes to `Requirement ID`, the name to `Summary`, the description
+        /// to `Description` and the topic names to `Component/s`.
+        #[arg(short, long, value_delimiter = ',')]
+        map: Vec<String>,
+        /// The separator between topic names in the topic column
+        #[arg(long, default_value = "/")]
+        topic_separator: String,
+        /// The path to write the CSV to, defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write a Doorstop document directory with an item file per topic and requirement
+    ///
+    /// Items are numbered in document order and keep the original ID in an `id` attribute.
+    Doorstop {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The directory to write the document to
+        #[arg(short, long)]
+        out_dir: PathBuf,
+        /// The prefix of the item UIDs
+        #[arg(short, long, default_value = "REQ")]
+        prefix: String,
+        /// The separator between prefix and number
+        #[arg(long, default_value = "")]
+        sep: String,
+        /// The minimum number of digits of the item numbers
+        #[arg(long, default_value_t = 3)]
+        digits: usize,
+    },
+    /// Write a StrictDoc document with a section per topic
+    #[command(alias = "strictdoc")]
+    Sdoc {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The path to write the document to, defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write reStructuredText with a Sphinx-needs directive per requirement
+    ///
+    /// IDs like `REQ-1.1` need a matching `needs_id_regex` in the Sphinx configuration.
+    SphinxNeeds {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The need type directive to use
+        #[arg(short, long, default_value = "req")]
+        directive: String,
+        /// The path to write the document to, defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write a ReqIF document as exchanged with Polarion and other ALM tools
+    #[command(alias = "polarion")]
+    Reqif {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The attribute names to use for the requirement fields
+        #[arg(short, long, value_enum, default_value = "polarion")]
+        preset: reqif::Preset,
+        /// Which attributes to write the fields to instead, e.g. `id=Identifier`
+        ///
+        /// Fields are id, name, description, heading and additional_info.
+        #[arg(short, long, value_delimiter = ',')]
+        map: Vec<String>,
+        /// The path to write the document to, defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write one JSON record per requirement and line, for search indexes and review tools
+    ///
+    /// Records have the fields record_id, id, project, version, topic_path, topic_names, name,
+    /// text, additional_info, links, linked_from, chunk and chunks.
+    Jsonl {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// Split descriptions longer than this many characters into several records
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        chunk_size: Option<u64>,
+        /// The path to write the records to, defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Create a Notion database with an entry per requirement and a page per top-level topic
+    ///
+    /// Entries are tagged with the names of the requirement's topics. Every export creates
+    /// a new database and new pages.
+    Notion {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The ID of the page to create the database and pages in, which must be shared with the integration
+        #[arg(long)]
+        parent: String,
+        /// Test output files or directories containing them, to set the status of the entries
+        #[arg(short, long, num_args=1..)]
+        tests: Vec<PathBuf>,
+        /// The token of the integration, defaults to `$NOTION_TOKEN`
+        #[arg(long)]
+        token: Option<String>,
+        /// The API to use
+        #[arg(long, default_value = "https://api.notion.com/v1")]
+        api_url: String,
+    },
 }
 
-fn to_markdown(requirements: PathBuf, add_toc: bool) -> anyhow::Result<String> {
-    let project: Project = parse(&std::fs::read_to_string(requirements)?)?;
+#[derive(Subcommand)]
+enum CodegenTarget {
+    /// Environment variables for the config defaults, as `.env.example` or docker-compose snippet
+    Env {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The output format
+        #[arg(short, long, value_enum, default_value = "dotenv")]
+        format: EnvFormat,
+        /// The path to write the output to, defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
 
-    let mut output = vec![format!("# Requirements for {}", project.name.trim()), nl()];
-    if add_toc {
-        output.extend([s!("[[_TOC_]]"), nl()]);
-    }
-    output.extend([
-        WORD_DESCRIPTION.trim().to_string(),
-        nl(),
-        format!("**VERSION: {}**", project.version),
-        nl(),
-        s!("## Description"),
-        project.description.trim().to_string(),
-        nl(),
-    ]);
+#[derive(Subcommand)]
+enum PublishTarget {
+    /// Publish an index page with a child page per top-level topic to Confluence
+    ///
+    /// The IDs of the created pages are stored, so publishing again updates the same pages.
+    Confluence {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The URL of the Confluence site, e.g. `https://example.atlassian.net/wiki`
+        #[arg(long)]
+        url: String,
+        /// The key of the space to publish to
+        #[arg(long)]
+        space: String,
+        /// The ID of the page to publish below, defaults to the top of the space
+        #[arg(long)]
+        parent: Option<String>,
+        /// The user of a Confluence Cloud API token, omit for a personal access token
+        #[arg(long)]
+        user: Option<String>,
+        /// The API token, defaults to `$CONFLUENCE_TOKEN`
+        #[arg(long)]
+        token: Option<String>,
+        /// The file storing the page IDs, defaults to the requirements file with the extension `confluence.json`
+        #[arg(long)]
+        page_ids: Option<PathBuf>,
+        /// Print which pages would be created or updated without changing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
 
-    if !project.topics.is_empty() {
-        output.push(s!("## Requirements"));
-        add_topics(&mut output, &project.topics, 3);
-    }
+#[derive(Subcommand)]
+enum SyncTarget {
+    /// Synchronize with the issues of a GitHub repository
+    ///
+    /// Issues are labelled `requirement` and with the names of the requirement's topics,
+    /// and are found again by a marker in their body.
+    Github {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The repository as `owner/name`
+        #[arg(short, long)]
+        repo: String,
+        /// The API token, defaults to `$GITHUB_TOKEN`
+        #[arg(long)]
+        token: Option<String>,
+        /// The API to use, e.g. the one of a GitHub Enterprise server
+        #[arg(long, default_value = "https://api.github.com")]
+        api_url: String,
+        /// Print which issues would be created or updated without changing them
+        #[arg(long)]
+        dry_run: bool,
+        /// Print the state of the issue of each requirement and write it into the requirement
+        /// instead of synchronizing
+        #[arg(long, conflicts_with = "dry_run")]
+        status: bool,
+    },
+    /// Synchronize with the issues of a GitLab project
+    ///
+    /// Issues are labelled `requirement` and with the names of the requirement's topics,
+    /// and are found again by a marker in their description.
+    Gitlab {
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// The project as `group/name` or numeric ID
+        #[arg(short, long)]
+        project: String,
+        /// The access token, defaults to `$GITLAB_TOKEN`
+        #[arg(long)]
+        token: Option<String>,
+        /// The API to use, e.g. the one of a self-managed instance
+        #[arg(long, default_value = "https://gitlab.com/api/v4")]
+        api_url: String,
+        /// Print which issues would be created or updated without changing them
+        #[arg(long)]
+        dry_run: bool,
+        /// Print the state of the issue of each requirement and write it into the requirement
+        /// instead of synchronizing
+        #[arg(long, conflicts_with = "dry_run")]
+        status: bool,
+    },
+}
 
-    if !project.definitions.is_empty() {
-        output.push(s!("## Definitions"));
-        for definition in project.definitions {
-            output.push(format!(
-                "- {}: {}",
-                definition.name.trim(),
-                definition.value.trim()
-            ));
-            for info in definition.additional_info {
-                output.push(format!("  - {}", info.trim()))
-            }
-        }
+#[derive(ValueEnum, Clone, Copy)]
+enum EnvFormat {
+    Dotenv,
+    Compose,
+}
+
+#[derive(Subcommand)]
+enum BaselineAction {
+    /// Snapshot the requirements and their content hashes under a name
+    Create {
+        /// The name of the baseline, e.g. `v1.2.0`
+        name: String,
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+        /// Replace an existing baseline with the same name
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Report the requirements that changed since a baseline
+    Compare {
+        /// The name of the baseline
+        name: String,
+        /// The path to the requirements file
+        #[arg(default_value_os_t = default_requirements())]
+        requirements: PathBuf,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum WatchOutput {
+    #[value(alias = "md")]
+    Markdown,
+    Html,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum TraceFormat {
+    #[value(alias = "md")]
+    Markdown,
+    Csv,
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum GraphFormat {
+    Dot,
+    Mermaid,
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum QueryFormat {
+    Json,
+    Yaml,
+    /// Strings without quotes, one line per array item
+    Plain,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum ReportFormat {
+    Text,
+    Json,
+}
+
+#[derive(Serialize)]
+struct RequirementDetails<'a> {
+    id: &'a str,
+    topic_path: Vec<String>,
+    topic_names: Vec<String>,
+    #[serde(flatten)]
+    requirement: &'a Requirement,
+    linked_from: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<trace::Status>,
+}
+
+/// Options for commands that edit requirement files in place
+#[derive(clap::Args)]
+struct EditOptions {
+    /// Print a unified diff of the changes instead of writing them
+    #[arg(long)]
+    dry_run: bool,
+    /// Copy each file to `<file>.bak` before changing it
+    #[arg(long, conflicts_with = "dry_run")]
+    backup: bool,
+}
+
+impl EditOptions {
+    /// Writes the edited project back into its file, keeping the formatting of the unchanged parts
+    fn save<T: Serialize + DeserializeOwned>(
+        &self,
+        path: &Path,
+        format: Format,
+        project: &T,
+    ) -> anyhow::Result<()> {
+        let original = std::fs::read_to_string(path)?;
+        self.write(path, &preserve::update(&original, format, project)?)
+    }
+
+    /// Writes every edited file of a project back, included files as fragments
+    fn save_sources(&self, sources: &Sources) -> anyhow::Result<()> {
+        for source in sources.changed() {
+            if source.is_root() {
+                self.save(&source.path, source.format, &source.project)?;
+            } else {
+                let fragment = Fragment::from(source.project.clone());
+                self.save(&source.path, source.format, &fragment)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write(&self, path: &Path, content: &str) -> anyhow::Result<()> {
+        if self.dry_run {
+            let original = std::fs::read_to_string(path).unwrap_or_default();
+            let diff = similar::TextDiff::from_lines(&original, content);
+            let name = path.display().to_string();
+            print!(
+                "{}",
+                diff.unified_diff()
+                    .header(&format!("a/{name}"), &format!("b/{name}"))
+            );
+            return Ok(());
+        }
+        if self.backup && path.exists() {
+            let mut backup = path.as_os_str().to_owned();
+            backup.push(".bak");
+            std::fs::copy(path, backup)?;
+        }
+        // Write next to the file and rename, so an interrupted write never leaves half a file
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        std::fs::write(&temporary, content)?;
+        std::fs::rename(&temporary, path)?;
+        Ok(())
+    }
+}
+
+#[derive(Parser)]
+#[command(version)]
+struct Args {
+    #[clap(subcommand)]
+    command: Command,
+    /// Log more details to stderr, repeat for even more
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+    /// Only log errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+    /// Redact topics and requirements classified above this level in everything that is read
+    #[arg(long, global = true, value_enum)]
+    max_classification: Option<Classification>,
+    /// Make sure generated files depend on their inputs only: fail if SOURCE_DATE_EPOCH is not
+    /// a valid timestamp, even for commands that embed none, and rule out `--current-time`.
+    /// Timestamps come from SOURCE_DATE_EPOCH or the Unix epoch either way
+    #[arg(long, global = true, conflicts_with = "current_time")]
+    reproducible: bool,
+    /// Embed the current time in generated files instead of the Unix epoch, unless
+    /// SOURCE_DATE_EPOCH is set
+    #[arg(long, global = true)]
+    current_time: bool,
+}
+
+static MAX_CLASSIFICATION: OnceLock<Classification> = OnceLock::new();
+static CURRENT_TIME: OnceLock<bool> = OnceLock::new();
+
+/// The time to embed in generated files, SOURCE_DATE_EPOCH or the Unix epoch unless
+/// `--current-time` is given
+fn timestamp() -> anyhow::Result<u64> {
+    reproducible::timestamp(CURRENT_TIME.get().copied().unwrap_or_default())
+}
+
+/// Loads the project, redacting content classified above `--max-classification`
+fn load(path: &Path) -> anyhow::Result<Project> {
+    let mut project = format::load(path)?;
+    if let Some(max) = MAX_CLASSIFICATION.get() {
+        let redacted = redact::redact(&mut project, *max);
+        tracing::info!("Redacted {redacted} requirements classified above {max}");
+    }
+    Ok(project)
+}
+
+fn load_for_edit(path: &Path) -> anyhow::Result<(Project, Format)> {
+    format::parse_file_with_format(path)
+}
+
+/// Replaces the patterns among `ids` with the IDs of all requirements they match, `*` standing
+/// for any text and `?` for any character
+fn expand_ids(project: &Project, ids: &[String]) -> anyhow::Result<Vec<String>> {
+    let mut all = Vec::new();
+    project.visit_requirements(|r| all.push(r.id.to_string()));
+    let mut expanded = IndexSet::new();
+    for id in ids {
+        let id = id.trim();
+        if !id.contains(['*', '?']) {
+            if !all.iter().any(|a| a == id) {
+                anyhow::bail!(
+                    "No requirement with ID {id}, use `*` or `?` to match several IDs, like `REQ-07*`"
+                );
+            }
+            expanded.insert(id.to_string());
+            continue;
+        }
+        let pattern = regex::escape(id).replace(r"\*", ".*").replace(r"\?", ".");
+        let pattern = Regex::new(&format!("^{pattern}$"))?;
+        let matching: Vec<_> = all.iter().filter(|a| pattern.is_match(a)).collect();
+        if matching.is_empty() {
+            anyhow::bail!("No requirement ID matches {id}");
+        }
+        expanded.extend(matching.into_iter().cloned());
+    }
+    Ok(expanded.into_iter().collect())
+}
+
+/// The file name for topic `id`, with everything but letters, digits, `-`, `_` and inner dots
+/// replaced so the file cannot end up outside the directory
+fn topic_file_name(id: &str, format: Format) -> String {
+    let stem: String = id
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let dots = stem.len() - stem.trim_start_matches('.').len();
+    format!(
+        "{}{}.{}",
+        "_".repeat(dots),
+        &stem[dots..],
+        format.extension()
+    )
+}
+
+/// The path of `include`, relative to the directory of `file`, relative to `dir` instead
+fn rebase_include(file: &Path, include: &str, dir: &Path) -> anyhow::Result<String> {
+    let base = file.parent().unwrap_or(Path::new("."));
+    let target = std::fs::canonicalize(base.join(include))
+        .map_err(|e| anyhow::anyhow!("Failed to read the included {include}: {e}"))?;
+    let dir = std::fs::canonicalize(dir)?;
+    let common = target
+        .components()
+        .zip(dir.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut parts = vec![String::from(".."); dir.components().count() - common];
+    for component in target.components().skip(common) {
+        let part = component.as_os_str().to_str().ok_or_else(|| {
+            anyhow::anyhow!("The path of the included {include} is not valid UTF-8")
+        })?;
+        parts.push(part.to_string());
+    }
+    Ok(parts.join("/"))
+}
+
+/// Whether the file at `path` is included by a project rather than a project itself
+fn is_fragment(path: &Path, content: &str) -> bool {
+    match Format::from_path(path) {
+        // RSN checks struct names, so a project never reads as a fragment
+        Some(Format::Rsn) => format::parse_file_with_format::<Fragment>(path).is_ok(),
+        // Any project reads as a fragment too, without its name, version and description
+        Some(format) => format
+            .parse::<serde_yaml::Mapping>(content)
+            .is_ok_and(|keys| {
+                !["name", "version", "description"]
+                    .iter()
+                    .any(|key| keys.contains_key(*key))
+            }),
+        None => false,
+    }
+}
+
+/// The canonical content of a project or included file, parsed by its extension
+///
+/// YAML keeps its comments and layout like in edits. Other formats are rewritten entirely,
+/// which fails if that would drop a comment.
+fn format_file(path: &Path, original: &str) -> anyhow::Result<String> {
+    let (formatted, format) = if is_fragment(path, original) {
+        let (mut fragment, format) = format::parse_file_with_format::<Fragment>(path)?;
+        format::canonicalize_fragment(&mut fragment);
+        (preserve::update(original, format, &fragment)?, format)
+    } else {
+        let (mut project, format) = format::parse_file_with_format::<Project>(path)?;
+        format::canonicalize(&mut project);
+        (preserve::update(original, format, &project)?, format)
+    };
+    if let Some(comment) = preserve::comments(original, format)
+        .into_iter()
+        .find(|comment| !formatted.contains(comment))
+    {
+        anyhow::bail!(
+            "Formatting {} would drop the comment `{comment}`",
+            path.display()
+        );
+    }
+    Ok(formatted)
+}
+
+fn prompt(label: &str) -> anyhow::Result<String> {
+    eprint!("{label}: ");
+    std::io::stderr().flush()?;
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
+
+fn token_or_env(token: Option<String>, var: &str) -> anyhow::Result<String> {
+    match token {
+        Some(token) => Ok(token),
+        None => std::env::var(var)
+            .map_err(|_| anyhow::anyhow!("No API token given, pass --token or set {var}")),
+    }
+}
+
+fn sync_issues(
+    tracker: &impl sync::Tracker,
+    requirements: &Path,
+    dry_run: bool,
+    status: bool,
+) -> anyhow::Result<()> {
+    let project = load(requirements)?;
+    let remote = tracker.issues()?;
+    if status {
+        for (id, issue) in sync::status(&project, &remote) {
+            match issue {
+                Some(issue) => println!("{id}: {} (#{})", issue.state, issue.number),
+                None => println!("{id}: no issue"),
+            }
+        }
+        let mut sources = Sources::open(requirements)?;
+        let mut changed = Vec::new();
+        for source in &mut sources.files {
+            changed.extend(sync::read_state(&mut source.project.topics, &remote));
+        }
+        if !changed.is_empty() {
+            let edit = EditOptions {
+                dry_run: false,
+                backup: false,
+            };
+            edit.save_sources(&sources)?;
+            eprintln!("Changed the state of {}", changed.join(", "));
+        }
+        return Ok(());
+    }
+    let actions = sync::plan(tracker, sync::issues(&project), &remote);
+    let unchanged = actions
+        .iter()
+        .filter(|a| matches!(a, sync::Action::Unchanged(..)))
+        .count();
+    if dry_run {
+        for action in &actions {
+            println!("{action}");
+        }
+    } else {
+        for line in sync::apply(tracker, &actions)? {
+            println!("{line}");
+        }
+        println!("{unchanged} issues unchanged");
+    }
+    Ok(())
+}
+
+fn write_output(output: Option<PathBuf>, content: &str) -> anyhow::Result<()> {
+    if let Some(output) = output {
+        std::fs::write(output, content)?;
+    } else {
+        print!("{content}");
+    }
+    Ok(())
+}
+
+/// Writes the project in the format matching the file extension, YAML by default
+fn write_project(output: Option<PathBuf>, project: &Project) -> anyhow::Result<()> {
+    let format = output
+        .as_deref()
+        .and_then(Format::from_path)
+        .unwrap_or(Format::Yaml);
+    write_output(output, &format.serialize(project)?)
+}
+
+fn project_name(name: Option<String>, file: &Path) -> String {
+    name.unwrap_or_else(|| {
+        file.file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default()
+    })
+}
+
+fn markdown_header(title: &str, add_toc: bool) -> Vec<String> {
+    let mut output = vec![format!("# {} {}", labels().title, title.trim()), nl()];
+    if add_toc {
+        output.extend([s!("[[_TOC_]]"), nl()]);
+    }
+    output.extend([labels().key_words.trim().to_string(), nl()]);
+    output
+}
+
+/// Renders everything below the title, with sections at heading `level`
+fn markdown_sections(project: Project, level: usize) -> Vec<String> {
+    let heading = "#".repeat(level);
+    let labels = labels();
+    let mut output = vec![
+        strong(&format!("{}: {}", labels.version, project.version)),
+        nl(),
+    ];
+
+    if profile().includes(Section::Description) {
+        output.extend([
+            format!("{heading} {}", labels.description),
+            project.description.trim().to_string(),
+            nl(),
+        ]);
+    }
+
+    if !project.topics.is_empty() && profile().includes(Section::Requirements) {
+        output.push(format!("{heading} {}", labels.requirements));
+        add_topics(&mut output, &project.topics, level + 1);
+    }
+
+    if !project.definitions.is_empty() && profile().includes(Section::Definitions) {
+        output.push(format!("{heading} {}", labels.definitions));
+        for definition in project.definitions {
+            output.push(format!(
+                "- {}: {}",
+                definition.name.trim(),
+                definition.value.trim()
+            ));
+            for info in definition.additional_info {
+                output.push(format!("  - {}", info.trim()))
+            }
+        }
         output.push(nl());
     }
 
-    if !project.config_defaults.is_empty() {
-        output.push(s!("## Config Defaults"));
+    if !project.config_defaults.is_empty() && profile().includes(Section::ConfigDefaults) {
+        output.push(format!("{heading} {}", labels.config_defaults));
         for default in project.config_defaults {
-            output.push(format!("- **{}**", default.name.trim()));
-            output.push(format!("  - Type: {}", default.typ.trim()));
+            output.push(format!("- {}", strong(default.name.trim())));
+            output.push(format!("  - {}: {}", labels.typ, default.typ.trim()));
+            if let Some(env) = &default.env {
+                output.push(format!("  - {}: `{}`", labels.env, env.trim()));
+            }
             if let Some(unit) = default.unit {
-                output.push(format!("  - Unit: {}", unit.trim()));
+                output.push(format!("  - {}: {}", labels.unit, unit.trim()));
             }
             if let Some(valid_values) = default.valid_values {
                 output.push(format!(
-                    "  - Valid Values: _{}_",
-                    valid_values.join(", ").trim()
+                    "  - {}: {}",
+                    labels.valid_values,
+                    em(valid_values.join(", ").trim())
                 ));
             }
             if let Some(default_value) = default.default_value {
                 output.push(format!(
-                    "  - Default Value: _{}_{}",
-                    default_value.trim(),
+                    "  - {}: {}{}",
+                    labels.default_value,
+                    em(default_value.trim()),
                     default
                         .hint
                         .map(|h| format!(" {}", h.trim()))
@@ -290,7 +1558,9 @@ fn to_markdown(requirements: PathBuf, add_toc: bool) -> anyhow::Result<String> {
                 ));
             } else {
                 output.push(format!(
-                    "  - **Required**: This value **_MUST_** be provided as a start parameter.{}",
+                    "  - {}: {}{}",
+                    strong(labels.required),
+                    labels.required_hint,
                     default
                         .hint
                         .map(|h| format!(" {}", h.trim()))
@@ -301,57 +1571,1926 @@ fn to_markdown(requirements: PathBuf, add_toc: bool) -> anyhow::Result<String> {
         }
     }
 
-    let mut output = output.join("\n");
-    for word in HIGHLIGHTED_WORDS {
-        output = output.replace(word, &format!("**_{}_**", word.to_uppercase()));
+    output
+}
+
+/// Highlights the key words in one pass, so a phrase is never highlighted twice and words
+/// merely containing a key word, like "mayor", are left alone
+fn highlight_keywords(output: String) -> String {
+    static KEYWORDS: OnceLock<Regex> = OnceLock::new();
+    let keywords = KEYWORDS.get_or_init(|| {
+        // Longer phrases first, so "must not" is not highlighted as "must"
+        let mut words = HIGHLIGHTED_WORDS.to_vec();
+        words.sort_by_key(|w| std::cmp::Reverse(w.len()));
+        Regex::new(&format!(r"\b({})\b", words.join("|"))).expect("Valid regex")
+    });
+    keywords
+        .replace_all(&output, |c: &regex::Captures| {
+            strong(&em(&c[1].to_uppercase()))
+        })
+        .into_owned()
+}
+
+/// Renders the requirements, with the commit that last changed each one if `blame` is set
+fn to_markdown(requirements: &Path, add_toc: bool, blame: bool) -> anyhow::Result<String> {
+    let mut project = load(requirements)?;
+    profile().apply(&mut project)?;
+    if blame {
+        blame::annotate(&mut project, &blame::blame(requirements)?);
+    }
+    Ok(project_markdown(project, add_toc))
+}
+
+fn project_markdown(project: Project, add_toc: bool) -> String {
+    let mut output = markdown_header(&project.name, add_toc);
+    output.extend(markdown_sections(project, 2));
+    highlight_keywords(output.join("\n"))
+}
+
+/// Renders all projects of a workspace into one document with a chapter per project
+fn workspace_markdown(workspace: &Workspace, add_toc: bool) -> anyhow::Result<String> {
+    let mut output = markdown_header(&workspace.name, add_toc);
+    for member in &workspace.projects {
+        let mut project = load(&member.requirements)?;
+        profile().apply(&mut project)?;
+        output.extend([format!("## {}", project.name.trim()), nl()]);
+        output.extend(markdown_sections(project, 3));
+    }
+    Ok(highlight_keywords(output.join("\n")))
+}
+
+/// Renders the test status of all requirements with the title at heading `level`
+fn check_report(
+    requirements: &Path,
+    test_results: &[PathBuf],
+    allowed_requirements: &[String],
+    min_priority: Option<Priority>,
+    level: usize,
+) -> anyhow::Result<String> {
+    let re: Vec<_> = allowed_requirements
+        .iter()
+        .map(|r| Regex::new(r).expect("Invalid regex!"))
+        .collect();
+    let mut project = load(requirements)?;
+    if let Some(min) = min_priority {
+        project.retain_priority(min);
+    }
+    let mut output = vec![format!(
+        "{} Test Results - {}",
+        "#".repeat(level),
+        project.name
+    )];
+    let contents = test_results
+        .iter()
+        .map(|test_result| {
+            tracing::debug!("Scanning {}", test_result.display());
+            std::fs::read_to_string(test_result)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    output.extend(check::report(&project, &contents, &re, level + 1));
+    Ok(output.join("\n"))
+}
+
+fn markdown_to_html(markdown: &str) -> anyhow::Result<String> {
+    markdown::to_html_with_options(markdown, &markdown::Options::gfm())
+        .map_err(|e| anyhow::anyhow!("{e}"))
+}
+
+/// Renders an index page with the description, definitions and config defaults, and a page per top-level topic
+fn confluence_pages(mut project: Project) -> anyhow::Result<Vec<confluence::Page>> {
+    let name = project.name.trim().to_string();
+    let mut pages = Vec::new();
+    for (id, topic) in &project.topics {
+        let mut output = Vec::new();
+        add_topics(
+            &mut output,
+            &IndexMap::from([(id.clone(), topic.clone())]),
+            1,
+        );
+        pages.push(confluence::Page {
+            key: id.trim().to_string(),
+            title: format!("{name}: {}", topic.name.trim()),
+            body: markdown_to_html(&highlight_keywords(output.join("\n")))?,
+            parent: Some(confluence::INDEX.to_string()),
+        });
+    }
+    project.topics.clear();
+    let mut output = vec![labels().key_words.trim().to_string(), nl()];
+    output.extend(markdown_sections(project, 1));
+    let index = confluence::Page {
+        key: confluence::INDEX.to_string(),
+        title: format!("Requirements for {name}"),
+        body: markdown_to_html(&highlight_keywords(output.join("\n")))?
+            + "\n<h1>Requirements</h1>\n<ac:structured-macro ac:name=\"children\" />",
+        parent: None,
+    };
+    pages.insert(0, index);
+    Ok(pages)
+}
+
+fn to_html(requirements: &Path, blame: bool) -> anyhow::Result<String> {
+    let output = to_markdown(requirements, false, blame)?;
+    let template = match profile().template.as_ref().or(config().template.as_ref()) {
+        Some(template) => std::fs::read_to_string(template)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", template.display()))?,
+        None => include_str!("../template.html").to_string(),
+    };
+    Ok(template.replace("{{content}}", &markdown_to_html(&output)?))
+}
+
+fn last_modified(requirements: &Path) -> u128 {
+    format::source_files(requirements)
+        .unwrap_or_else(|_| vec![requirements.to_path_buf()])
+        .iter()
+        .filter_map(|f| std::fs::metadata(f).and_then(|m| m.modified()).ok())
+        .filter_map(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .max()
+        .unwrap_or_default()
+}
+
+/// Completes requirement IDs for the subcommands that take one, using the file on the command line or the default file
+const BASH_ID_COMPLETION: &str = r#"
+_req_ids() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" file="" word
+    case "${COMP_WORDS[1]}" in
+        show|edit|rm|remove|archive)
+            if [[ "$cur" != -* && ( $COMP_CWORD -eq 2 || "${COMP_WORDS[1]}" == archive ) ]]; then
+                for word in "${COMP_WORDS[@]:2}"; do
+                    [[ -f "$word" ]] && file="$word"
+                done
+                COMPREPLY=( $(compgen -W "$(req complete-ids $file 2>/dev/null)" -- "$cur") )
+                [[ ${#COMPREPLY[@]} -gt 0 ]] && return 0
+            fi
+            ;;
+    esac
+    _req "$@"
+}
+
+complete -F _req_ids -o bashdefault -o default req
+"#;
+
+const ZSH_ID_COMPLETION: &str = r#"
+_req_ids() {
+    local complete_ids=0
+    case ${words[2]} in
+        show|edit|rm|remove) (( CURRENT == 3 )) && complete_ids=1 ;;
+        archive) complete_ids=1 ;;
+    esac
+    if (( complete_ids )) && [[ ${words[CURRENT]} != -* ]]; then
+        local file word
+        local -a ids
+        for word in ${words[3,-1]}; do
+            [[ -f $word ]] && file=$word
+        done
+        ids=(${(f)"$(req complete-ids $file 2>/dev/null)"})
+        compadd -a ids && return 0
+    fi
+    _req "$@"
+}
+
+compdef _req_ids req
+"#;
+
+const FISH_ID_COMPLETION: &str = r#"
+function __req_ids
+    set -l file
+    for token in (commandline -opc)[3..-1]
+        test -f $token; and set file $token
+    end
+    req complete-ids $file 2>/dev/null
+end
+
+complete -c req -n "__fish_seen_subcommand_from show edit rm remove archive" -a "(__req_ids)"
+"#;
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+fn config() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+static PROFILE: OnceLock<Profile> = OnceLock::new();
+
+/// The rendering options selected with `--profile`, the defaults if none is selected
+fn profile() -> &'static Profile {
+    PROFILE.get_or_init(Profile::default)
+}
+
+/// Selects the profile called `name`, or the defaults, with `min_priority` taking precedence
+/// over the one of the profile
+fn select_profile(name: Option<String>, min_priority: Option<Priority>) -> anyhow::Result<()> {
+    let mut profile = match name {
+        Some(name) => find_profile(&name)?.clone(),
+        None => Profile::default(),
+    };
+    if min_priority.is_some() {
+        profile.min_priority = min_priority;
+    }
+    PROFILE.set(profile).expect("Profile is only selected once");
+    Ok(())
+}
+
+fn find_profile(name: &str) -> anyhow::Result<&'static Profile> {
+    let Some(profile) = config().profiles.get(name) else {
+        let names: Vec<_> = config().profiles.keys().map(String::as_str).collect();
+        let names = if names.is_empty() {
+            s!("none")
+        } else {
+            names.join(", ")
+        };
+        anyhow::bail!("Unknown profile '{name}', configured are: {names}");
+    };
+    Ok(profile)
+}
+
+fn labels() -> &'static Labels {
+    profile().language.labels()
+}
+
+/// Puts text in italics, unless the profile asks for plain symbols
+fn em(text: &str) -> String {
+    match profile().symbols {
+        SymbolStyle::Emphasized => format!("_{text}_"),
+        SymbolStyle::Plain => text.to_string(),
+    }
+}
+
+/// Puts text in bold, unless the profile asks for plain symbols
+fn strong(text: &str) -> String {
+    match profile().symbols {
+        SymbolStyle::Emphasized => format!("**{text}**"),
+        SymbolStyle::Plain => text.to_string(),
+    }
+}
+
+/// The configured requirements file, or the first existing `requirements.<format>`
+fn default_requirements() -> PathBuf {
+    config().requirements.clone().unwrap_or_else(|| {
+        ["yml", "yaml", "json", "toml", "rsn"]
+            .iter()
+            .map(|e| PathBuf::from(format!("requirements.{e}")))
+            .find(|p| p.exists())
+            .unwrap_or_else(|| PathBuf::from("requirements.yml"))
+    })
+}
+
+fn default_allowed_requirements() -> Vec<String> {
+    let allowed = &config().allowed_requirements;
+    if allowed.is_empty() {
+        vec![s!("REQ-.*")]
+    } else {
+        allowed.clone()
+    }
+}
+
+/// Reads the workspace given on the command line, configured or found in a parent directory
+fn load_workspace(path: Option<PathBuf>) -> anyhow::Result<Workspace> {
+    let path = path
+        .or_else(|| config().workspace.clone())
+        .or_else(|| workspace::find(&std::env::current_dir().ok()?))
+        .ok_or_else(|| anyhow::anyhow!("No workspace file found"))?;
+    Workspace::read(&path)
+}
+
+fn default_out_dir() -> PathBuf {
+    config()
+        .out_dir
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("dist"))
+}
+
+const LIVE_RELOAD_SCRIPT: &str = r#"<script>
+    setInterval(async () => {
+        const response = await fetch("/__version");
+        if (response.ok && (await response.text()) !== "{{version}}") {
+            location.reload();
+        }
+    }, 1000);
+</script>
+"#;
+
+/// Answers a request below `/oslc`, with the resource URIs based on the host the client connected to
+fn oslc_response(
+    request: &tiny_http::Request,
+    requirements: &Path,
+    port: u16,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let host = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Host"))
+        .map_or_else(|| format!("127.0.0.1:{port}"), |h| h.value.to_string());
+    let base = format!("http://{host}");
+    match load(requirements).and_then(|p| oslc::handle(&base, &p, request.url())) {
+        Ok(Some(body)) => tiny_http::Response::from_string(body)
+            .with_header(
+                tiny_http::Header::from_bytes("Content-Type", oslc::CONTENT_TYPE)
+                    .expect("Valid header"),
+            )
+            .with_header(
+                tiny_http::Header::from_bytes("OSLC-Core-Version", oslc::CORE_VERSION)
+                    .expect("Valid header"),
+            ),
+        Ok(None) => tiny_http::Response::from_string("Not found").with_status_code(404),
+        Err(e) => tiny_http::Response::from_string(format!("{e}")).with_status_code(400),
+    }
+}
+
+/// The JSON API of `serve --api`
+struct Api {
+    watch: bool,
+    tests: Vec<PathBuf>,
+    /// The loaded project and the modification time of its files when it was loaded
+    project: Option<(u128, Project)>,
+}
+
+impl Api {
+    fn serves(url: &str) -> bool {
+        let path = url.split_once('?').map_or(url, |(path, _)| path);
+        ["/requirements", "/check", "/render/"]
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+    }
+
+    fn respond(
+        &mut self,
+        request: &tiny_http::Request,
+        requirements: &Path,
+    ) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+        let modified = last_modified(requirements);
+        let stale = match &self.project {
+            Some((loaded, _)) => self.watch && *loaded != modified,
+            None => true,
+        };
+        if stale {
+            match load(requirements) {
+                Ok(project) => self.project = Some((modified, project)),
+                Err(e) => {
+                    return tiny_http::Response::from_string(
+                        serde_json::json!({ "error": format!("{e}") }).to_string(),
+                    )
+                    .with_status_code(500)
+                }
+            }
+        }
+        let (_, project) = self.project.as_ref().expect("Project was loaded");
+        let response = api::handle(
+            project,
+            request.method().as_str(),
+            request.url(),
+            &self.tests,
+            |p| project_markdown(p.clone(), true),
+        );
+        tiny_http::Response::from_string(response.body)
+            .with_status_code(response.status)
+            .with_header(
+                tiny_http::Header::from_bytes("Content-Type", response.content_type)
+                    .expect("Valid header"),
+            )
+    }
+}
+
+fn serve(requirements: &Path, port: u16, oslc: bool, mut api: Option<Api>) -> anyhow::Result<()> {
+    let server = tiny_http::Server::http(("127.0.0.1", port)).map_err(|e| anyhow::anyhow!(e))?;
+    eprintln!(
+        "Serving {} on http://127.0.0.1:{port}",
+        requirements.display()
+    );
+    let html = tiny_http::Header::from_bytes("Content-Type", "text/html; charset=utf-8")
+        .expect("Valid header");
+    for request in server.incoming_requests() {
+        let version = last_modified(requirements).to_string();
+        let response = if request.url() == "/__version" {
+            tiny_http::Response::from_string(version)
+        } else if oslc && (request.url() == "/oslc" || request.url().starts_with("/oslc/")) {
+            oslc_response(&request, requirements, port)
+        } else if let Some(api) = api.as_mut().filter(|_| Api::serves(request.url())) {
+            api.respond(&request, requirements)
+        } else {
+            let script = LIVE_RELOAD_SCRIPT.replace("{{version}}", &version);
+            let page = to_html(requirements, false)
+                .unwrap_or_else(|e| format!("<body><h1>Error</h1><pre>{e:?}</pre></body>"));
+            tiny_http::Response::from_string(page.replace("</body>", &format!("{script}</body>")))
+                .with_header(html.clone())
+        };
+        if let Err(e) = request.respond(response) {
+            eprintln!("Failed to respond: {e}");
+        }
+    }
+    Ok(())
+}
+
+fn watch(requirements: &Path, cmd: WatchOutput, output: &Path) -> ! {
+    let mut generated = None;
+    loop {
+        let modified = last_modified(requirements);
+        if generated != Some(modified) {
+            generated = Some(modified);
+            let content = match cmd {
+                WatchOutput::Markdown => to_markdown(requirements, true, false),
+                WatchOutput::Html => to_html(requirements, false),
+            };
+            match content.and_then(|c| Ok(std::fs::write(output, c)?)) {
+                Ok(()) => eprintln!("Wrote {}", output.display()),
+                Err(e) => eprintln!("Error: {e:?}"),
+            }
+        }
+        std::thread::sleep(std::time::Duration::from_millis(500));
     }
-    Ok(output)
 }
 
 fn main() -> anyhow::Result<()> {
-    let Args { command } = Args::parse();
+    let current_dir = std::env::current_dir()?;
+    // Parsing the arguments needs the config for their defaults, but an invalid config must
+    // not keep the help, the version or completions from being printed
+    let config = config::discover(&current_dir);
+    CONFIG
+        .set(config.as_ref().cloned().unwrap_or_default())
+        .expect("Config is only set once");
+    let Args {
+        command,
+        verbose,
+        quiet,
+        max_classification,
+        reproducible,
+        current_time,
+    } = Args::parse();
+    if let Err(e) = config {
+        if !matches!(
+            command,
+            Command::Schema | Command::Demo | Command::Completions { .. } | Command::Man { .. }
+        ) {
+            return Err(e);
+        }
+        eprintln!("Warning: using the default config. {e}");
+    }
+    if reproducible {
+        reproducible::source_date_epoch()?;
+    }
+    CURRENT_TIME
+        .set(current_time)
+        .expect("Current time is only set once");
+    if let Some(max) = max_classification {
+        MAX_CLASSIFICATION
+            .set(max)
+            .expect("Max classification is only set once");
+    }
+    let level = match (quiet, verbose) {
+        (true, _) => tracing::Level::ERROR,
+        (false, 0) => tracing::Level::WARN,
+        (false, 1) => tracing::Level::INFO,
+        (false, 2) => tracing::Level::DEBUG,
+        (false, _) => tracing::Level::TRACE,
+    };
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .without_time()
+        .init();
+    if let Some(path) = config::find(&current_dir) {
+        tracing::info!("Using config {}", path.display());
+    }
+    let start = std::time::Instant::now();
+    if let Err(e) = run(command) {
+        match e.downcast::<format::ParseError>() {
+            Ok(diagnostic) => {
+                eprintln!("{:?}", miette::Report::new(diagnostic));
+                std::process::exit(1);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    tracing::info!("Finished in {:?}", start.elapsed());
+    Ok(())
+}
+
+fn run(command: Command) -> anyhow::Result<()> {
     match command {
         Command::Demo => {
             println!("{}", serde_yaml::to_string(&demo_project())?);
         }
-        Command::Html { requirements } => {
-            let output = to_markdown(requirements, false)?;
-            let template = include_str!("../template.html");
-            println!(
-                "{}",
-                template.replace(
-                    "{{content}}",
-                    &markdown::to_html_with_options(&output, &markdown::Options::gfm())
-                        .map_err(|e| anyhow::anyhow!("{e}"))?
-                )
-            );
+        Command::Html {
+            requirements,
+            blame,
+            profile,
+            min_priority,
+        } => {
+            select_profile(profile, min_priority)?;
+            println!("{}", to_html(&requirements, blame)?);
+        }
+        Command::Serve {
+            requirements,
+            port,
+            oslc,
+            api,
+            watch,
+            tests,
+        } => {
+            let api = api.then(|| Api {
+                watch,
+                tests: if tests.is_empty() {
+                    config().test_results.clone()
+                } else {
+                    tests
+                },
+                project: None,
+            });
+            serve(&requirements, port, oslc, api)?;
+        }
+        Command::Lsp { requirements } => {
+            lsp::Server::new(&requirements)
+                .run(std::io::stdin().lock(), std::io::stdout().lock())?;
         }
+        Command::Watch {
+            requirements,
+            cmd,
+            output,
+        } => watch(&requirements, cmd, &output),
         Command::Schema => {
             let schema = schema_for!(Project);
             println!("{}", serde_json::to_string_pretty(&schema).unwrap());
         }
-        Command::Markdown { requirements } => {
-            let output = to_markdown(requirements, true)?;
+        Command::Markdown {
+            requirements,
+            workspace,
+            blame,
+            profile,
+            min_priority,
+        } => {
+            select_profile(profile, min_priority)?;
+            let output = match workspace {
+                Some(workspace) => workspace_markdown(&load_workspace(workspace)?, true)?,
+                None => to_markdown(&requirements, true, blame)?,
+            };
             println!("{output}");
         }
+        Command::Check {
+            allowed_requirements,
+            workspace: Some(workspace),
+            min_priority,
+            ..
+        } => {
+            let workspace = load_workspace(workspace)?;
+            let mut output = vec![format!("# Test Results - {}", workspace.name.trim())];
+            for member in &workspace.projects {
+                output.push(nl());
+                output.push(check_report(
+                    &member.requirements,
+                    &member.test_results,
+                    &allowed_requirements,
+                    min_priority,
+                    2,
+                )?);
+            }
+            println!("{}", output.join("\n"));
+        }
         Command::Check {
             allowed_requirements,
             requirements,
             test_results,
+            workspace: None,
+            min_priority,
         } => {
-            let re = allowed_requirements
-                .into_iter()
-                .map(|r| Regex::new(&r).expect("Invalid regex!"));
-            let re: Vec<_> = re.collect();
-            let project: Project = parse(&std::fs::read_to_string(requirements)?)?;
-            let mut output = vec![format!("# Test Results - {}", project.name)];
-            check_topics(&test_results, &mut output, &project.topics, &re, 2)?;
-
-            let output = output.join("\n");
+            let test_results = if test_results.is_empty() {
+                config().test_results.clone()
+            } else {
+                test_results
+            };
+            if test_results.is_empty() {
+                anyhow::bail!("No test output files given or configured");
+            }
+            let output = check_report(
+                &requirements,
+                &test_results,
+                &allowed_requirements,
+                min_priority,
+                1,
+            )?;
             println!("{output}");
         }
-        Command::Completions { shell } => {
-            shell.generate(&mut Args::command(), &mut std::io::stdout());
+        Command::Merge {
+            requirements,
+            output,
+        } => {
+            let mut requirements = requirements.into_iter();
+            let first = requirements
+                .next()
+                .expect("At least two files are required");
+            let mut project = load(&first)?;
+            let mut conflicts = Vec::new();
+            for path in requirements {
+                let other = load(&path)?;
+                conflicts.extend(
+                    merge::merge(&mut project, other)
+                        .into_iter()
+                        .map(|c| format!("{}: {c}", path.display())),
+                );
+            }
+            if !conflicts.is_empty() {
+                for conflict in &conflicts {
+                    eprintln!("{conflict}");
+                }
+                anyhow::bail!("Found {} conflicts while merging", conflicts.len());
+            }
+            write_project(output, &project)?;
+        }
+        Command::Init {
+            name,
+            format,
+            output,
+            config,
+        } => {
+            let output = output
+                .unwrap_or_else(|| PathBuf::from(format!("requirements.{}", format.extension())));
+            let config_path = config.then(|| output.with_file_name(".easyreq.toml"));
+            // Check every path first, so a failure leaves nothing behind
+            for path in std::iter::once(&output).chain(&config_path) {
+                if path.exists() {
+                    anyhow::bail!("{} already exists", path.display());
+                }
+            }
+            std::fs::write(&output, format.serialize(&starter_project(&name))?)?;
+            if let Some(config_path) = config_path {
+                let file_name = output
+                    .file_name()
+                    .map(|f| f.to_string_lossy())
+                    .unwrap_or_default();
+                std::fs::write(
+                    config_path,
+                    format!("requirements = {file_name:?}\nallowed_requirements = [\"REQ-.*\"]\n"),
+                )?;
+            }
+        }
+        Command::Add {
+            id,
+            requirements,
+            topic,
+            name,
+            description,
+            additional_info,
+            edit,
+        } => {
+            let mut sources = Sources::open(&requirements)?;
+            let project = &sources.merged;
+            let topic_path: Vec<_> = topic.split('/').collect();
+            let id = if id == "auto" {
+                let Some(scheme) = &config().id_scheme else {
+                    anyhow::bail!("The ID auto needs an id_scheme in the configuration");
+                };
+                let id = scheme.next(project, &topic_path);
+                println!("{id}");
+                id
+            } else {
+                id
+            };
+            if project.find_requirement(&id).is_some() {
+                anyhow::bail!("Requirement {id} already exists");
+            }
+            if project.is_archived(&id) {
+                anyhow::bail!("Requirement {id} is archived, its ID cannot be reused");
+            }
+            let Some(topic) = sources
+                .topic_file(&topic_path)
+                .and_then(|f| f.project.topic_mut(&topic_path))
+            else {
+                anyhow::bail!("Topic {topic} does not exist");
+            };
+            let name = name.map_or_else(|| prompt("Name"), Ok)?;
+            let description = description.map_or_else(|| prompt("Description"), Ok)?;
+            topic.requirements.insert(
+                id,
+                Requirement {
+                    name,
+                    description,
+                    additional_info,
+                    links: Vec::new(),
+                    classification: None,
+                    priority: None,
+                    state: None,
+                },
+            );
+            edit.save_sources(&sources)?;
+        }
+        Command::Remove {
+            id,
+            requirements,
+            force,
+            edit,
+        } => {
+            let mut sources = Sources::open(&requirements)?;
+            let project = &sources.merged;
+            let mut removed_ids = Vec::new();
+            let is_requirement = project.find_requirement(&id).is_some();
+            if is_requirement {
+                removed_ids.push(id.trim().to_string());
+            } else if let Some(topic) = project.find_topic(&id) {
+                topic.visit_requirements(|id, _| removed_ids.push(id.to_string()));
+            } else {
+                anyhow::bail!("No requirement or topic with ID {id}");
+            }
+            let removed_ids: Vec<_> = removed_ids.iter().map(String::as_str).collect();
+            let linking = project.linking_to(&removed_ids);
+            if !linking.is_empty() {
+                let message = format!("{id} is linked from {}", linking.join(", "));
+                if !force {
+                    anyhow::bail!("{message}, use --force to remove it anyway");
+                }
+                eprintln!("Warning: {message}");
+            }
+            // A topic can be spread over several files
+            for source in &mut sources.files {
+                if is_requirement {
+                    source.project.remove_requirement(&id);
+                } else {
+                    source.project.remove_topic(&id);
+                }
+            }
+            edit.save_sources(&sources)?;
+        }
+        Command::Convert {
+            requirements,
+            to,
+            output,
+        } => {
+            let project = load(&requirements)?;
+            write_output(output, &to.serialize(&project)?)?;
+        }
+        Command::Quality {
+            requirements,
+            top,
+            format,
+        } => {
+            let project = load(&requirements)?;
+            let report = quality::report(&project, top);
+            match format {
+                ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+                ReportFormat::Text => println!("{}", quality::to_markdown(&project, &report)),
+            }
+        }
+        Command::Stats {
+            requirements,
+            format,
+        } => {
+            let project = load(&requirements)?;
+            let stats = stats::stats(&project);
+            match format {
+                ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&stats)?),
+                ReportFormat::Text => {
+                    println!("Topics: {}", stats.topics);
+                    println!("Requirements: {}", stats.requirements);
+                    println!("Definitions: {}", stats.definitions);
+                    println!("Config Defaults: {}", stats.config_defaults);
+                    println!();
+                    println!("Requirements per topic:");
+                    for (topic, count) in &stats.requirements_per_topic {
+                        println!("  {topic}: {count}");
+                    }
+                    println!();
+                    println!("Requirements per depth:");
+                    for (depth, count) in &stats.requirements_per_depth {
+                        println!("  {depth}: {count}");
+                    }
+                    println!();
+                    println!("Requirements per priority:");
+                    for (priority, count) in &stats.requirements_per_priority {
+                        println!("  {priority}: {count}");
+                    }
+                }
+            }
+        }
+        Command::Search {
+            requirements,
+            query,
+            regex,
+        } => {
+            let query = if regex { query } else { regex::escape(&query) };
+            let re = RegexBuilder::new(&query).case_insensitive(true).build()?;
+            let project = load(&requirements)?;
+            project.visit_requirements(|r| {
+                let matches: Vec<_> = [("id", r.id), ("name", r.requirement.name.trim())]
+                    .into_iter()
+                    .chain(
+                        r.requirement
+                            .description
+                            .lines()
+                            .map(|l| ("description", l.trim())),
+                    )
+                    .chain(
+                        r.requirement
+                            .additional_info
+                            .iter()
+                            .map(|i| ("additional info", i.trim())),
+                    )
+                    .filter(|(_, text)| re.is_match(text))
+                    .collect();
+                if !matches.is_empty() {
+                    println!(
+                        "{} > {} - {}",
+                        r.topic_path.join("/"),
+                        r.id,
+                        r.requirement.name.trim()
+                    );
+                    for (field, text) in matches {
+                        println!("  {field}: {text}");
+                    }
+                }
+            });
+        }
+        Command::Sort {
+            requirements,
+            by,
+            edit,
+        } => {
+            let mut sources = Sources::open(&requirements)?;
+            for source in &mut sources.files {
+                sort::sort(&mut source.project, by);
+            }
+            edit.save_sources(&sources)?;
+        }
+        Command::Reid {
+            requirements,
+            prefix,
+            start,
+            step,
+            mapping,
+            edit,
+        } => {
+            let mut sources = Sources::open(&requirements)?;
+            let ids = reid::mapping(&sources.merged, &prefix, start, step)?;
+            // Links to a requirement can be in any file, not only the one defining it
+            for source in &mut sources.files {
+                reid::apply(&mut source.project, &ids);
+            }
+            edit.save_sources(&sources)?;
+            let ids = if mapping.as_deref().and_then(Format::from_path) == Some(Format::Json) {
+                serde_json::to_string_pretty(&ids)? + "\n"
+            } else {
+                serde_yaml::to_string(&ids)?
+            };
+            if !edit.dry_run {
+                write_output(mapping, &ids)?;
+            }
+        }
+        Command::Split {
+            requirements,
+            out_dir,
+        } => {
+            let (mut project, format) = load_for_edit(&requirements)?;
+            let root = requirements
+                .file_name()
+                .ok_or_else(|| anyhow::anyhow!("Invalid path {}", requirements.display()))?;
+            std::fs::create_dir_all(&out_dir)?;
+            // Included paths are relative to the including file, which moves to `out_dir`
+            project.includes = project
+                .includes
+                .iter()
+                .map(|include| rebase_include(&requirements, include, &out_dir))
+                .collect::<anyhow::Result<_>>()?;
+            // Compared in lower case, as file systems may ignore case
+            let mut taken: HashMap<String, String> = project
+                .includes
+                .iter()
+                .map(|include| {
+                    (
+                        include.to_lowercase(),
+                        format!("the included file {include}"),
+                    )
+                })
+                .collect();
+            taken.insert(
+                root.to_string_lossy().to_lowercase(),
+                String::from("the project file"),
+            );
+            let mut files = Vec::new();
+            for (id, topic) in std::mem::take(&mut project.topics) {
+                let file_name = topic_file_name(&id, format);
+                if let Some(other) = taken.get(&file_name.to_lowercase()) {
+                    anyhow::bail!(
+                        "Topic {} would be written to {file_name}, which is already {other}",
+                        id.trim()
+                    );
+                }
+                taken.insert(
+                    file_name.to_lowercase(),
+                    format!("the file of topic {}", id.trim()),
+                );
+                let fragment = Fragment {
+                    topics: IndexMap::from([(id, topic)]),
+                    ..Default::default()
+                };
+                files.push((format.serialize(&fragment)?, file_name.clone()));
+                project.includes.push(file_name);
+            }
+            for (content, file_name) in files {
+                std::fs::write(out_dir.join(file_name), content)?;
+            }
+            std::fs::write(out_dir.join(root), format.serialize(&project)?)?;
+        }
+        Command::Fmt {
+            requirements,
+            check,
+            edit,
+        } => {
+            let mut unformatted = Vec::new();
+            for path in requirements {
+                let original = std::fs::read_to_string(&path)?;
+                let formatted = format_file(&path, &original)?;
+                if formatted != original {
+                    if check {
+                        unformatted.push(path.display().to_string());
+                    } else {
+                        edit.write(&path, &formatted)?;
+                    }
+                }
+            }
+            if !unformatted.is_empty() {
+                anyhow::bail!("Not formatted: {}", unformatted.join(", "));
+            }
+        }
+        Command::Trace {
+            requirements,
+            tests,
+            source,
+            features,
+            format,
+            output,
+        } => {
+            let project = load(&requirements)?;
+            let mut traces = trace::trace(&project, &tests, &source)?;
+            if !features.is_empty() {
+                trace::add_scenarios(&mut traces, &gherkin::scenarios(&features)?);
+            }
+            let content = match format {
+                TraceFormat::Markdown => trace::to_markdown(&project, &traces) + "\n",
+                TraceFormat::Csv => trace::to_csv(&traces),
+                TraceFormat::Json => serde_json::to_string_pretty(&traces)? + "\n",
+            };
+            write_output(output, &content)?;
+        }
+        Command::Impact {
+            id,
+            requirements,
+            tests,
+            source,
+            features,
+            format,
+        } => {
+            let project = load(&requirements)?;
+            let mut traces = trace::trace(&project, &tests, &source)?;
+            if !features.is_empty() {
+                trace::add_scenarios(&mut traces, &gherkin::scenarios(&features)?);
+            }
+            let impact = impact::impact(&project, &traces, &id)?;
+            match format {
+                ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&impact)?),
+                ReportFormat::Text => {
+                    println!("{}", impact::to_tree(&impact));
+                    let (requirements, tests, code) = impact.count();
+                    println!(
+                        "\n{requirements} requirements, {tests} test results and {code} code references affected"
+                    );
+                }
+            }
+        }
+        Command::Docs {
+            paths,
+            requirements,
+            format,
+            strict,
+        } => {
+            let project = load(&requirements)?;
+            let coverage = docs::coverage(&project, &paths)?;
+            let undocumented: Vec<_> = coverage.iter().filter(|c| c.mentions.is_empty()).collect();
+            match format {
+                ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&coverage)?),
+                ReportFormat::Text => {
+                    for requirement in &undocumented {
+                        println!("{} - {}", requirement.id, requirement.name);
+                    }
+                }
+            }
+            eprintln!(
+                "{} of {} requirements are mentioned in the documentation",
+                coverage.len() - undocumented.len(),
+                coverage.len()
+            );
+            if strict && !undocumented.is_empty() {
+                anyhow::bail!("Not every requirement is documented");
+            }
+        }
+        Command::Changelog { old, new } => {
+            let old = load(&old)?;
+            let new = load(&new)?;
+            println!("{}", diff::changelog(&old, &new, &diff::diff(&old, &new)));
+        }
+        Command::Bump {
+            part,
+            requirements,
+            changelog,
+            since,
+            edit,
+        } => {
+            let (mut project, format) = load_for_edit(&requirements)?;
+            project.version.bump(part);
+            edit.save(&requirements, format, &project)?;
+            if !edit.dry_run {
+                eprintln!("Bumped version to {}", project.version);
+            }
+            if let (Some(changelog), Some(since)) = (changelog, since) {
+                let old = load(&since)?;
+                let mut new = load(&requirements)?;
+                new.version = project.version.clone();
+                let mut content = std::fs::read_to_string(&changelog).unwrap_or_default();
+                if !content.is_empty() {
+                    content.push('\n');
+                }
+                content.push_str(&diff::changelog(&old, &new, &diff::diff(&old, &new)));
+                content.push('\n');
+                edit.write(&changelog, &content)?;
+            }
+        }
+        Command::Graph {
+            requirements,
+            format,
+        } => {
+            let graph = graph::graph(&load(&requirements)?);
+            let output = match format {
+                GraphFormat::Dot => graph::to_dot(&graph),
+                GraphFormat::Mermaid => graph::to_mermaid(&graph),
+                GraphFormat::Json => serde_json::to_string_pretty(&graph)?,
+            };
+            println!("{output}");
+        }
+        Command::Import { source } => match source {
+            ImportSource::Csv {
+                file,
+                map,
+                topic_separator,
+                name,
+                output,
+            } => {
+                let mut project = import::empty_project(&project_name(name, &file));
+                let columns = import::ColumnMap::parse(&map)?;
+                import::from_csv(
+                    &mut project,
+                    std::fs::File::open(&file)?,
+                    &columns,
+                    &topic_separator,
+                )?;
+                write_project(output, &project)?;
+            }
+            ImportSource::Jira {
+                file,
+                map,
+                topic_separator,
+                name,
+                output,
+            } => {
+                let mut project = import::empty_project(&project_name(name, &file));
+                let columns = import::ColumnMap::jira().with_mappings(&map)?;
+                import::from_csv(
+                    &mut project,
+                    std::fs::File::open(&file)?,
+                    &columns,
+                    &topic_separator,
+                )?;
+                write_project(output, &project)?;
+            }
+            ImportSource::Doors {
+                file,
+                map,
+                prefix,
+                name,
+                output,
+            } => {
+                let mut project = import::empty_project(&project_name(name, &file));
+                let columns = import::DoorsColumns::parse(&map)?;
+                import::from_doors(&mut project, std::fs::File::open(&file)?, &columns, &prefix)?;
+                write_project(output, &project)?;
+            }
+            ImportSource::Doorstop { dir, name, output } => {
+                let mut project = doorstop::read(&dir)?;
+                if let Some(name) = name {
+                    project.name = name;
+                }
+                write_project(output, &project)?;
+            }
+            ImportSource::Sdoc { file, output } => {
+                let mut project = sdoc::from_sdoc(&std::fs::read_to_string(&file)?)?;
+                if project.name.is_empty() {
+                    project.name = project_name(None, &file);
+                }
+                write_project(output, &project)?;
+            }
+            ImportSource::Reqif {
+                file,
+                preset,
+                map,
+                output,
+            } => {
+                let attributes = reqif::AttributeMap::preset(preset).with_mappings(&map)?;
+                let mut project = reqif::from_reqif(&std::fs::read_to_string(&file)?, &attributes)?;
+                if project.name.is_empty() {
+                    project.name = project_name(None, &file);
+                }
+                write_project(output, &project)?;
+            }
+            ImportSource::Markdown { file, output } => {
+                let project = import::from_markdown(&std::fs::read_to_string(file)?)?;
+                write_project(output, &project)?;
+            }
+            ImportSource::Gherkin {
+                paths,
+                requirements,
+                format,
+                strict,
+            } => {
+                let project = load(&requirements)?;
+                let scenarios = gherkin::scenarios(&paths)?;
+                let coverage = gherkin::coverage(&project, &scenarios);
+                match format {
+                    ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&coverage)?),
+                    ReportFormat::Text => {
+                        for requirement in &coverage {
+                            println!("{} - {}", requirement.id, requirement.name);
+                            for scenario in &requirement.scenarios {
+                                println!(
+                                    "  {}:{} {}",
+                                    scenario.file.display(),
+                                    scenario.line,
+                                    scenario.name
+                                );
+                            }
+                            if requirement.scenarios.is_empty() {
+                                println!("  no scenario");
+                            }
+                        }
+                    }
+                }
+                let uncovered = coverage.iter().filter(|c| c.scenarios.is_empty()).count();
+                if uncovered > 0 {
+                    eprintln!(
+                        "{uncovered} of {} requirements have no scenario",
+                        coverage.len()
+                    );
+                    if strict {
+                        anyhow::bail!("Not every requirement has a scenario");
+                    }
+                }
+            }
+        },
+        Command::Export { target } => match target {
+            ExportTarget::Jira {
+                requirements,
+                map,
+                topic_separator,
+                output,
+            } => {
+                let columns = import::ColumnMap::jira().with_mappings(&map)?;
+                let csv = export::jira_csv(&load(&requirements)?, &columns, &topic_separator)?;
+                write_output(output, &csv)?;
+            }
+            ExportTarget::Doorstop {
+                requirements,
+                out_dir,
+                prefix,
+                sep,
+                digits,
+            } => {
+                let files =
+                    doorstop::write(&load(&requirements)?, &out_dir, &prefix, &sep, digits)?;
+                println!("Wrote {} files to {}", files.len(), out_dir.display());
+            }
+            ExportTarget::Sdoc {
+                requirements,
+                output,
+            } => {
+                write_output(output, &sdoc::to_sdoc(&load(&requirements)?))?;
+            }
+            ExportTarget::SphinxNeeds {
+                requirements,
+                directive,
+                output,
+            } => {
+                let rst = export::sphinx_needs(&load(&requirements)?, &directive);
+                write_output(output, &rst)?;
+            }
+            ExportTarget::Reqif {
+                requirements,
+                preset,
+                map,
+                output,
+            } => {
+                let attributes = reqif::AttributeMap::preset(preset).with_mappings(&map)?;
+                write_output(
+                    output,
+                    &reqif::to_reqif(&load(&requirements)?, &attributes, timestamp()?),
+                )?;
+            }
+            ExportTarget::Jsonl {
+                requirements,
+                chunk_size,
+                output,
+            } => {
+                let chunk_size = chunk_size.map(usize::try_from).transpose()?;
+                let records = export::records(&load(&requirements)?, chunk_size);
+                write_output(output, &export::jsonl(&records))?;
+            }
+            ExportTarget::Notion {
+                requirements,
+                parent,
+                tests,
+                token,
+                api_url,
+            } => {
+                let token = token_or_env(token, "NOTION_TOKEN")?;
+                let project = load(&requirements)?;
+                let statuses = if tests.is_empty() {
+                    HashMap::new()
+                } else {
+                    trace::trace(&project, &tests, &[])?
+                        .into_iter()
+                        .map(|t| (t.id, t.status))
+                        .collect()
+                };
+                let notion = notion::Notion::new(&api_url, &token);
+                for line in notion::export(&notion, &parent, &project, &statuses)? {
+                    println!("{line}");
+                }
+            }
+        },
+        Command::Query {
+            requirements,
+            expression,
+            format,
+        } => {
+            let project = serde_json::to_value(load(&requirements)?)?;
+            let value = query::query(&project, &expression)?;
+            match format {
+                QueryFormat::Json => println!("{}", serde_json::to_string_pretty(&value)?),
+                QueryFormat::Yaml => print!("{}", serde_yaml::to_string(&value)?),
+                QueryFormat::Plain => {
+                    let items = match value {
+                        serde_json::Value::Array(items) => items,
+                        value => vec![value],
+                    };
+                    for item in items {
+                        match item {
+                            serde_json::Value::String(s) => println!("{s}"),
+                            item => println!("{item}"),
+                        }
+                    }
+                }
+            }
+        }
+        Command::Show {
+            id,
+            requirements,
+            tests,
+            format,
+        } => {
+            let project = load(&requirements)?;
+            let Some((topic_path, requirement)) = project.find_requirement(&id) else {
+                anyhow::bail!("No requirement with ID {id}");
+            };
+            let topic_names = (1..=topic_path.len())
+                .filter_map(|i| project.topic(&topic_path[..i]))
+                .map(|t| t.name.trim().to_string())
+                .collect();
+            let status = if tests.is_empty() {
+                None
+            } else {
+                trace::trace(&project, &tests, &[])?
+                    .into_iter()
+                    .find(|t| t.id == id.trim())
+                    .map(|t| t.status)
+            };
+            let details = RequirementDetails {
+                id: id.trim(),
+                topic_path,
+                topic_names,
+                requirement,
+                linked_from: project.linking_to(&[id.trim()]),
+                status,
+            };
+            match format {
+                ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&details)?),
+                ReportFormat::Text => {
+                    println!("{} - {}", details.id, requirement.name.trim());
+                    println!("Topic: {}", details.topic_names.join(" > "));
+                    if let Some(status) = details.status {
+                        println!("Status: {status}");
+                    }
+                    println!();
+                    println!("{}", requirement.description.trim());
+                    for info in &requirement.additional_info {
+                        println!("  - {}", info.trim());
+                    }
+                    if !requirement.links.is_empty() {
+                        println!();
+                        println!("Links: {}", requirement.links.join(", "));
+                    }
+                    if !details.linked_from.is_empty() {
+                        println!("Linked from: {}", details.linked_from.join(", "));
+                    }
+                }
+            }
+        }
+        Command::List {
+            requirements,
+            filter,
+        } => {
+            let filter = filter.map(|f| Regex::new(&f)).transpose()?;
+            load(&requirements)?.visit_requirements(|r| {
+                if filter.as_ref().is_none_or(|f| f.is_match(r.id)) {
+                    println!(
+                        "{}\t{}\t{}",
+                        r.id,
+                        r.topic_path.join("/"),
+                        r.requirement.name.trim()
+                    );
+                }
+            });
+        }
+        Command::VerifyLinks {
+            requirements,
+            concurrency,
+            timeout,
+        } => {
+            let urls = urls::extract(&load(&requirements)?);
+            let dead = urls::check(&urls, concurrency, Duration::from_secs(timeout));
+            for dead in &dead {
+                println!("{} ({}): {}", dead.url.url, dead.url.location, dead.error);
+            }
+            if !dead.is_empty() {
+                anyhow::bail!("{} of {} links are dead", dead.len(), urls.len());
+            }
+            eprintln!("All {} links are reachable", urls.len());
+        }
+        Command::Lint {
+            requirements,
+            text,
+            rule,
+            skip,
+            similarity,
+            ears,
+        } => {
+            let project = load(&requirements)?;
+            let mut findings = lint::structure(&project);
+            if ears {
+                let checked = text && rule.contains(&TextRule::Ears);
+                if !checked && !skip.contains(&TextRule::Ears) {
+                    findings.extend(lint::text(&project, &[TextRule::Ears], &config().keywords));
+                }
+            }
+            if let Some(threshold) = similarity {
+                if !(0.0..=1.0).contains(&threshold) {
+                    anyhow::bail!("The similarity threshold must be between 0 and 1");
+                }
+                findings.extend(lint::similarity(&project, threshold));
+            }
+            if text {
+                let rules: Vec<_> = if rule.is_empty() {
+                    TextRule::defaults()
+                } else {
+                    rule
+                };
+                let rules: Vec<_> = rules.into_iter().filter(|r| !skip.contains(r)).collect();
+                findings.extend(lint::text(&project, &rules, &config().keywords));
+            }
+            for finding in &findings {
+                println!("{finding}");
+            }
+            if !findings.is_empty() {
+                anyhow::bail!("Found {} problems", findings.len());
+            }
+        }
+        Command::Ears { requirements } => {
+            load(&requirements)?.visit_requirements(|r| {
+                let pattern = lint::ears(&r.requirement.description);
+                let pattern = pattern.map_or_else(|| "none".to_string(), |p| p.to_string());
+                println!("{} {pattern}", r.id);
+            });
+        }
+        Command::Stubs {
+            language,
+            requirements,
+            output,
+        } => {
+            let project = load(&requirements)?;
+            write_output(output, &(stubs::generate(&project, language) + "\n"))?;
+        }
+        Command::Gherkin {
+            requirements,
+            out_dir,
+        } => {
+            let project = load(&requirements)?;
+            std::fs::create_dir_all(&out_dir)?;
+            for (file_name, content) in gherkin::features(&project) {
+                std::fs::write(out_dir.join(file_name), content)?;
+            }
+        }
+        Command::Sign {
+            requirements,
+            lockfile,
+        } => {
+            let project = load(&requirements)?;
+            let lockfile = lockfile.unwrap_or_else(|| requirements.with_extension("lock"));
+            let lock = lock::lock(&project);
+            std::fs::write(lockfile, serde_json::to_string_pretty(&lock)? + "\n")?;
+        }
+        Command::Verify {
+            requirements,
+            lockfile,
+        } => {
+            let project = load(&requirements)?;
+            let lockfile = lockfile.unwrap_or_else(|| requirements.with_extension("lock"));
+            let signed: lock::Lock = serde_json::from_str(&std::fs::read_to_string(&lockfile)?)?;
+            let current = lock::lock(&project);
+            if current.version < signed.version {
+                anyhow::bail!(
+                    "Version {} is older than the signed version {}",
+                    current.version,
+                    signed.version
+                );
+            }
+            if current.hash != signed.hash {
+                for change in lock::changes(&signed, &current) {
+                    println!("{change}");
+                }
+                if current.version == signed.version {
+                    anyhow::bail!(
+                        "Requirements changed without a version bump from {}",
+                        signed.version
+                    );
+                }
+                println!(
+                    "Version bumped from {} to {}, run `req sign` to update {}",
+                    signed.version,
+                    current.version,
+                    lockfile.display()
+                );
+            }
+        }
+        Command::Blame {
+            requirements,
+            format,
+        } => {
+            let project = load(&requirements)?;
+            let provenance = blame::blame(&requirements)?;
+            let mut blamed = IndexMap::new();
+            project.visit_requirements(|r| {
+                if let Some(p) = provenance.get(r.id) {
+                    blamed.insert(r.id.to_string(), p.clone());
+                }
+            });
+            match format {
+                ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&blamed)?),
+                ReportFormat::Text => {
+                    let width = blamed.keys().map(String::len).max().unwrap_or_default();
+                    for (id, p) in &blamed {
+                        let commit = if p.commit.is_empty() {
+                            "0000000"
+                        } else {
+                            &p.commit[..p.commit.len().min(7)]
+                        };
+                        let line = format!(
+                            "{commit} {id:width$} {} {} {}",
+                            p.date.get(..10).unwrap_or("          "),
+                            p.author,
+                            p.summary
+                        );
+                        println!("{}", line.trim_end());
+                    }
+                }
+            }
+        }
+        Command::Fingerprint {
+            requirements,
+            format,
+            compare,
+            changelog,
+        } => {
+            let current = fingerprint::fingerprints(&load(&requirements)?);
+            let Some(compare) = compare else {
+                match format {
+                    ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&current)?),
+                    ReportFormat::Text => {
+                        for (id, hash) in &current.requirements {
+                            println!("{hash}  {id}");
+                        }
+                        println!("{}  *", current.digest);
+                    }
+                }
+                return Ok(());
+            };
+            let previous: fingerprint::Fingerprints = serde_json::from_str(
+                &std::fs::read_to_string(&compare)
+                    .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", compare.display()))?,
+            )?;
+            if previous.digest == current.digest {
+                return Ok(());
+            }
+            let changes = fingerprint::changes(&previous, &current);
+            for change in &changes {
+                println!("{change}");
+            }
+            if current.version > previous.version {
+                return Ok(());
+            }
+            let changelog = match changelog {
+                Some(changelog) => std::fs::read_to_string(changelog)?,
+                None => String::new(),
+            };
+            let unmentioned: Vec<_> = changes
+                .iter()
+                .map(|c| match c {
+                    lock::Change::Added(id)
+                    | lock::Change::Removed(id)
+                    | lock::Change::Modified(id) => id.as_str(),
+                })
+                .filter(|id| trace::mentions(&changelog, id).next().is_none())
+                .collect();
+            if !unmentioned.is_empty() {
+                anyhow::bail!(
+                    "Requirements changed without a version bump from {} or a change log entry for {}",
+                    previous.version,
+                    unmentioned.join(", ")
+                );
+            }
+        }
+        Command::Baseline { action } => match action {
+            BaselineAction::Create {
+                name,
+                requirements,
+                force,
+            } => {
+                let project = load(&requirements)?;
+                let path = baseline::path(&requirements, &name);
+                if path.exists() && !force {
+                    anyhow::bail!("Baseline {name} already exists, use --force to replace it");
+                }
+                std::fs::create_dir_all(baseline::directory(&requirements))?;
+                let baseline = baseline::create(&name, &project);
+                std::fs::write(&path, serde_json::to_string_pretty(&baseline)? + "\n")?;
+                eprintln!("Created baseline {name} at {}", path.display());
+            }
+            BaselineAction::Compare { name, requirements } => {
+                let project = load(&requirements)?;
+                let baseline = baseline::load(&requirements, &name)?;
+                let changes = baseline::compare(&baseline, &project);
+                if changes.is_empty() {
+                    println!("No changes since baseline {name}");
+                } else {
+                    for change in &changes {
+                        println!("{change}");
+                    }
+                    anyhow::bail!("Requirements changed since baseline {name}");
+                }
+            }
+        },
+        Command::Release {
+            requirements,
+            out_dir,
+            tests,
+            source,
+            allowed_requirements,
+            pdf_command,
+        } => {
+            let project = load(&requirements)?;
+            let stem = requirements
+                .file_stem()
+                .ok_or_else(|| anyhow::anyhow!("Invalid path {}", requirements.display()))?
+                .to_string_lossy();
+            let mut artifacts = vec![
+                (
+                    format!("{stem}.md"),
+                    to_markdown(&requirements, true, false)? + "\n",
+                ),
+                (format!("{stem}.html"), to_html(&requirements, false)?),
+                (
+                    s!("schema.json"),
+                    serde_json::to_string_pretty(&schema_for!(Project))? + "\n",
+                ),
+            ];
+            if !tests.is_empty() || !source.is_empty() {
+                let traces = trace::trace(&project, &tests, &source)?;
+                artifacts.push((
+                    s!("traceability.md"),
+                    trace::to_markdown(&project, &traces) + "\n",
+                ));
+            }
+            if !tests.is_empty() {
+                let test_results = trace::files(&tests)?;
+                artifacts.push((
+                    s!("test-results.md"),
+                    check_report(&requirements, &test_results, &allowed_requirements, None, 1)?
+                        + "\n",
+                ));
+            }
+            std::fs::create_dir_all(&out_dir)?;
+            for (file_name, content) in &artifacts {
+                std::fs::write(out_dir.join(file_name), content)?;
+            }
+            let mut manifest = certpack::manifest(&project, &artifacts);
+            let pdf = format!("{stem}.pdf");
+            match pdf_command {
+                Some(command) => {
+                    let mut command = command.split_whitespace();
+                    let Some(program) = command.next() else {
+                        anyhow::bail!("The PDF command is empty");
+                    };
+                    let status = std::process::Command::new(program)
+                        .args(command)
+                        .arg(out_dir.join(format!("{stem}.html")))
+                        .arg(out_dir.join(&pdf))
+                        .status()
+                        .map_err(|e| anyhow::anyhow!("Failed to run {program}: {e}"))?;
+                    if !status.success() {
+                        anyhow::bail!("{program} exited with {status}");
+                    }
+                    let hash = lock::hash(&std::fs::read(out_dir.join(&pdf)).map_err(|e| {
+                        anyhow::anyhow!("{program} wrote no {}: {e}", out_dir.join(&pdf).display())
+                    })?);
+                    manifest["files"][&pdf] = hash.into();
+                }
+                None => {
+                    tracing::warn!("No PDF in the release, pass --pdf-command to render one");
+                    manifest["omitted"] = serde_json::json!([pdf]);
+                }
+            }
+            std::fs::write(
+                out_dir.join("manifest.json"),
+                serde_json::to_string_pretty(&manifest)? + "\n",
+            )?;
+        }
+        Command::Certpack {
+            requirements,
+            out_dir,
+            standard,
+            tests,
+            source,
+            allowed_requirements,
+        } => {
+            use certpack::Document;
+            let project = load(&requirements)?;
+            let traces = trace::trace(&project, &tests, &source)?;
+            let verification = if tests.is_empty() {
+                s!("No test results were provided.")
+            } else {
+                let test_results = trace::files(&tests)?;
+                check_report(&requirements, &test_results, &allowed_requirements, None, 2)?
+            };
+            let baselines = baseline::all(&requirements)?;
+            let contents = [
+                (Document::Specification, {
+                    let mut output = vec![labels().key_words.trim().to_string(), nl()];
+                    output.extend(markdown_sections(project.clone(), 2));
+                    highlight_keywords(output.join("\n"))
+                }),
+                (
+                    Document::Traceability,
+                    trace::to_markdown(&project, &traces)
+                        .split_once('\n')
+                        .map(|(_, table)| table.trim().to_string())
+                        .unwrap_or_default(),
+                ),
+                (Document::Verification, verification),
+                (
+                    Document::OpenItems,
+                    certpack::open_items(&project, &traces, !tests.is_empty(), !source.is_empty()),
+                ),
+                (
+                    Document::ChangeHistory,
+                    certpack::change_history(&baselines, &project),
+                ),
+            ];
+            let name = format!(
+                "{}-{}-{}",
+                project_name(None, &requirements),
+                project.version,
+                standard
+                    .to_possible_value()
+                    .expect("No skipped variants")
+                    .get_name()
+            );
+            let mut files: Vec<_> = contents
+                .into_iter()
+                .map(|(document, content)| {
+                    (
+                        format!("{name}/{}", document.file_name()),
+                        format!(
+                            "{}\n\n{}\n",
+                            document.header(standard, &project),
+                            content.trim()
+                        ),
+                    )
+                })
+                .collect();
+            let mut manifest = certpack::manifest(&project, &files);
+            manifest["standard"] = standard.to_string().into();
+            manifest["documents"] = Document::ALL
+                .iter()
+                .map(|d| (d.file_name().to_string(), d.title(standard).0.into()))
+                .collect::<serde_json::Map<_, _>>()
+                .into();
+            files.push((
+                format!("{name}/manifest.json"),
+                serde_json::to_string_pretty(&manifest)? + "\n",
+            ));
+            let archive = certpack::tar(&files, timestamp()?)?;
+            std::fs::create_dir_all(&out_dir)?;
+            let path = out_dir.join(format!("{name}.tar"));
+            std::fs::write(&path, &archive)?;
+            let hash = lock::hash(&archive);
+            std::fs::write(
+                out_dir.join(format!("{name}.tar.sha256")),
+                format!("{hash}  {name}.tar\n"),
+            )?;
+            println!("Wrote {} with SHA-256 {hash}", path.display());
+        }
+        Command::Edit { id, requirements } => {
+            let project = load(&requirements)?;
+            if project.find_requirement(&id).is_none() {
+                anyhow::bail!("No requirement with ID {id}");
+            }
+            let mut location = None;
+            for file in format::source_files(&requirements)? {
+                if let Some(line) = format::key_line(&std::fs::read_to_string(&file)?, &id) {
+                    location = Some((file, line));
+                    break;
+                }
+            }
+            let Some((file, line)) = location else {
+                anyhow::bail!("Could not find the line of {id}");
+            };
+            let editor = std::env::var("VISUAL")
+                .or_else(|_| std::env::var("EDITOR"))
+                .unwrap_or_else(|_| s!("vi"));
+            let mut editor = editor.split_whitespace();
+            let program = editor.next().unwrap_or("vi");
+            let status = std::process::Command::new(program)
+                .args(editor)
+                .arg(format!("+{line}"))
+                .arg(&file)
+                .status()?;
+            if !status.success() {
+                anyhow::bail!("{program} exited with {status}");
+            }
+        }
+        Command::Archive {
+            ids,
+            requirements,
+            force,
+            edit,
+        } => {
+            let mut sources = Sources::open(&requirements)?;
+            let ids = expand_ids(&sources.merged, &ids)?;
+            let ids: Vec<_> = ids.iter().map(String::as_str).collect();
+            let linking = sources.merged.linking_to(&ids);
+            if !linking.is_empty() {
+                let message = format!("{} linked from {}", ids.join(", "), linking.join(", "));
+                if !force {
+                    anyhow::bail!("{message}, use --force to archive anyway");
+                }
+                eprintln!("Warning: {message}");
+            }
+            for id in &ids {
+                let Some(source) = sources.requirement_file(id) else {
+                    anyhow::bail!("No requirement with ID {id}");
+                };
+                source.project.archive_requirement(id);
+            }
+            edit.save_sources(&sources)?;
+        }
+        Command::Apply {
+            requirements,
+            filter,
+            set,
+            append_info,
+            edit,
+        } => {
+            let filter = Regex::new(&format!("^(?:{filter})$"))?;
+            let mut changes = set
+                .iter()
+                .map(|s| apply::Change::set(s))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            changes.extend(append_info.into_iter().map(apply::Change::AppendInfo));
+            if changes.is_empty() {
+                anyhow::bail!("Nothing to apply, use --set or --append-info");
+            }
+            let mut sources = Sources::open(&requirements)?;
+            let mut changed = Vec::new();
+            for source in &mut sources.files {
+                changed.extend(apply::apply(&mut source.project.topics, &filter, &changes));
+            }
+            if changed.is_empty() {
+                eprintln!("No requirement changed");
+                return Ok(());
+            }
+            edit.save_sources(&sources)?;
+            eprintln!("Changed {}", changed.join(", "));
+        }
+        Command::Codegen { target } => match target {
+            CodegenTarget::Env {
+                requirements,
+                format,
+                output,
+            } => {
+                let project = load(&requirements)?;
+                let content = match format {
+                    EnvFormat::Dotenv => codegen::env_example(&project),
+                    EnvFormat::Compose => codegen::compose_environment(&project),
+                };
+                write_output(output, &content)?;
+            }
+        },
+        Command::ConfigSchema {
+            requirements,
+            output,
+        } => {
+            let schema = codegen::config_schema(&load(&requirements)?);
+            write_output(output, &(serde_json::to_string_pretty(&schema)? + "\n"))?;
+        }
+        Command::Sync { target } => match target {
+            SyncTarget::Github {
+                requirements,
+                repo,
+                token,
+                api_url,
+                dry_run,
+                status,
+            } => {
+                let token = token_or_env(token, "GITHUB_TOKEN")?;
+                let github = sync::GitHub::new(&api_url, &repo, &token);
+                sync_issues(&github, &requirements, dry_run, status)?;
+            }
+            SyncTarget::Gitlab {
+                requirements,
+                project,
+                token,
+                api_url,
+                dry_run,
+                status,
+            } => {
+                let token = token_or_env(token, "GITLAB_TOKEN")?;
+                let gitlab = sync::GitLab::new(&api_url, &project, &token);
+                sync_issues(&gitlab, &requirements, dry_run, status)?;
+            }
+        },
+        Command::Publish { target } => match target {
+            PublishTarget::Confluence {
+                requirements,
+                url,
+                space,
+                parent,
+                user,
+                token,
+                page_ids,
+                dry_run,
+            } => {
+                let token = token_or_env(token, "CONFLUENCE_TOKEN")?;
+                let confluence = confluence::Confluence::new(&url, &space, user.as_deref(), &token);
+                let page_ids =
+                    page_ids.unwrap_or_else(|| requirements.with_extension("confluence.json"));
+                let mut ids = confluence::read_page_ids(&page_ids)?;
+                let pages = confluence_pages(load(&requirements)?)?;
+                let published =
+                    confluence::publish(&confluence, &pages, parent.as_deref(), &mut ids, dry_run);
+                if !dry_run {
+                    // Keep the pages created before a failure
+                    confluence::write_page_ids(&page_ids, &ids)?;
+                }
+                for line in published? {
+                    println!("{line}");
+                }
+            }
+        },
+        Command::Completions { shell } => {
+            shell.generate(&mut Args::command(), &mut std::io::stdout());
+            let dynamic = match shell {
+                clap_complete_command::Shell::Bash => BASH_ID_COMPLETION,
+                clap_complete_command::Shell::Zsh => ZSH_ID_COMPLETION,
+                clap_complete_command::Shell::Fish => FISH_ID_COMPLETION,
+                _ => "",
+            };
+            print!("{dynamic}");
+        }
+        Command::CompleteIds { requirements } => {
+            let requirements = requirements.unwrap_or_else(default_requirements);
+            if !requirements.exists() {
+                return Ok(());
+            }
+            load(&requirements)?.visit_requirements(|r| println!("{}", r.id));
+        }
+        Command::Man { out_dir } => {
+            if let Some(out_dir) = out_dir {
+                std::fs::create_dir_all(&out_dir)?;
+                clap_mangen::generate_to(Args::command(), out_dir)?;
+            } else {
+                clap_mangen::Man::new(Args::command()).render(&mut std::io::stdout())?;
+            }
         }
     }
 
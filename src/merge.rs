@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use indexmap::IndexMap;
+
+use crate::{ConfigDefault, Definition, Fragment, Project, Requirement, Topic};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    Requirement {
+        id: String,
+    },
+    /// The same requirement ID in two places, like two topics or a topic and the archive
+    Location {
+        id: String,
+        first: String,
+        second: String,
+    },
+    Topic {
+        id: String,
+    },
+    Definition {
+        name: String,
+    },
+    ConfigDefault {
+        name: String,
+    },
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Conflict::Requirement { id } => {
+                write!(f, "requirement '{id}' is defined differently")
+            }
+            Conflict::Location { id, first, second } => {
+                write!(
+                    f,
+                    "requirement '{id}' is defined in {first} and in {second}"
+                )
+            }
+            Conflict::Topic { id } => write!(f, "topic '{id}' has different names"),
+            Conflict::Definition { name } => {
+                write!(f, "definition '{name}' is defined differently")
+            }
+            Conflict::ConfigDefault { name } => {
+                write!(f, "config default '{name}' is defined differently")
+            }
+        }
+    }
+}
+
+pub(crate) fn same_requirement(a: &Requirement, b: &Requirement) -> bool {
+    a.name.trim() == b.name.trim()
+        && a.classification == b.classification
+        && a.priority == b.priority
+        && a.state == b.state
+        && a.description.trim() == b.description.trim()
+        && a.additional_info
+            .iter()
+            .map(|i| i.trim())
+            .eq(b.additional_info.iter().map(|i| i.trim()))
+        && a.links
+            .iter()
+            .map(|l| l.trim())
+            .eq(b.links.iter().map(|l| l.trim()))
+}
+
+/// Where each requirement is defined, by trimmed ID
+type Locations = HashMap<String, String>;
+
+fn topic_location(path: &[String]) -> String {
+    format!("topic '{}'", path.join("/"))
+}
+
+const ARCHIVE: &str = "the archive";
+
+fn collect_locations(
+    topics: &IndexMap<String, Topic>,
+    path: &mut Vec<String>,
+    locations: &mut Locations,
+) {
+    for (id, topic) in topics {
+        path.push(id.trim().to_string());
+        let location = topic_location(path);
+        for id in topic.requirements.keys() {
+            locations.insert(id.trim().to_string(), location.clone());
+        }
+        collect_locations(&topic.subtopics, path, locations);
+        path.pop();
+    }
+}
+
+fn merge_requirements(
+    base: &mut IndexMap<String, Requirement>,
+    other: IndexMap<String, Requirement>,
+    location: &str,
+    locations: &mut Locations,
+    conflicts: &mut Vec<Conflict>,
+) {
+    for (id, requirement) in other {
+        let trimmed = id.trim();
+        match locations.get(trimmed) {
+            Some(existing) if existing != location => conflicts.push(Conflict::Location {
+                id: trimmed.to_string(),
+                first: existing.clone(),
+                second: location.to_string(),
+            }),
+            Some(_) => {
+                let existing = base.iter().find(|(key, _)| key.trim() == trimmed);
+                if existing.is_some_and(|(_, existing)| !same_requirement(existing, &requirement)) {
+                    conflicts.push(Conflict::Requirement {
+                        id: trimmed.to_string(),
+                    });
+                }
+            }
+            None => {
+                locations.insert(trimmed.to_string(), location.to_string());
+                base.insert(id, requirement);
+            }
+        }
+    }
+}
+
+fn merge_topics(
+    base: &mut IndexMap<String, Topic>,
+    other: IndexMap<String, Topic>,
+    path: &mut Vec<String>,
+    locations: &mut Locations,
+    conflicts: &mut Vec<Conflict>,
+) {
+    for (id, mut topic) in other {
+        let requirements = std::mem::take(&mut topic.requirements);
+        let subtopics = std::mem::take(&mut topic.subtopics);
+        let existing = match base.iter().position(|(key, _)| key.trim() == id.trim()) {
+            Some(index) => {
+                let (_, existing) = base.get_index_mut(index).expect("Valid index");
+                if existing.name.trim() != topic.name.trim() {
+                    conflicts.push(Conflict::Topic {
+                        id: id.trim().to_string(),
+                    });
+                }
+                // Content from either side must stay as protected as it was
+                existing.classification = existing.classification.max(topic.classification);
+                existing
+            }
+            None => base.entry(id.clone()).or_insert(topic),
+        };
+        path.push(id.trim().to_string());
+        let location = topic_location(path);
+        merge_requirements(
+            &mut existing.requirements,
+            requirements,
+            &location,
+            locations,
+            conflicts,
+        );
+        merge_topics(
+            &mut existing.subtopics,
+            subtopics,
+            path,
+            locations,
+            conflicts,
+        );
+        path.pop();
+    }
+}
+
+fn merge_definitions(
+    base: &mut Vec<Definition>,
+    other: Vec<Definition>,
+    conflicts: &mut Vec<Conflict>,
+) {
+    for definition in other {
+        match base
+            .iter()
+            .find(|d| d.name.trim() == definition.name.trim())
+        {
+            Some(existing) if *existing != definition => conflicts.push(Conflict::Definition {
+                name: definition.name.trim().to_string(),
+            }),
+            Some(_) => {}
+            None => base.push(definition),
+        }
+    }
+}
+
+fn merge_config_defaults(
+    base: &mut Vec<ConfigDefault>,
+    other: Vec<ConfigDefault>,
+    conflicts: &mut Vec<Conflict>,
+) {
+    for default in other {
+        match base.iter().find(|d| d.name.trim() == default.name.trim()) {
+            Some(existing) if *existing != default => conflicts.push(Conflict::ConfigDefault {
+                name: default.name.trim().to_string(),
+            }),
+            Some(_) => {}
+            None => base.push(default),
+        }
+    }
+}
+
+/// Merges `other` into `base`.
+///
+/// Name, version and description of `base` are kept. Entries with the same
+/// ID that differ in content are left untouched in `base` and reported as
+/// conflicts, as are requirements whose ID is already used in another topic
+/// or the archive.
+pub fn merge(base: &mut Project, other: Project) -> Vec<Conflict> {
+    merge_fragment(base, other.into())
+}
+
+/// Merges the content of an included file into `base`, see [`merge`]
+pub fn merge_fragment(base: &mut Project, other: Fragment) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+    let mut locations = Locations::new();
+    collect_locations(&base.topics, &mut Vec::new(), &mut locations);
+    for id in base.archive.keys() {
+        locations.insert(id.trim().to_string(), ARCHIVE.to_string());
+    }
+    merge_topics(
+        &mut base.topics,
+        other.topics,
+        &mut Vec::new(),
+        &mut locations,
+        &mut conflicts,
+    );
+    merge_definitions(&mut base.definitions, other.definitions, &mut conflicts);
+    merge_config_defaults(
+        &mut base.config_defaults,
+        other.config_defaults,
+        &mut conflicts,
+    );
+    merge_requirements(
+        &mut base.archive,
+        other.archive,
+        ARCHIVE,
+        &mut locations,
+        &mut conflicts,
+    );
+    conflicts
+}
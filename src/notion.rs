@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+
+use crate::trace::Status;
+use crate::{Project, Topic};
+
+/// The version of the API the requests are written for
+const NOTION_VERSION: &str = "2022-06-28";
+/// The longest text a rich text object may contain
+const MAX_TEXT: usize = 2000;
+/// The most blocks that can be added at once
+const MAX_BLOCKS: usize = 100;
+
+/// Splits text into rich text objects short enough for the API
+fn rich_text(text: &str) -> Value {
+    let chars: Vec<char> = text.trim().chars().collect();
+    chars
+        .chunks(MAX_TEXT)
+        .map(|chunk| json!({ "type": "text", "text": { "content": chunk.iter().collect::<String>() } }))
+        .collect()
+}
+
+fn block(kind: &str, text: &str) -> Value {
+    json!({ "object": "block", "type": kind, kind: { "rich_text": rich_text(text) } })
+}
+
+/// Option names of selects may not contain commas
+fn option(name: &str) -> Value {
+    json!({ "name": name.trim().replace(',', " ") })
+}
+
+/// The properties of the requirements database
+#[must_use]
+pub fn database(parent: &str, project: &Project) -> Value {
+    json!({
+        "parent": { "type": "page_id", "page_id": parent },
+        "title": rich_text(&format!("Requirements for {}", project.name.trim())),
+        "properties": {
+            "Name": { "title": {} },
+            "ID": { "rich_text": {} },
+            "Topic": { "select": {} },
+            "Tags": { "multi_select": {} },
+            "Status": { "select": {} },
+            "Priority": { "select": {} },
+            "Links": { "rich_text": {} },
+            "Version": { "rich_text": {} },
+        },
+    })
+}
+
+/// Builds a database entry per requirement, tagged with the names of the topics containing it
+///
+/// The status is the test status if `statuses` has one for the requirement.
+#[must_use]
+pub fn requirement_pages(
+    database_id: &str,
+    project: &Project,
+    statuses: &HashMap<String, Status>,
+) -> Vec<(String, Value)> {
+    let mut pages = Vec::new();
+    project.visit_requirements(|r| {
+        let tags: Vec<_> = (1..=r.topic_path.len())
+            .filter_map(|depth| project.topic(&r.topic_path[..depth]))
+            .map(|t| option(&t.name))
+            .collect();
+        let links: Vec<_> = r.requirement.links.iter().map(|l| l.trim()).collect();
+        let mut properties = json!({
+            "Name": { "title": rich_text(&r.requirement.name) },
+            "ID": { "rich_text": rich_text(r.id) },
+            "Tags": { "multi_select": tags },
+            "Links": { "rich_text": rich_text(&links.join(", ")) },
+            "Version": { "rich_text": rich_text(&project.version.to_string()) },
+        });
+        if let Some(topic) = project.topic(r.topic_path) {
+            properties["Topic"] = json!({ "select": option(&topic.name) });
+        }
+        if let Some(status) = statuses.get(r.id) {
+            properties["Status"] = json!({ "select": option(&status.to_string()) });
+        }
+        if let Some(priority) = r.requirement.priority {
+            properties["Priority"] = json!({ "select": option(&priority.to_string()) });
+        }
+        let mut children = vec![block("paragraph", &r.requirement.description)];
+        children.extend(
+            r.requirement
+                .additional_info
+                .iter()
+                .map(|i| block("bulleted_list_item", i)),
+        );
+        pages.push((
+            r.id.to_string(),
+            json!({
+                "parent": { "database_id": database_id },
+                "properties": properties,
+                "children": children,
+            }),
+        ));
+    });
+    pages
+}
+
+fn topic_blocks(topic: &Topic, level: usize, blocks: &mut Vec<Value>) {
+    for (id, requirement) in &topic.requirements {
+        blocks.push(block(
+            "heading_3",
+            &format!("{} - {}", id.trim(), requirement.name.trim()),
+        ));
+        blocks.push(block("paragraph", &requirement.description));
+        blocks.extend(
+            requirement
+                .additional_info
+                .iter()
+                .map(|i| block("bulleted_list_item", i)),
+        );
+    }
+    for (id, subtopic) in &topic.subtopics {
+        // Notion has three heading levels, deeper topics share the last one
+        let heading = format!("heading_{}", level.min(3));
+        blocks.push(block(
+            &heading,
+            &format!("{} - {}", id.trim(), subtopic.name.trim()),
+        ));
+        topic_blocks(subtopic, level + 1, blocks);
+    }
+}
+
+/// Builds a page per top-level topic with its requirements and subtopics as content
+#[must_use]
+pub fn topic_pages(parent: &str, project: &Project) -> Vec<(String, Value, Vec<Value>)> {
+    project
+        .topics
+        .iter()
+        .map(|(id, topic)| {
+            let mut blocks = Vec::new();
+            topic_blocks(topic, 1, &mut blocks);
+            let page = json!({
+                "parent": { "type": "page_id", "page_id": parent },
+                "properties": {
+                    "title": { "title": rich_text(&format!("{} - {}", id.trim(), topic.name.trim())) },
+                },
+            });
+            (id.trim().to_string(), page, blocks)
+        })
+        .collect()
+}
+
+/// A Notion workspace, accessed through the REST API with the token of an integration
+pub struct Notion {
+    agent: ureq::Agent,
+    api_url: String,
+    token: String,
+}
+
+impl Notion {
+    /// Connects to the API at `api_url`, e.g. `https://api.notion.com/v1`
+    #[must_use]
+    pub fn new(api_url: &str, token: &str) -> Notion {
+        Notion {
+            agent: crate::sync::agent(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    /// Sends the body and returns the ID of the created or changed object
+    fn send(
+        &self,
+        request: ureq::RequestBuilder<ureq::typestate::WithBody>,
+        body: &Value,
+    ) -> anyhow::Result<String> {
+        let response = request
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Notion-Version", NOTION_VERSION)
+            .content_type("application/json")
+            .send(body.to_string())?;
+        let created: Value = serde_json::from_str(&response.into_body().read_to_string()?)?;
+        Ok(created["id"].as_str().unwrap_or_default().to_string())
+    }
+
+    fn create(&self, path: &str, body: &Value) -> anyhow::Result<String> {
+        self.send(self.agent.post(format!("{}/{path}", self.api_url)), body)
+    }
+
+    fn append(&self, block_id: &str, blocks: &[Value]) -> anyhow::Result<()> {
+        for chunk in blocks.chunks(MAX_BLOCKS) {
+            self.send(
+                self.agent
+                    .patch(format!("{}/blocks/{block_id}/children", self.api_url)),
+                &json!({ "children": chunk }),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Creates the requirements database and the topic pages below the page with ID `parent`
+/// and describes what was done
+///
+/// Every export creates a new database and new pages.
+pub fn export(
+    notion: &Notion,
+    parent: &str,
+    project: &Project,
+    statuses: &HashMap<String, Status>,
+) -> anyhow::Result<Vec<String>> {
+    let mut output = Vec::new();
+    let database_id = notion
+        .create("databases", &database(parent, project))
+        .map_err(|e| anyhow::anyhow!("Failed to create the database: {e}"))?;
+    output.push(format!("Created database {database_id}"));
+    let pages = requirement_pages(&database_id, project, statuses);
+    for (id, page) in &pages {
+        notion
+            .create("pages", page)
+            .map_err(|e| anyhow::anyhow!("Failed to create the entry of {id}: {e}"))?;
+    }
+    output.push(format!("Created {} requirement entries", pages.len()));
+    for (id, page, blocks) in topic_pages(parent, project) {
+        let page_id = notion
+            .create("pages", &page)
+            .and_then(|page_id| notion.append(&page_id, &blocks).map(|()| page_id))
+            .map_err(|e| anyhow::anyhow!("Failed to create the page of {id}: {e}"))?;
+        output.push(format!("Created page {page_id} for {id}"));
+    }
+    Ok(output)
+}
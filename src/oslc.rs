@@ -0,0 +1,344 @@
+use quick_xml::escape::escape;
+
+use crate::{Project, RequirementRef};
+
+/// The media type of all responses
+pub const CONTENT_TYPE: &str = "application/rdf+xml";
+/// The OSLC version the responses follow, sent as `OSLC-Core-Version` header
+pub const CORE_VERSION: &str = "2.0";
+
+const NAMESPACES: &str = r#"xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:rdfs="http://www.w3.org/2000/01/rdf-schema#" xmlns:dcterms="http://purl.org/dc/terms/" xmlns:oslc="http://open-services.net/ns/core#" xmlns:oslc_rm="http://open-services.net/ns/rm#""#;
+
+fn rdf(content: &[String]) -> String {
+    let mut output = vec![
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string(),
+        format!("<rdf:RDF {NAMESPACES}>"),
+    ];
+    output.extend(content.iter().cloned());
+    output.push("</rdf:RDF>".to_string());
+    output.join("\n") + "\n"
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            b => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// Decodes a query parameter, where `+` stands for a space
+pub(crate) fn percent_decode(value: &str) -> String {
+    decode(value, true)
+}
+
+/// Decodes a path segment, where `+` stands for itself
+pub(crate) fn percent_decode_path(value: &str) -> String {
+    decode(value, false)
+}
+
+fn decode(value: &str, plus_is_space: bool) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b'+', _) if plus_is_space => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn requirement_url(base: &str, id: &str) -> String {
+    format!("{base}/oslc/requirements/{}", percent_encode(id))
+}
+
+/// The names of the topics containing the requirement
+fn subjects(project: &Project, r: &RequirementRef) -> Vec<String> {
+    (1..=r.topic_path.len())
+        .filter_map(|depth| project.topic(&r.topic_path[..depth]))
+        .map(|t| t.name.trim().to_string())
+        .collect()
+}
+
+/// Describes a requirement as an OSLC RM requirement resource
+///
+/// Links become `oslc_rm:decomposes` relations, like parent relations in other exports.
+fn requirement(base: &str, project: &Project, r: &RequirementRef) -> String {
+    let mut output = vec![
+        format!(
+            r#"<oslc_rm:Requirement rdf:about="{}">"#,
+            escape(requirement_url(base, r.id))
+        ),
+        format!(
+            "  <dcterms:identifier>{}</dcterms:identifier>",
+            escape(r.id)
+        ),
+        format!(
+            "  <dcterms:title>{}</dcterms:title>",
+            escape(r.requirement.name.trim())
+        ),
+        format!(
+            "  <dcterms:description>{}</dcterms:description>",
+            escape(r.requirement.description.trim())
+        ),
+    ];
+    for subject in subjects(project, r) {
+        output.push(format!(
+            "  <dcterms:subject>{}</dcterms:subject>",
+            escape(subject)
+        ));
+    }
+    for link in &r.requirement.links {
+        output.push(format!(
+            r#"  <oslc_rm:decomposes rdf:resource="{}"/>"#,
+            escape(requirement_url(base, link.trim()))
+        ));
+    }
+    output.push(format!(
+        r#"  <oslc:serviceProvider rdf:resource="{base}/oslc/provider"/>"#
+    ));
+    output.push("</oslc_rm:Requirement>".to_string());
+    output.join("\n")
+}
+
+/// The catalog listing the single service provider of the project
+#[must_use]
+pub fn catalog(base: &str, project: &Project) -> String {
+    rdf(&[
+        format!(r#"<oslc:ServiceProviderCatalog rdf:about="{base}/oslc/catalog">"#),
+        format!(
+            "  <dcterms:title>{}</dcterms:title>",
+            escape(project.name.trim())
+        ),
+        format!(r#"  <oslc:serviceProvider rdf:resource="{base}/oslc/provider"/>"#),
+        "</oslc:ServiceProviderCatalog>".to_string(),
+    ])
+}
+
+/// The service provider offering a query capability for the requirements
+#[must_use]
+pub fn service_provider(base: &str, project: &Project) -> String {
+    rdf(&[
+        format!(r#"<oslc:ServiceProvider rdf:about="{base}/oslc/provider">"#),
+        format!(
+            "  <dcterms:title>{}</dcterms:title>",
+            escape(project.name.trim())
+        ),
+        format!(
+            "  <dcterms:description>{}</dcterms:description>",
+            escape(project.description.trim())
+        ),
+        "  <oslc:service>".to_string(),
+        "    <oslc:Service>".to_string(),
+        r#"      <oslc:domain rdf:resource="http://open-services.net/ns/rm#"/>"#.to_string(),
+        "      <oslc:queryCapability>".to_string(),
+        "        <oslc:QueryCapability>".to_string(),
+        "          <dcterms:title>Requirements</dcterms:title>".to_string(),
+        format!(r#"          <oslc:queryBase rdf:resource="{base}/oslc/requirements"/>"#),
+        r#"          <oslc:resourceType rdf:resource="http://open-services.net/ns/rm#Requirement"/>"#
+            .to_string(),
+        "        </oslc:QueryCapability>".to_string(),
+        "      </oslc:queryCapability>".to_string(),
+        "    </oslc:Service>".to_string(),
+        "  </oslc:service>".to_string(),
+        "</oslc:ServiceProvider>".to_string(),
+    ])
+}
+
+/// A comparison of `oslc.where`, like `dcterms:identifier="REQ-1"`
+struct Condition {
+    property: String,
+    equal: bool,
+    value: String,
+}
+
+/// Parses the conditions of `oslc.where`, which have to be joined by `and`
+fn parse_where(clause: &str) -> anyhow::Result<Vec<Condition>> {
+    let mut conditions = Vec::new();
+    let mut rest = clause.trim();
+    while !rest.is_empty() {
+        let Some(operator) = rest.find(['=', '!']) else {
+            anyhow::bail!("Expected a comparison in '{rest}'");
+        };
+        let property = rest[..operator].trim().to_string();
+        let equal = !rest[operator..].starts_with("!=");
+        let value = rest[operator..].trim_start_matches(['!', '=']).trim_start();
+        let Some(value) = value.strip_prefix('"') else {
+            anyhow::bail!("Expected a quoted value after {property}");
+        };
+        let Some(end) = value.find('"') else {
+            anyhow::bail!("Unterminated value after {property}");
+        };
+        conditions.push(Condition {
+            property,
+            equal,
+            value: value[..end].to_string(),
+        });
+        rest = value[end + 1..].trim_start();
+        if let Some(next) = rest.strip_prefix("and ") {
+            rest = next.trim_start();
+        } else if !rest.is_empty() {
+            anyhow::bail!("Expected 'and' before '{rest}'");
+        }
+    }
+    Ok(conditions)
+}
+
+fn matches(project: &Project, r: &RequirementRef, condition: &Condition) -> anyhow::Result<bool> {
+    let values = match condition.property.as_str() {
+        "dcterms:identifier" => vec![r.id.to_string()],
+        "dcterms:title" => vec![r.requirement.name.trim().to_string()],
+        "dcterms:description" => vec![r.requirement.description.trim().to_string()],
+        "dcterms:subject" => subjects(project, r),
+        property => anyhow::bail!("Unsupported property {property}"),
+    };
+    Ok(values.contains(&condition.value) == condition.equal)
+}
+
+/// Lists the requirements matching the query parameters, with their properties inline
+///
+/// Supports `oslc.where` with `=` and `!=` comparisons joined by `and`, `oslc.searchTerms`
+/// searching names and descriptions, and paging with `oslc.pageSize` and `page`.
+pub fn query(
+    base: &str,
+    project: &Project,
+    parameters: &[(String, String)],
+) -> anyhow::Result<String> {
+    let parameter = |name: &str| {
+        parameters
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    };
+    let conditions = parse_where(parameter("oslc.where").unwrap_or_default())?;
+    let terms: Vec<String> = parameter("oslc.searchTerms")
+        .unwrap_or_default()
+        .split('"')
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty() && t != ",")
+        .collect();
+    let mut members = Vec::new();
+    let mut error = None;
+    project.visit_requirements(|r| {
+        let text = format!("{} {}", r.requirement.name, r.requirement.description).to_lowercase();
+        if !terms.iter().all(|t| text.contains(t.as_str())) {
+            return;
+        }
+        match conditions
+            .iter()
+            .map(|c| matches(project, &r, c))
+            .collect::<anyhow::Result<Vec<_>>>()
+        {
+            Ok(results) if results.iter().all(|m| *m) => {
+                members.push((requirement_url(base, r.id), requirement(base, project, &r)));
+            }
+            Ok(_) => {}
+            Err(e) => error = Some(e),
+        }
+    });
+    if let Some(error) = error {
+        return Err(error);
+    }
+    let total = members.len();
+    let page_size = parameter("oslc.pageSize")
+        .map(str::parse)
+        .transpose()?
+        .unwrap_or(total.max(1));
+    let page: usize = parameter("page").map(str::parse).transpose()?.unwrap_or(1);
+    let members: Vec<_> = members
+        .into_iter()
+        .skip(page.saturating_sub(1) * page_size)
+        .take(page_size)
+        .collect();
+    let query_string: Vec<_> = parameters
+        .iter()
+        .filter(|(n, _)| n != "page")
+        .map(|(n, v)| format!("{n}={}", percent_encode(v)))
+        .collect();
+    let page_url = |page: usize| {
+        let mut url = format!("{base}/oslc/requirements?");
+        for parameter in &query_string {
+            url.push_str(parameter);
+            url.push('&');
+        }
+        format!("{url}page={page}")
+    };
+    let mut output = vec![format!(
+        r#"<rdf:Description rdf:about="{base}/oslc/requirements">"#
+    )];
+    for (url, _) in &members {
+        output.push(format!(
+            r#"  <rdfs:member rdf:resource="{}"/>"#,
+            escape(url)
+        ));
+    }
+    output.push("</rdf:Description>".to_string());
+    output.push(format!(
+        r#"<oslc:ResponseInfo rdf:about="{}">"#,
+        escape(page_url(page))
+    ));
+    output.push(format!("  <oslc:totalCount>{total}</oslc:totalCount>"));
+    if page * page_size < total {
+        output.push(format!(
+            r#"  <oslc:nextPage rdf:resource="{}"/>"#,
+            escape(page_url(page + 1))
+        ));
+    }
+    output.push("</oslc:ResponseInfo>".to_string());
+    output.extend(members.into_iter().map(|(_, member)| member));
+    Ok(rdf(&output))
+}
+
+/// Answers a request to a path below `/oslc`, `None` if there is no resource at the path
+///
+/// `base` is the URL the server is reached at, used to build the resource URIs.
+pub fn handle(base: &str, project: &Project, url: &str) -> anyhow::Result<Option<String>> {
+    let (path, query_string) = url.split_once('?').unwrap_or((url, ""));
+    let parameters: Vec<_> = query_string
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let (name, value) = p.split_once('=').unwrap_or((p, ""));
+            (percent_decode(name), percent_decode(value))
+        })
+        .collect();
+    let base = base.trim_end_matches('/');
+    Ok(match path.trim_end_matches('/') {
+        "/oslc" | "/oslc/catalog" => Some(catalog(base, project)),
+        "/oslc/provider" => Some(service_provider(base, project)),
+        "/oslc/requirements" => Some(query(base, project, &parameters)?),
+        path => match path.strip_prefix("/oslc/requirements/") {
+            Some(id) => {
+                let id = percent_decode_path(id);
+                let mut found = None;
+                project.visit_requirements(|r| {
+                    if r.id == id {
+                        found = Some(requirement(base, project, &r));
+                    }
+                });
+                found.map(|r| rdf(&[r]))
+            }
+            None => None,
+        },
+    })
+}
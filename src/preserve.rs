@@ -0,0 +1,484 @@
+use std::ops::Range;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+
+use crate::format::Format;
+
+/// How nested blocks are indented in a YAML file
+struct Style {
+    /// The spaces per level of nesting
+    step: usize,
+    /// Whether sequence items are indented below their key, like `key:\n  - item`
+    indent_sequences: bool,
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+fn is_comment(line: &str) -> bool {
+    line.trim_start().starts_with('#')
+}
+
+fn is_content(line: &str) -> bool {
+    !is_blank(line) && !is_comment(line)
+}
+
+fn is_sequence_item(line: &str) -> bool {
+    let line = line.trim();
+    line == "-" || line.starts_with("- ")
+}
+
+/// Whether a line ends with the indicator of a block scalar, like `key: |-` or `- >`
+fn starts_block_scalar(line: &str) -> bool {
+    line.trim_end()
+        .rsplit(' ')
+        .next()
+        .is_some_and(|last| last.len() <= 3 && last.starts_with(['|', '>']))
+}
+
+impl Style {
+    fn detect(lines: &[&str]) -> Style {
+        // The lines of block scalars are text, not structure
+        let mut block_scalar = None;
+        let content: Vec<_> = lines
+            .iter()
+            .filter(|l| is_content(l))
+            .filter(|l| {
+                if block_scalar.is_some_and(|indent| indentation(l) > indent) {
+                    return false;
+                }
+                block_scalar = starts_block_scalar(l).then(|| indentation(l));
+                true
+            })
+            .collect();
+        let step = content
+            .iter()
+            .map(|l| indentation(l))
+            .find(|i| *i > 0)
+            .unwrap_or(2);
+        let indent_sequences = content
+            .windows(2)
+            .find(|pair| pair[0].trim_end().ends_with(':') && is_sequence_item(pair[1]))
+            .is_some_and(|pair| indentation(pair[1]) > indentation(pair[0]));
+        Style {
+            step,
+            indent_sequences,
+        }
+    }
+}
+
+/// Compares values including the order of mapping keys, which `==` ignores
+fn identical(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Mapping(a), Value::Mapping(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b)
+                    .all(|((ak, av), (bk, bv))| ak == bk && identical(av, bv))
+        }
+        (Value::Sequence(a), Value::Sequence(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| identical(a, b))
+        }
+        _ => a == b,
+    }
+}
+
+/// A key of a block mapping with the lines it spans
+struct Entry {
+    key: String,
+    /// Blank lines separating the entry from the one above, which stay in place when the
+    /// entries are reordered
+    separator: Range<usize>,
+    /// Comment lines above the key
+    leading: Range<usize>,
+    /// The line of the key and the lines of its value
+    block: Range<usize>,
+}
+
+/// Splits `key: value` into the unquoted key and the text after the colon
+fn split_key(line: &str) -> Option<(String, &str)> {
+    let line = line.trim_start();
+    let end = match line.chars().next()? {
+        quote @ ('"' | '\'') => line[1..].find(quote)? + 2,
+        _ => match line.find(": ") {
+            Some(end) => end,
+            None => line.trim_end().strip_suffix(':')?.len(),
+        },
+    };
+    let rest = line[end..].strip_prefix(':')?;
+    let key = match serde_yaml::from_str(&line[..end]).ok()? {
+        Value::String(key) => key,
+        Value::Number(key) => key.to_string(),
+        _ => return None,
+    };
+    Some((key, rest))
+}
+
+/// Splits the lines in `range` into the entries of a mapping indented by `indent` and the
+/// blank and comment lines after the last one, `None` if they are not a block mapping
+fn parse_entries(
+    lines: &[&str],
+    range: Range<usize>,
+    indent: usize,
+) -> Option<(Vec<Entry>, Range<usize>)> {
+    let mut entries = Vec::new();
+    let mut start = range.start;
+    loop {
+        let Some(key_line) = (start..range.end).find(|i| is_content(lines[*i])) else {
+            return Some((entries, start..range.end));
+        };
+        let line = lines[key_line];
+        if indentation(line) != indent || is_sequence_item(line) {
+            return None;
+        }
+        let (key, _) = split_key(line)?;
+        let mut end = key_line + 1;
+        for (i, line) in lines.iter().enumerate().take(range.end).skip(key_line + 1) {
+            if is_blank(line) {
+                continue;
+            }
+            let nested = indentation(line) > indent;
+            if is_content(line)
+                && !nested
+                && !(indentation(line) == indent && is_sequence_item(line))
+            {
+                break;
+            }
+            // Comments up to the indentation of the key belong to the next entry
+            if is_content(line) || nested {
+                end = i + 1;
+            }
+        }
+        let comment = (start..key_line)
+            .find(|i| !is_blank(lines[*i]))
+            .unwrap_or(key_line);
+        entries.push(Entry {
+            key,
+            separator: start..comment,
+            leading: comment..key_line,
+            block: key_line..end,
+        });
+        start = end;
+    }
+}
+
+struct Editor<'a> {
+    lines: Vec<&'a str>,
+    style: Style,
+    output: String,
+}
+
+impl Editor<'_> {
+    fn copy(&mut self, range: Range<usize>) {
+        for line in &self.lines[range] {
+            self.output.push_str(line);
+        }
+    }
+
+    /// Writes a scalar after a key or sequence item, re-indenting block scalars to `indent`
+    fn write_scalar(&mut self, value: &Value, indent: usize) -> Option<()> {
+        let text = serde_yaml::to_string(value).ok()?;
+        let mut lines = text.lines();
+        self.output.push_str(lines.next()?);
+        self.output.push('\n');
+        for line in lines {
+            if !line.is_empty() {
+                self.output.push_str(&" ".repeat(indent));
+                self.output
+                    .push_str(line.strip_prefix("  ").unwrap_or(line));
+            }
+            self.output.push('\n');
+        }
+        Some(())
+    }
+
+    /// Writes a new entry in the style of the file
+    fn write_entry(&mut self, key: &str, value: &Value, indent: usize) -> Option<()> {
+        let key = serde_yaml::to_string(key).ok()?;
+        self.output.push_str(&" ".repeat(indent));
+        self.output.push_str(key.trim_end());
+        self.output.push(':');
+        match value {
+            Value::Mapping(mapping) if !mapping.is_empty() => {
+                self.output.push('\n');
+                for (key, value) in mapping {
+                    self.write_entry(key.as_str()?, value, indent + self.style.step)?;
+                }
+            }
+            Value::Sequence(items) if !items.is_empty() => {
+                self.output.push('\n');
+                let indent = indent
+                    + if self.style.indent_sequences {
+                        self.style.step
+                    } else {
+                        0
+                    };
+                for item in items {
+                    self.write_item(item, indent)?;
+                }
+            }
+            _ => {
+                self.output.push(' ');
+                self.write_scalar(value, indent + self.style.step)?;
+            }
+        }
+        Some(())
+    }
+
+    fn write_item(&mut self, item: &Value, indent: usize) -> Option<()> {
+        let nested = match item {
+            Value::Mapping(mapping) if !mapping.is_empty() => {
+                let start = self.output.len();
+                for (key, value) in mapping {
+                    self.write_entry(key.as_str()?, value, indent + 2)?;
+                }
+                start
+            }
+            _ => {
+                self.output.push_str(&" ".repeat(indent));
+                self.output.push_str("- ");
+                return self.write_scalar(item, indent + 2);
+            }
+        };
+        // The first key goes on the line of the dash
+        self.output.replace_range(
+            nested..nested + indent + 2,
+            &format!("{}- ", " ".repeat(indent)),
+        );
+        Some(())
+    }
+
+    /// Writes the entry under `key`, which differs from the one in the file if it was renamed,
+    /// with a value that may have changed. Descends into block mappings so only the changed keys
+    /// are rewritten.
+    fn update_entry(
+        &mut self,
+        entry: &Entry,
+        key: &str,
+        separator: Range<usize>,
+        old: Option<&Value>,
+        new: &Value,
+        indent: usize,
+    ) -> Option<()> {
+        self.copy(separator);
+        self.copy(entry.leading.clone());
+        let key_line = self.lines[entry.block.start];
+        let (_, rest) = split_key(key_line)?;
+        let inline = !rest.trim().is_empty() && !rest.trim_start().starts_with('#');
+        let child_indent = self.lines[entry.block.start + 1..entry.block.end]
+            .iter()
+            .find(|l| is_content(l))
+            .map(|l| indentation(l));
+        let unchanged = old.is_some_and(|old| identical(old, new));
+        let nested = match (old, new, child_indent) {
+            (Some(Value::Mapping(old)), Value::Mapping(new), Some(child_indent))
+                if !unchanged && !inline && !new.is_empty() && child_indent > indent =>
+            {
+                let range = entry.block.start + 1..entry.block.end;
+                Some((parse_entries(&self.lines, range, child_indent)?, old, new))
+            }
+            _ if unchanged => None,
+            _ => return self.write_entry(key, new, indent),
+        };
+        if key == entry.key {
+            self.copy(entry.block.start..entry.block.start + 1);
+        } else {
+            let key = serde_yaml::to_string(key).ok()?;
+            self.output.push_str(&" ".repeat(indent));
+            self.output.push_str(key.trim_end());
+            self.output.push(':');
+            self.output.push_str(rest);
+        }
+        match nested {
+            Some(((entries, trailer), old, new)) => {
+                let child_indent = child_indent?;
+                self.update_mapping(&entries, trailer, old, new, child_indent)
+            }
+            None => {
+                self.copy(entry.block.start + 1..entry.block.end);
+                Some(())
+            }
+        }
+    }
+
+    fn update_mapping(
+        &mut self,
+        entries: &[Entry],
+        trailer: Range<usize>,
+        old: &Mapping,
+        new: &Mapping,
+        indent: usize,
+    ) -> Option<()> {
+        let keys = new
+            .keys()
+            .map(|k| k.as_str().map(str::to_string))
+            .collect::<Option<Vec<_>>>()?;
+        let known = |key: &str| old.contains_key(key) || new.contains_key(key);
+        // Keys only in the file, like explicit defaults, move with the entry above them
+        let mut unknown = Vec::new();
+        let mut groups: Vec<(&Entry, Vec<&Entry>)> = Vec::new();
+        for entry in entries {
+            if known(&entry.key) {
+                groups.push((entry, Vec::new()));
+            } else if let Some((_, attached)) = groups.last_mut() {
+                attached.push(entry);
+            } else {
+                unknown.push(entry);
+            }
+        }
+        // The key each entry of the file is written under, a removed key replaced by a new one
+        // at the same position is a rename and keeps its comments and formatting
+        let targets: Vec<Option<&str>> = groups
+            .iter()
+            .enumerate()
+            .map(|(index, (entry, _))| {
+                if new.contains_key(entry.key.as_str()) {
+                    return Some(entry.key.as_str());
+                }
+                keys.get(index)
+                    .filter(|key| !groups.iter().any(|(e, _)| e.key == **key))
+                    .map(String::as_str)
+            })
+            .collect();
+        let group = |key: &str| {
+            groups
+                .iter()
+                .zip(&targets)
+                .find_map(|(group, target)| (*target == Some(key)).then_some(group))
+        };
+        let in_file: Vec<_> = targets.iter().flatten().map(|k| k.to_string()).collect();
+        let mut separators = groups
+            .iter()
+            .zip(&targets)
+            .filter(|(_, target)| target.is_some())
+            .map(|((entry, _), _)| entry.separator.clone());
+        // Keep the order of the file unless the edit reordered its keys
+        let order = if keys
+            .iter()
+            .filter(|k| group(k).is_some())
+            .eq(in_file.iter())
+        {
+            let mut order = in_file;
+            for (index, key) in keys.iter().enumerate() {
+                if group(key).is_none() {
+                    let position = keys[..index]
+                        .iter()
+                        .rev()
+                        .find_map(|previous| order.iter().position(|k| k == previous))
+                        .map_or(0, |p| p + 1);
+                    order.insert(position, key.clone());
+                }
+            }
+            order
+        } else {
+            keys.clone()
+        };
+        for entry in unknown {
+            self.copy(entry.separator.start..entry.block.end);
+        }
+        for key in &order {
+            let value = &new[key.as_str()];
+            match group(key) {
+                Some((entry, attached)) => {
+                    let separator = separators.next()?;
+                    let old = old.get(entry.key.as_str());
+                    self.update_entry(entry, key, separator, old, value, indent)?;
+                    for entry in attached {
+                        self.copy(entry.separator.start..entry.block.end);
+                    }
+                }
+                None if old
+                    .get(key.as_str())
+                    .is_some_and(|old| identical(old, value)) => {}
+                None => self.write_entry(key, value, indent)?,
+            }
+        }
+        for ((_, attached), target) in groups.iter().zip(&targets) {
+            if target.is_none() {
+                for entry in attached {
+                    self.copy(entry.separator.start..entry.block.end);
+                }
+            }
+        }
+        self.copy(trailer);
+        Some(())
+    }
+}
+
+fn update_yaml(original: &str, old: &Value, new: &Value) -> Option<String> {
+    let (Value::Mapping(old), Value::Mapping(new)) = (old, new) else {
+        return None;
+    };
+    let lines: Vec<_> = original.split_inclusive('\n').collect();
+    let (entries, trailer) = parse_entries(&lines, 0..lines.len(), 0)?;
+    let mut editor = Editor {
+        style: Style::detect(&lines),
+        lines,
+        output: String::with_capacity(original.len()),
+    };
+    editor.update_mapping(&entries, trailer, old, new, 0)?;
+    Some(editor.output)
+}
+
+/// The comments in `content`, trimmed, which rewriting the lines they are on drops
+///
+/// A marker after a space inside a quoted value counts as a comment too, so this may find more
+/// than there are, but never less.
+#[must_use]
+pub fn comments(content: &str, format: Format) -> Vec<&str> {
+    let marker = match format {
+        Format::Yaml | Format::Toml => "#",
+        Format::Rsn => "//",
+        Format::Json => return Vec::new(),
+    };
+    content
+        .lines()
+        .map(str::trim)
+        .filter_map(|line| {
+            if line.starts_with(marker) {
+                return Some(line);
+            }
+            let start = line.find(&format!(" {marker}"))?;
+            Some(line[start..].trim_start())
+        })
+        .collect()
+}
+
+/// Writes an edited project or included fragment back into the content of its file
+///
+/// YAML files keep their comments, blank lines and layout, only the keys whose values changed
+/// are rewritten, in the indentation of the file. Other formats and YAML this cannot edit,
+/// like flow mappings or anchors, are rewritten entirely.
+pub fn update<T: Serialize + DeserializeOwned>(
+    original: &str,
+    format: Format,
+    project: &T,
+) -> anyhow::Result<String> {
+    if format == Format::Yaml {
+        let mut original = original.to_string();
+        if !original.ends_with('\n') {
+            original.push('\n');
+        }
+        let old = serde_yaml::to_value(format.parse::<T>(&original)?)?;
+        let new = serde_yaml::to_value(project)?;
+        match update_yaml(&original, &old, &new) {
+            // Only keep the edit if it reads back as the edited project
+            Some(updated)
+                if format
+                    .parse::<T>(&updated)
+                    .is_ok_and(|p| serde_yaml::to_value(p).is_ok_and(|v| identical(&v, &new))) =>
+            {
+                return Ok(updated);
+            }
+            _ => tracing::info!("Could not keep the formatting, rewriting the whole file"),
+        }
+    }
+    format.serialize(project)
+}
@@ -0,0 +1,187 @@
+use std::path::PathBuf;
+
+use indexmap::IndexMap;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{redact, Classification, Priority, Project, Topic};
+
+/// A part of the rendered document a profile can leave out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Section {
+    Description,
+    Requirements,
+    Definitions,
+    ConfigDefaults,
+}
+
+/// The language of the headings and fixed text of rendered documents
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    De,
+}
+
+/// How key words and IDs are marked up in rendered documents
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolStyle {
+    /// Key words in bold italic capitals and IDs in italics, like `**_MUST_**` and `_REQ-1_`
+    #[default]
+    Emphasized,
+    /// Key words in capitals and IDs as they are, for documents read as plain text
+    Plain,
+}
+
+/// The fixed text of rendered documents in one language
+pub struct Labels {
+    pub title: &'static str,
+    pub key_words: &'static str,
+    pub version: &'static str,
+    pub description: &'static str,
+    pub requirements: &'static str,
+    pub definitions: &'static str,
+    pub config_defaults: &'static str,
+    pub typ: &'static str,
+    pub env: &'static str,
+    pub unit: &'static str,
+    pub valid_values: &'static str,
+    pub default_value: &'static str,
+    pub required: &'static str,
+    pub required_hint: &'static str,
+    pub links: &'static str,
+    pub priority: &'static str,
+}
+
+const ENGLISH: Labels = Labels {
+    title: "Requirements for",
+    key_words: r#"The key words "MUST", "MUST NOT", "REQUIRED", "SHALL", "SHALL NOT", "SHOULD", "SHOULD NOT", "RECOMMENDED",
+"MAY", and "OPTIONAL" in this document are to be interpreted as described in
+[RFC 2119](https://datatracker.ietf.org/doc/html/rfc2119).
+"#,
+    version: "VERSION",
+    description: "Description",
+    requirements: "Requirements",
+    definitions: "Definitions",
+    config_defaults: "Config Defaults",
+    typ: "Type",
+    env: "Environment Variable",
+    unit: "Unit",
+    valid_values: "Valid Values",
+    default_value: "Default Value",
+    required: "Required",
+    required_hint: "This value must be provided as a start parameter.",
+    links: "Links",
+    priority: "Priority",
+};
+
+const GERMAN: Labels = Labels {
+    title: "Anforderungen an",
+    key_words: r#"Die Schlüsselwörter "MUST", "MUST NOT", "REQUIRED", "SHALL", "SHALL NOT", "SHOULD", "SHOULD NOT",
+"RECOMMENDED", "MAY" und "OPTIONAL" in diesem Dokument sind wie in
+[RFC 2119](https://datatracker.ietf.org/doc/html/rfc2119) beschrieben zu interpretieren.
+"#,
+    version: "VERSION",
+    description: "Beschreibung",
+    requirements: "Anforderungen",
+    definitions: "Definitionen",
+    config_defaults: "Konfiguration",
+    typ: "Typ",
+    env: "Umgebungsvariable",
+    unit: "Einheit",
+    valid_values: "Gültige Werte",
+    default_value: "Standardwert",
+    required: "Erforderlich",
+    required_hint: "Dieser Wert muss als Startparameter angegeben werden.",
+    links: "Verweise",
+    priority: "Priorität",
+};
+
+impl Language {
+    #[must_use]
+    pub fn labels(self) -> &'static Labels {
+        match self {
+            Language::En => &ENGLISH,
+            Language::De => &GERMAN,
+        }
+    }
+}
+
+/// A named set of rendering options for one audience, configured under `profiles`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    /// Regexes selecting the requirements to include by ID, all are included if empty
+    pub requirements: Vec<String>,
+    /// IDs of the topics to include with their subtopics, all are included if empty
+    pub topics: Vec<String>,
+    /// Only include requirements with at least this priority, those without one count as low
+    pub min_priority: Option<Priority>,
+    /// Redact content classified above this level
+    pub max_classification: Option<Classification>,
+    /// The sections to include, all are included if empty
+    pub sections: Vec<Section>,
+    /// An HTML template replacing the configured or built-in one
+    pub template: Option<PathBuf>,
+    /// The language of headings and fixed text
+    pub language: Language,
+    /// How key words and IDs are marked up
+    pub symbols: SymbolStyle,
+}
+
+/// Keeps the topics in `ids` and the ancestors of those, dropping the requirements of ancestors
+fn retain_topics(topics: &mut IndexMap<String, Topic>, ids: &[String]) {
+    topics.retain(|id, topic| {
+        if ids.iter().any(|i| i == id.trim()) {
+            return true;
+        }
+        topic.requirements.clear();
+        retain_topics(&mut topic.subtopics, ids);
+        !topic.subtopics.is_empty()
+    });
+}
+
+/// Keeps the requirements matching one of `filters` and the topics containing any of them
+fn retain_requirements(topics: &mut IndexMap<String, Topic>, filters: &[Regex]) {
+    topics.retain(|_, topic| {
+        topic
+            .requirements
+            .retain(|id, _| filters.iter().any(|f| f.is_match(id.trim())));
+        retain_requirements(&mut topic.subtopics, filters);
+        !topic.requirements.is_empty() || !topic.subtopics.is_empty()
+    });
+}
+
+impl Profile {
+    /// Whether the section is rendered
+    #[must_use]
+    pub fn includes(&self, section: Section) -> bool {
+        self.sections.is_empty() || self.sections.contains(&section)
+    }
+
+    /// Removes the topics and requirements the audience should not see and redacts the content
+    /// above its classification
+    pub fn apply(&self, project: &mut Project) -> anyhow::Result<()> {
+        if !self.topics.is_empty() {
+            retain_topics(&mut project.topics, &self.topics);
+        }
+        if !self.requirements.is_empty() {
+            let filters = self
+                .requirements
+                .iter()
+                .map(|r| Regex::new(&format!("^(?:{r})$")))
+                .collect::<Result<Vec<_>, _>>()?;
+            retain_requirements(&mut project.topics, &filters);
+        }
+        if let Some(min) = self.min_priority {
+            project.retain_priority(min);
+        }
+        if let Some(max) = self.max_classification {
+            redact::redact(project, max);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+use indexmap::IndexMap;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::lint::{word_regex, KEYWORDS, VAGUE_TERMS};
+use crate::Project;
+
+/// Quantifiers claiming something for every case, which can rarely be verified
+pub const UNBOUNDED_QUANTIFIERS: [&str; 11] = [
+    "all",
+    "any",
+    "every",
+    "each",
+    "always",
+    "never",
+    "none",
+    "everything",
+    "anything",
+    "whatever",
+    "whenever",
+];
+
+/// Pronouns that refer to something outside the requirement when they start a sentence
+const DEMONSTRATIVES: [&str; 4] = ["this", "that", "these", "those"];
+/// Pronouns that need a referent, which a standalone requirement usually lacks
+const PRONOUNS: [&str; 5] = ["it", "its", "they", "them", "their"];
+
+/// The quality of one requirement's description
+#[derive(Debug, Clone, Serialize)]
+pub struct Score {
+    pub id: String,
+    pub name: String,
+    /// How well the requirement can be verified, from 0 to 100, higher is better
+    pub testability: u32,
+    /// How open to interpretation the requirement is, from 0 to 100, lower is better
+    pub ambiguity: u32,
+    /// Both scores combined for ranking, from 0 to 100, higher is worse
+    pub badness: u32,
+    /// The problems found, explaining the scores
+    pub issues: Vec<String>,
+}
+
+struct Rules {
+    keywords: Regex,
+    measurable: Regex,
+    vague: Regex,
+    quantifiers: Regex,
+    pronouns: Regex,
+    demonstratives: Regex,
+}
+
+impl Rules {
+    fn new() -> Rules {
+        Rules {
+            keywords: word_regex(&KEYWORDS),
+            measurable: Regex::new(
+                r"(?i)\d|%|\b(at least|at most|within|less than|more than|fewer than|no more than|maximum|minimum|exactly|percent|between)\b",
+            )
+            .expect("Valid regex"),
+            vague: word_regex(&VAGUE_TERMS),
+            quantifiers: word_regex(&UNBOUNDED_QUANTIFIERS),
+            pronouns: word_regex(&PRONOUNS),
+            demonstratives: Regex::new(&format!(
+                r"(?i)(^|[.!?:]\s+)({})\b",
+                DEMONSTRATIVES.join("|")
+            ))
+            .expect("Valid regex"),
+        }
+    }
+
+    fn score(&self, id: &str, name: &str, description: &str) -> Score {
+        let mut issues = Vec::new();
+        let mut testability: u32 = 100;
+        let mut ambiguity: u32 = 0;
+        if !self.keywords.is_match(description) {
+            testability -= 40;
+            issues.push("no RFC 2119 keyword".to_string());
+        }
+        if !self.measurable.is_match(description) {
+            testability -= 30;
+            issues.push("no measurable criterion".to_string());
+        }
+        for term in self.vague.find_iter(description) {
+            testability = testability.saturating_sub(10);
+            ambiguity += 20;
+            issues.push(format!("vague term '{}'", term.as_str()));
+        }
+        for quantifier in self.quantifiers.find_iter(description) {
+            ambiguity += 15;
+            issues.push(format!("unbounded quantifier '{}'", quantifier.as_str()));
+        }
+        let pronouns = self.pronouns.find_iter(description).map(|m| m.as_str());
+        let demonstratives = self
+            .demonstratives
+            .captures_iter(description)
+            .filter_map(|c| c.get(2))
+            .map(|m| m.as_str());
+        for pronoun in pronouns.chain(demonstratives) {
+            ambiguity += 20;
+            issues.push(format!("pronoun '{pronoun}' without referent"));
+        }
+        let ambiguity = ambiguity.min(100);
+        Score {
+            id: id.to_string(),
+            name: name.trim().to_string(),
+            testability,
+            ambiguity,
+            badness: (100 - testability + ambiguity) / 2,
+            issues,
+        }
+    }
+}
+
+/// Scores every requirement and groups the scores by the names of their topics, worst first
+///
+/// Only the `top` worst requirements with issues are kept for each topic.
+#[must_use]
+pub fn report(project: &Project, top: usize) -> IndexMap<String, Vec<Score>> {
+    let rules = Rules::new();
+    let mut topics: IndexMap<String, Vec<Score>> = IndexMap::new();
+    project.visit_requirements(|r| {
+        let topic = (1..=r.topic_path.len())
+            .filter_map(|depth| project.topic(&r.topic_path[..depth]))
+            .map(|t| t.name.trim().to_string())
+            .collect::<Vec<_>>()
+            .join(" > ");
+        let score = rules.score(r.id, &r.requirement.name, &r.requirement.description);
+        topics.entry(topic).or_default().push(score);
+    });
+    for scores in topics.values_mut() {
+        scores.retain(|s| !s.issues.is_empty());
+        // Stable, so equally bad requirements stay in document order
+        scores.sort_by_key(|s| std::cmp::Reverse(s.badness));
+        scores.truncate(top);
+    }
+    topics.retain(|_, scores| !scores.is_empty());
+    topics
+}
+
+/// Renders the report as Markdown with a table per topic
+#[must_use]
+pub fn to_markdown(project: &Project, report: &IndexMap<String, Vec<Score>>) -> String {
+    let mut output = vec![format!("# Requirement Quality - {}", project.name.trim())];
+    if report.is_empty() {
+        output.push(String::new());
+        output.push("No requirement has quality issues.".to_string());
+    }
+    for (topic, scores) in report {
+        output.extend([
+            String::new(),
+            format!("## {topic}"),
+            String::new(),
+            "| Requirement | Testability | Ambiguity | Issues |".to_string(),
+            "| --- | --- | --- | --- |".to_string(),
+        ]);
+        for score in scores {
+            output.push(format!(
+                "| _{}_ - {} | {} | {} | {} |",
+                score.id,
+                score.name,
+                score.testability,
+                score.ambiguity,
+                score.issues.join(", ")
+            ));
+        }
+    }
+    output.join("\n")
+}
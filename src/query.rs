@@ -0,0 +1,206 @@
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+fn parse_path(path: &str) -> anyhow::Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let mut chars = path.trim().chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => match chars.peek() {
+                Some('"') => {
+                    chars.next();
+                    let mut key = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some('\\') => key.extend(chars.next()),
+                            Some(c) => key.push(c),
+                            None => anyhow::bail!("Unterminated quote in '{path}'"),
+                        }
+                    }
+                    segments.push(Segment::Key(key));
+                }
+                Some(c) if *c != '.' && *c != '[' => {
+                    let mut key = String::new();
+                    while let Some(c) = chars.next_if(|c| *c != '.' && *c != '[') {
+                        key.push(c);
+                    }
+                    segments.push(Segment::Key(key));
+                }
+                _ => {}
+            },
+            '[' => {
+                let mut index = String::new();
+                while let Some(c) = chars.next_if(|c| *c != ']') {
+                    index.push(c);
+                }
+                if chars.next() != Some(']') {
+                    anyhow::bail!("Unterminated index in '{path}'");
+                }
+                segments.push(Segment::Index(index.trim().parse()?));
+            }
+            c => anyhow::bail!("Unexpected '{c}' in '{path}'"),
+        }
+    }
+    Ok(segments)
+}
+
+fn apply_filter(value: Value, filter: &str) -> anyhow::Result<Value> {
+    Ok(match (filter, value) {
+        ("keys", Value::Object(map)) => map.keys().map(|k| Value::String(k.clone())).collect(),
+        ("values", Value::Object(map)) => map.into_iter().map(|(_, v)| v).collect(),
+        ("values", Value::Array(array)) => Value::Array(array),
+        ("length", Value::Object(map)) => map.len().into(),
+        ("length", Value::Array(array)) => array.len().into(),
+        ("length", Value::String(string)) => string.chars().count().into(),
+        ("length", Value::Null) => 0.into(),
+        (filter @ ("keys" | "values" | "length"), value) => {
+            anyhow::bail!("Cannot apply '{filter}' to {value}")
+        }
+        (filter, _) => anyhow::bail!("Unknown filter '{filter}'"),
+    })
+}
+
+/// Splits an expression at the `|` outside of quoted keys
+fn split_pipes(expression: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+    for (index, c) in expression.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '|' if !quoted => {
+                parts.push(&expression[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&expression[start..]);
+    parts
+}
+
+/// Evaluates an expression like `.topics."TOPIC-1".requirements | keys` against `value`
+///
+/// Paths consist of `.key`, `."quoted.key"` and `[index]` segments and can be
+/// followed by the filters `keys`, `values` and `length`, separated by `|`.
+pub fn query(value: &Value, expression: &str) -> anyhow::Result<Value> {
+    let mut parts = split_pipes(expression).into_iter();
+    let path = parts.next().unwrap_or_default();
+    let mut current = value;
+    for segment in parse_path(path)? {
+        current = match &segment {
+            Segment::Key(key) => current
+                .get(key)
+                .ok_or_else(|| anyhow::anyhow!("No key '{key}'"))?,
+            Segment::Index(index) => current
+                .get(index)
+                .ok_or_else(|| anyhow::anyhow!("No index {index}"))?,
+        };
+    }
+    parts.try_fold(current.clone(), |value, filter| {
+        apply_filter(value, filter.trim())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn value() -> Value {
+        json!({
+            "topics": {
+                "TOPIC-1": {"requirements": {"REQ-1.1": {"name": "A"}, "REQ-1.2": {"name": "B"}}},
+                "a|b": {"name": "Pipe"},
+                "a.b": {"name": "Dot"},
+                "say \"hi\"": {"name": "Quote"},
+            },
+            "includes": ["a.yml", "b.yml"],
+        })
+    }
+
+    #[test]
+    fn paths_select_keys_and_indices() {
+        assert_eq!(
+            query(&value(), r#".topics."TOPIC-1".requirements."REQ-1.2".name"#).unwrap(),
+            json!("B")
+        );
+        assert_eq!(query(&value(), ".includes[1]").unwrap(), json!("b.yml"));
+        assert_eq!(query(&value(), "").unwrap(), value());
+        assert_eq!(query(&value(), ".").unwrap(), value());
+    }
+
+    #[test]
+    fn quoted_keys_can_contain_dots_pipes_and_escaped_quotes() {
+        assert_eq!(
+            query(&value(), r#".topics."a.b".name"#).unwrap(),
+            json!("Dot")
+        );
+        assert_eq!(
+            query(&value(), r#".topics."a|b".name"#).unwrap(),
+            json!("Pipe")
+        );
+        assert_eq!(
+            query(&value(), r#".topics."a|b" | length"#).unwrap(),
+            json!(1)
+        );
+        assert_eq!(
+            query(&value(), r#".topics."say \"hi\"".name"#).unwrap(),
+            json!("Quote")
+        );
+    }
+
+    #[test]
+    fn filters_are_applied_in_order() {
+        assert_eq!(
+            query(&value(), r#".topics."TOPIC-1".requirements | keys"#).unwrap(),
+            json!(["REQ-1.1", "REQ-1.2"])
+        );
+        assert_eq!(
+            query(
+                &value(),
+                r#".topics."TOPIC-1".requirements | keys | length"#
+            )
+            .unwrap(),
+            json!(2)
+        );
+        assert_eq!(
+            query(&value(), r#".topics."TOPIC-1".requirements|values|length"#).unwrap(),
+            json!(2)
+        );
+        assert_eq!(
+            query(&value(), ".includes | values").unwrap(),
+            json!(["a.yml", "b.yml"])
+        );
+        assert_eq!(
+            query(&value(), ".topics.\"a.b\".name | length").unwrap(),
+            json!(3)
+        );
+    }
+
+    #[test]
+    fn invalid_expressions_fail() {
+        for expression in [
+            ".missing",
+            ".includes[2]",
+            ".includes[x]",
+            ".includes[0",
+            r#".topics."TOPIC-1"#,
+            "topics",
+            ".includes | keys",
+            ".includes | sort",
+        ] {
+            assert!(query(&value(), expression).is_err(), "{expression}");
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use indexmap::IndexMap;
+
+use crate::{Classification, Project, Requirement, Topic};
+
+/// Replaces the names of redacted topics and requirements
+pub const PLACEHOLDER: &str = "[REDACTED]";
+
+fn redact_requirement(requirement: &mut Requirement, classification: Classification) {
+    requirement.name = PLACEHOLDER.to_string();
+    requirement.description = format!("{PLACEHOLDER} ({classification})");
+    requirement.additional_info.clear();
+}
+
+fn redact_topics(
+    topics: &mut IndexMap<String, Topic>,
+    inherited: Classification,
+    max: Classification,
+) -> usize {
+    let mut redacted = 0;
+    for topic in topics.values_mut() {
+        let classification = topic.classification.unwrap_or(inherited).max(inherited);
+        if classification > max {
+            topic.name = PLACEHOLDER.to_string();
+        }
+        for requirement in topic.requirements.values_mut() {
+            let own = requirement.classification.unwrap_or(classification);
+            let effective = own.max(classification);
+            if effective > max {
+                redact_requirement(requirement, effective);
+                redacted += 1;
+            }
+        }
+        redacted += redact_topics(&mut topic.subtopics, classification, max);
+    }
+    redacted
+}
+
+/// Replaces the content of topics and requirements classified above `max` with placeholders,
+/// returning the number of redacted requirements
+///
+/// IDs, links and the topic structure are kept, so references into the redacted parts stay
+/// valid. Requirements without a classification have the one of their topic, topics without
+/// one are public.
+pub fn redact(project: &mut Project, max: Classification) -> usize {
+    let mut redacted = redact_topics(&mut project.topics, Classification::Public, max);
+    for requirement in project.archive.values_mut() {
+        let classification = requirement.classification.unwrap_or(Classification::Public);
+        if classification > max {
+            redact_requirement(requirement, classification);
+            redacted += 1;
+        }
+    }
+    redacted
+}
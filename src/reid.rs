@@ -0,0 +1,69 @@
+use indexmap::IndexMap;
+
+use crate::{Project, Requirement, Topic};
+
+/// Assigns new IDs `<prefix><start>`, `<prefix><start + step>`, ... to all requirements in document order
+///
+/// IDs of archived requirements are skipped. Fails if `step` is 0, the numbers run out or a
+/// requirement is defined more than once, as two requirements would end up with the same ID.
+pub fn mapping(
+    project: &Project,
+    prefix: &str,
+    start: u64,
+    step: u64,
+) -> anyhow::Result<IndexMap<String, String>> {
+    if step == 0 {
+        anyhow::bail!("The step between IDs must be at least 1");
+    }
+    let mut ids = Vec::new();
+    project.visit_requirements(|r| ids.push(r.id.to_string()));
+    let mut mapping = IndexMap::new();
+    let mut next = Some(start);
+    for id in ids {
+        let new = loop {
+            let Some(number) = next else {
+                anyhow::bail!("Ran out of numbers for IDs starting with {prefix}");
+            };
+            next = number.checked_add(step);
+            let new = format!("{prefix}{number}");
+            if !project.is_archived(&new) {
+                break new;
+            }
+        };
+        if mapping.insert(id.clone(), new).is_some() {
+            anyhow::bail!("Requirement {id} is defined more than once");
+        }
+    }
+    Ok(mapping)
+}
+
+fn rename_links(requirement: &mut Requirement, mapping: &IndexMap<String, String>) {
+    for link in &mut requirement.links {
+        if let Some(new) = mapping.get(link.trim()) {
+            *link = new.clone();
+        }
+    }
+}
+
+fn rename_in_topics(topics: &mut IndexMap<String, Topic>, mapping: &IndexMap<String, String>) {
+    for topic in topics.values_mut() {
+        topic.requirements = std::mem::take(&mut topic.requirements)
+            .into_iter()
+            .map(|(id, mut requirement)| {
+                rename_links(&mut requirement, mapping);
+                let id = mapping.get(id.trim()).cloned().unwrap_or(id);
+                (id, requirement)
+            })
+            .collect();
+        rename_in_topics(&mut topic.subtopics, mapping);
+    }
+}
+
+/// Renames requirements according to `mapping` and updates all links pointing to them,
+/// including those of archived requirements
+pub fn apply(project: &mut Project, mapping: &IndexMap<String, String>) {
+    rename_in_topics(&mut project.topics, mapping);
+    for requirement in project.archive.values_mut() {
+        rename_links(requirement, mapping);
+    }
+}
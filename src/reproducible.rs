@@ -0,0 +1,73 @@
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The environment variable fixing the time embedded in generated artifacts, in seconds since
+/// the Unix epoch, see <https://reproducible-builds.org/specs/source-date-epoch/>
+pub const SOURCE_DATE_EPOCH: &str = "SOURCE_DATE_EPOCH";
+
+/// Reads `SOURCE_DATE_EPOCH`, `None` if it is not set or empty
+pub fn source_date_epoch() -> anyhow::Result<Option<u64>> {
+    match std::env::var(SOURCE_DATE_EPOCH) {
+        Ok(value) if value.trim().is_empty() => Ok(None),
+        Ok(value) => value.trim().parse().map(Some).map_err(|_| {
+            anyhow::anyhow!(
+                "Invalid {SOURCE_DATE_EPOCH} '{value}', expected seconds since the Unix epoch"
+            )
+        }),
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(e) => anyhow::bail!("Invalid {SOURCE_DATE_EPOCH}: {e}"),
+    }
+}
+
+/// The time to embed in generated artifacts, in seconds since the Unix epoch
+///
+/// `SOURCE_DATE_EPOCH` takes precedence. Without it, artifacts get the Unix epoch itself, so
+/// they stay byte-stable, unless `current_time` asks for the time they were generated at.
+pub fn timestamp(current_time: bool) -> anyhow::Result<u64> {
+    match source_date_epoch()? {
+        Some(epoch) => Ok(epoch),
+        None if current_time => Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs()),
+        None => Ok(0),
+    }
+}
+
+/// Formats seconds since the Unix epoch as ISO 8601 in UTC, like `1970-01-01T00:00:00Z`
+///
+/// Calculated by hand instead of with the system's time functions, so neither the time zone
+/// nor the locale affect the result.
+#[must_use]
+pub fn iso8601(seconds: u64) -> String {
+    let (days, time) = (seconds / 86_400, seconds % 86_400);
+    // Days to civil dates, counting from 0000-03-01 so leap days end each 4, 100 and 400 year era
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
+
+/// Writes a path with forward slashes, so output mentioning files is the same on every platform
+#[must_use]
+pub fn portable_path(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    if std::path::MAIN_SEPARATOR == '/' {
+        path.into_owned()
+    } else {
+        path.replace(std::path::MAIN_SEPARATOR, "/")
+    }
+}
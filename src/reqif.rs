@@ -0,0 +1,654 @@
+use std::collections::HashMap;
+
+use clap::ValueEnum;
+use indexmap::IndexMap;
+use quick_xml::escape::escape;
+use quick_xml::events::Event;
+
+use crate::import::{self, empty_project};
+use crate::{reproducible, Project, Requirement, Topic};
+
+/// The attribute names used by a tool for the requirement fields
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    /// The standard `ReqIF.*` names Polarion maps work item fields to
+    Polarion,
+    /// Plain names like `ID` and `Description`
+    Generic,
+}
+
+/// Which ReqIF attributes hold which requirement fields
+#[derive(Debug, Clone)]
+pub struct AttributeMap {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// The title of objects that are headings
+    pub heading: String,
+    pub additional_info: String,
+}
+
+impl AttributeMap {
+    #[must_use]
+    pub fn preset(preset: Preset) -> AttributeMap {
+        let names = match preset {
+            Preset::Polarion => [
+                "ReqIF.ForeignID",
+                "ReqIF.Name",
+                "ReqIF.Text",
+                "ReqIF.ChapterName",
+                "Additional Info",
+            ],
+            Preset::Generic => ["ID", "Name", "Description", "Heading", "Additional Info"],
+        };
+        AttributeMap {
+            id: names[0].to_string(),
+            name: names[1].to_string(),
+            description: names[2].to_string(),
+            heading: names[3].to_string(),
+            additional_info: names[4].to_string(),
+        }
+    }
+
+    /// Replaces the attributes of the fields named by mappings like `id=Identifier`
+    pub fn with_mappings<S: AsRef<str>>(mut self, mappings: &[S]) -> anyhow::Result<AttributeMap> {
+        for mapping in mappings {
+            let Some((field, attribute)) = mapping.as_ref().split_once('=') else {
+                anyhow::bail!(
+                    "Invalid mapping '{}', expected field=attribute",
+                    mapping.as_ref()
+                );
+            };
+            let attribute = attribute.trim().to_string();
+            match field.trim() {
+                "id" => self.id = attribute,
+                "name" => self.name = attribute,
+                "description" => self.description = attribute,
+                "heading" => self.heading = attribute,
+                "additional_info" => self.additional_info = attribute,
+                field => anyhow::bail!("Unknown field '{field}'"),
+            }
+        }
+        Ok(self)
+    }
+}
+
+/// Turns an ID into a valid XML identifier
+fn identifier(prefix: &str, id: &str) -> String {
+    let id: String = id
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || "-_.".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{prefix}-{id}")
+}
+
+fn string_value(definition: &str, value: &str) -> String {
+    // Line breaks in attributes are read back as spaces unless they are character references
+    format!(
+        "<ATTRIBUTE-VALUE-STRING THE-VALUE=\"{}\"><DEFINITION><ATTRIBUTE-DEFINITION-STRING-REF>{definition}</ATTRIBUTE-DEFINITION-STRING-REF></DEFINITION></ATTRIBUTE-VALUE-STRING>",
+        escape(value).replace('\n', "&#10;")
+    )
+}
+
+fn xhtml_value(definition: &str, value: &str) -> String {
+    let paragraphs: Vec<_> = value
+        .trim()
+        .lines()
+        .map(|l| format!("<xhtml:p>{}</xhtml:p>", escape(l.trim())))
+        .collect();
+    format!(
+        "<ATTRIBUTE-VALUE-XHTML><DEFINITION><ATTRIBUTE-DEFINITION-XHTML-REF>{definition}</ATTRIBUTE-DEFINITION-XHTML-REF></DEFINITION><THE-VALUE><xhtml:div>{}</xhtml:div></THE-VALUE></ATTRIBUTE-VALUE-XHTML>",
+        paragraphs.join("")
+    )
+}
+
+fn spec_object(identifier: &str, typ: &str, values: &[String], last_change: &str) -> String {
+    format!(
+        "        <SPEC-OBJECT IDENTIFIER=\"{identifier}\" LAST-CHANGE=\"{last_change}\">\n          <TYPE><SPEC-OBJECT-TYPE-REF>{typ}</SPEC-OBJECT-TYPE-REF></TYPE>\n          <VALUES>{}</VALUES>\n        </SPEC-OBJECT>",
+        values.join("")
+    )
+}
+
+fn attribute_definition(
+    kind: &str,
+    identifier: &str,
+    name: &str,
+    datatype: &str,
+    last_change: &str,
+) -> String {
+    format!(
+        "<ATTRIBUTE-DEFINITION-{kind} IDENTIFIER=\"{identifier}\" LONG-NAME=\"{}\" LAST-CHANGE=\"{last_change}\"><TYPE><DATATYPE-DEFINITION-{kind}-REF>{datatype}</DATATYPE-DEFINITION-{kind}-REF></TYPE></ATTRIBUTE-DEFINITION-{kind}>",
+        escape(name)
+    )
+}
+
+fn hierarchy(
+    topics: &IndexMap<String, Topic>,
+    objects: &mut Vec<String>,
+    indent: usize,
+    last_change: &str,
+) -> Vec<String> {
+    let pad = " ".repeat(indent);
+    let node = |object: &str, children: Vec<String>| {
+        let mut lines = vec![format!(
+            "{pad}<SPEC-HIERARCHY IDENTIFIER=\"h-{object}\" LAST-CHANGE=\"{last_change}\">"
+        )];
+        lines.push(format!(
+            "{pad}  <OBJECT><SPEC-OBJECT-REF>{object}</SPEC-OBJECT-REF></OBJECT>"
+        ));
+        if !children.is_empty() {
+            lines.push(format!("{pad}  <CHILDREN>"));
+            lines.extend(children);
+            lines.push(format!("{pad}  </CHILDREN>"));
+        }
+        lines.push(format!("{pad}</SPEC-HIERARCHY>"));
+        lines
+    };
+    let mut lines = Vec::new();
+    for (id, topic) in topics {
+        let object = identifier("topic", id);
+        objects.push(spec_object(
+            &object,
+            "heading-type",
+            &[
+                string_value("heading-id", id.trim()),
+                string_value("heading-name", topic.name.trim()),
+            ],
+            last_change,
+        ));
+        let mut children = Vec::new();
+        for (req_id, requirement) in &topic.requirements {
+            let req_object = identifier("req", req_id);
+            let mut values = vec![
+                string_value("attr-id", req_id.trim()),
+                string_value("attr-name", requirement.name.trim()),
+                xhtml_value("attr-description", &requirement.description),
+            ];
+            if !requirement.additional_info.is_empty() {
+                let info: Vec<_> = requirement
+                    .additional_info
+                    .iter()
+                    .map(|i| i.trim())
+                    .collect();
+                values.push(string_value("attr-info", &info.join("\n")));
+            }
+            objects.push(spec_object(
+                &req_object,
+                "requirement-type",
+                &values,
+                last_change,
+            ));
+            children.extend(node(&req_object, Vec::new()));
+        }
+        children.extend(hierarchy(
+            &topic.subtopics,
+            objects,
+            indent + 4,
+            last_change,
+        ));
+        lines.extend(node(&object, children));
+    }
+    lines
+}
+
+/// Writes the project as a ReqIF 1.2 document, with topics as headings and links as relations
+///
+/// ReqIF requires creation and change dates, all of them are set to `timestamp`, in seconds
+/// since the Unix epoch.
+#[must_use]
+pub fn to_reqif(project: &Project, attributes: &AttributeMap, timestamp: u64) -> String {
+    let last_change = reproducible::iso8601(timestamp);
+    let mut objects = Vec::new();
+    let specification = hierarchy(&project.topics, &mut objects, 12, &last_change);
+    let mut relations = Vec::new();
+    project.visit_requirements(|r| {
+        for link in &r.requirement.links {
+            let source = identifier("req", r.id);
+            let target = identifier("req", link);
+            relations.push(format!(
+                "        <SPEC-RELATION IDENTIFIER=\"rel-{source}-{target}\" LAST-CHANGE=\"{last_change}\"><TYPE><SPEC-RELATION-TYPE-REF>link-type</SPEC-RELATION-TYPE-REF></TYPE><SOURCE><SPEC-OBJECT-REF>{source}</SPEC-OBJECT-REF></SOURCE><TARGET><SPEC-OBJECT-REF>{target}</SPEC-OBJECT-REF></TARGET></SPEC-RELATION>"
+            ));
+        }
+    });
+    let name = escape(project.name.trim());
+    let requirement_attributes = [
+        attribute_definition("STRING", "attr-id", &attributes.id, "string", &last_change),
+        attribute_definition(
+            "STRING",
+            "attr-name",
+            &attributes.name,
+            "string",
+            &last_change,
+        ),
+        attribute_definition(
+            "XHTML",
+            "attr-description",
+            &attributes.description,
+            "xhtml",
+            &last_change,
+        ),
+        attribute_definition(
+            "STRING",
+            "attr-info",
+            &attributes.additional_info,
+            "string",
+            &last_change,
+        ),
+    ];
+    let heading_attributes = [
+        attribute_definition(
+            "STRING",
+            "heading-id",
+            &attributes.id,
+            "string",
+            &last_change,
+        ),
+        attribute_definition(
+            "STRING",
+            "heading-name",
+            &attributes.heading,
+            "string",
+            &last_change,
+        ),
+    ];
+    let mut output = vec![
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string(),
+        r#"<REQ-IF xmlns="http://www.omg.org/spec/ReqIF/20110401/reqif.xsd" xmlns:xhtml="http://www.w3.org/1999/xhtml">"#.to_string(),
+        "  <THE-HEADER>".to_string(),
+        "    <REQ-IF-HEADER IDENTIFIER=\"header\">".to_string(),
+        format!("      <COMMENT>Version {}</COMMENT>", project.version),
+        format!("      <CREATION-TIME>{last_change}</CREATION-TIME>"),
+        "      <REQ-IF-TOOL-ID>req</REQ-IF-TOOL-ID>".to_string(),
+        "      <REQ-IF-VERSION>1.0</REQ-IF-VERSION>".to_string(),
+        "      <SOURCE-TOOL-ID>req</SOURCE-TOOL-ID>".to_string(),
+        format!("      <TITLE>{name}</TITLE>"),
+        "    </REQ-IF-HEADER>".to_string(),
+        "  </THE-HEADER>".to_string(),
+        "  <CORE-CONTENT>".to_string(),
+        "    <REQ-IF-CONTENT>".to_string(),
+        "      <DATATYPES>".to_string(),
+        format!("        <DATATYPE-DEFINITION-STRING IDENTIFIER=\"string\" LONG-NAME=\"String\" MAX-LENGTH=\"32000\" LAST-CHANGE=\"{last_change}\"/>"),
+        format!("        <DATATYPE-DEFINITION-XHTML IDENTIFIER=\"xhtml\" LONG-NAME=\"XHTML\" LAST-CHANGE=\"{last_change}\"/>"),
+        "      </DATATYPES>".to_string(),
+        "      <SPEC-TYPES>".to_string(),
+        format!("        <SPEC-OBJECT-TYPE IDENTIFIER=\"requirement-type\" LONG-NAME=\"Requirement\" LAST-CHANGE=\"{last_change}\"><SPEC-ATTRIBUTES>{}</SPEC-ATTRIBUTES></SPEC-OBJECT-TYPE>", requirement_attributes.join("")),
+        format!("        <SPEC-OBJECT-TYPE IDENTIFIER=\"heading-type\" LONG-NAME=\"Heading\" LAST-CHANGE=\"{last_change}\"><SPEC-ATTRIBUTES>{}</SPEC-ATTRIBUTES></SPEC-OBJECT-TYPE>", heading_attributes.join("")),
+        format!("        <SPEC-RELATION-TYPE IDENTIFIER=\"link-type\" LONG-NAME=\"relates to\" LAST-CHANGE=\"{last_change}\"/>"),
+        format!("        <SPECIFICATION-TYPE IDENTIFIER=\"specification-type\" LONG-NAME=\"Specification\" LAST-CHANGE=\"{last_change}\"/>"),
+        "      </SPEC-TYPES>".to_string(),
+        "      <SPEC-OBJECTS>".to_string(),
+    ];
+    output.extend(objects);
+    output.push("      </SPEC-OBJECTS>".to_string());
+    output.push("      <SPEC-RELATIONS>".to_string());
+    output.extend(relations);
+    output.push("      </SPEC-RELATIONS>".to_string());
+    output.push("      <SPECIFICATIONS>".to_string());
+    output.push(format!(
+        "        <SPECIFICATION IDENTIFIER=\"specification\" LONG-NAME=\"{name}\" DESC=\"{}\" LAST-CHANGE=\"{last_change}\">",
+        escape(project.description.trim()).replace('\n', "&#10;")
+    ));
+    output.push("          <TYPE><SPECIFICATION-TYPE-REF>specification-type</SPECIFICATION-TYPE-REF></TYPE>".to_string());
+    output.push("          <CHILDREN>".to_string());
+    output.extend(specification);
+    output.push("          </CHILDREN>".to_string());
+    output.push("        </SPECIFICATION>".to_string());
+    output.push("      </SPECIFICATIONS>".to_string());
+    output.push("    </REQ-IF-CONTENT>".to_string());
+    output.push("  </CORE-CONTENT>".to_string());
+    output.push("</REQ-IF>".to_string());
+    output.join("\n") + "\n"
+}
+
+/// An XML element with its attributes, children and text, without namespace prefixes
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: HashMap<String, String>,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn parse(xml: &str) -> anyhow::Result<Element> {
+        let mut reader = quick_xml::Reader::from_str(xml);
+        let mut stack = vec![Element::default()];
+        loop {
+            let event = reader.read_event()?;
+            match event {
+                Event::Start(ref start) | Event::Empty(ref start) => {
+                    let mut element = Element {
+                        name: start.local_name().as_ref().to_string(),
+                        ..Element::default()
+                    };
+                    for attribute in start.attributes() {
+                        let attribute = attribute?;
+                        element.attributes.insert(
+                            attribute.key.local_name().as_ref().to_string(),
+                            attribute
+                                .normalized_value(quick_xml::XmlVersion::Implicit1_0)?
+                                .into_owned(),
+                        );
+                    }
+                    if matches!(event, Event::Start(_)) {
+                        stack.push(element);
+                    } else if let Some(parent) = stack.last_mut() {
+                        parent.children.push(element);
+                    }
+                }
+                Event::End(_) => {
+                    let element = stack.pop().expect("Reader checks nesting");
+                    let Some(parent) = stack.last_mut() else {
+                        anyhow::bail!("Unexpected closing tag");
+                    };
+                    parent.children.push(element);
+                }
+                Event::Text(text) => {
+                    if let Some(element) = stack.last_mut() {
+                        element.text.push_str(&text.into_inner());
+                    }
+                }
+                Event::CData(text) => {
+                    if let Some(element) = stack.last_mut() {
+                        element.text.push_str(&text.into_inner());
+                    }
+                }
+                Event::GeneralRef(reference) => {
+                    let reference = reference.into_inner();
+                    let reference = format!("&{reference};");
+                    let resolved = quick_xml::escape::unescape(&reference)?;
+                    if let Some(element) = stack.last_mut() {
+                        element.text.push_str(&resolved);
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        stack
+            .pop()
+            .filter(|_| stack.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Unclosed element"))
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    /// Finds all descendants with the name, in document order
+    fn descendants<'a>(&'a self, name: &str, found: &mut Vec<&'a Element>) {
+        for child in &self.children {
+            if child.name == name {
+                found.push(child);
+            }
+            child.descendants(name, found);
+        }
+    }
+
+    /// The text of the first element of the path of names below this one
+    fn text_at(&self, path: &[&str]) -> Option<&str> {
+        let mut element = self;
+        for name in path {
+            element = element.child(name)?;
+        }
+        Some(element.text.trim())
+    }
+
+    /// The text of this element and all descendants, with paragraphs on separate lines
+    fn all_text(&self) -> String {
+        let mut text = self.text.clone();
+        for child in &self.children {
+            let child_text = child.all_text();
+            if ["p", "div", "li", "br"].contains(&child.name.as_str()) && !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(&child_text);
+        }
+        text
+    }
+}
+
+/// The values of a spec object, by attribute name
+struct SpecObject {
+    values: IndexMap<String, String>,
+}
+
+fn spec_objects(content: &Element) -> HashMap<String, SpecObject> {
+    let mut names = HashMap::new();
+    let mut enum_values = HashMap::new();
+    let mut elements = Vec::new();
+    content.descendants("ENUM-VALUE", &mut elements);
+    for element in elements.drain(..) {
+        if let (Some(id), Some(name)) = (
+            element.attributes.get("IDENTIFIER"),
+            element.attributes.get("LONG-NAME"),
+        ) {
+            enum_values.insert(id.clone(), name.clone());
+        }
+    }
+    content.descendants("SPEC-ATTRIBUTES", &mut elements);
+    for definition in elements.drain(..).flat_map(|e| &e.children) {
+        if let (Some(id), Some(name)) = (
+            definition.attributes.get("IDENTIFIER"),
+            definition.attributes.get("LONG-NAME"),
+        ) {
+            names.insert(id.clone(), name.clone());
+        }
+    }
+    content.descendants("SPEC-OBJECT", &mut elements);
+    elements
+        .into_iter()
+        .filter_map(|object| {
+            let id = object.attributes.get("IDENTIFIER")?;
+            let mut values = IndexMap::new();
+            for value in object.child("VALUES").into_iter().flat_map(|v| &v.children) {
+                let Some(definition) = value.child("DEFINITION").and_then(|d| d.children.first())
+                else {
+                    continue;
+                };
+                let name = names
+                    .get(definition.text.trim())
+                    .cloned()
+                    .unwrap_or_else(|| definition.text.trim().to_string());
+                let text = if let Some(the_value) = value.attributes.get("THE-VALUE") {
+                    the_value.clone()
+                } else if let Some(the_value) = value.child("THE-VALUE") {
+                    the_value.all_text()
+                } else {
+                    let mut refs = Vec::new();
+                    value.descendants("ENUM-VALUE-REF", &mut refs);
+                    let refs: Vec<_> = refs
+                        .iter()
+                        .map(|r| {
+                            enum_values
+                                .get(r.text.trim())
+                                .map_or(r.text.trim(), String::as_str)
+                        })
+                        .collect();
+                    refs.join(", ")
+                };
+                values.insert(name, text.trim().to_string());
+            }
+            Some((id.clone(), SpecObject { values }))
+        })
+        .collect()
+}
+
+fn read_hierarchy(
+    nodes: &Element,
+    objects: &HashMap<String, SpecObject>,
+    attributes: &AttributeMap,
+    topics: &mut IndexMap<String, Topic>,
+    mut requirements: Option<&mut IndexMap<String, Requirement>>,
+    ids: &mut HashMap<String, String>,
+) {
+    for node in nodes.children("SPEC-HIERARCHY") {
+        let Some(reference) = node.text_at(&["OBJECT", "SPEC-OBJECT-REF"]) else {
+            continue;
+        };
+        let Some(object) = objects.get(reference) else {
+            continue;
+        };
+        let value = |name: &str| object.values.get(name).filter(|v| !v.is_empty());
+        let id = value(&attributes.id).map_or(reference, String::as_str);
+        let children = node.child("CHILDREN");
+        let heading = value(&attributes.heading);
+        if heading.is_some() || (children.is_some() && value(&attributes.description).is_none()) {
+            let topic = topics.entry(id.to_string()).or_insert_with(|| Topic {
+                name: heading
+                    .or(value(&attributes.name))
+                    .cloned()
+                    .unwrap_or_default(),
+                requirements: IndexMap::new(),
+                subtopics: IndexMap::new(),
+                classification: None,
+            });
+            if let Some(children) = children {
+                read_hierarchy(
+                    children,
+                    objects,
+                    attributes,
+                    &mut topic.subtopics,
+                    Some(&mut topic.requirements),
+                    ids,
+                );
+            }
+            continue;
+        }
+        let description = value(&attributes.description).cloned().unwrap_or_default();
+        let mut additional_info: Vec<String> = value(&attributes.additional_info)
+            .map(|i| i.lines().map(|l| l.trim().to_string()).collect())
+            .unwrap_or_default();
+        // Keep custom fields that have no counterpart
+        let known = [
+            &attributes.id,
+            &attributes.name,
+            &attributes.description,
+            &attributes.heading,
+            &attributes.additional_info,
+        ];
+        for (name, value) in &object.values {
+            if !known.contains(&name) && !value.is_empty() {
+                additional_info.push(format!("{name}: {value}"));
+            }
+        }
+        let requirement = Requirement {
+            name: value(&attributes.name)
+                .cloned()
+                .unwrap_or_else(|| import::name_from_text(&description)),
+            description,
+            additional_info,
+            links: Vec::new(),
+            classification: None,
+            priority: None,
+            state: None,
+        };
+        ids.insert(reference.to_string(), id.to_string());
+        let requirements = match requirements.as_deref_mut() {
+            Some(requirements) => requirements,
+            None => {
+                &mut topics
+                    .entry("GENERAL".to_string())
+                    .or_insert_with(|| Topic {
+                        name: "General".to_string(),
+                        requirements: IndexMap::new(),
+                        subtopics: IndexMap::new(),
+                        classification: None,
+                    })
+                    .requirements
+            }
+        };
+        requirements.insert(id.to_string(), requirement);
+    }
+}
+
+fn read_links(content: &Element, ids: &HashMap<String, String>, project: &mut Project) {
+    let mut relations = Vec::new();
+    content.descendants("SPEC-RELATION", &mut relations);
+    for relation in relations {
+        let source = relation
+            .text_at(&["SOURCE", "SPEC-OBJECT-REF"])
+            .and_then(|s| ids.get(s));
+        let target = relation
+            .text_at(&["TARGET", "SPEC-OBJECT-REF"])
+            .and_then(|t| ids.get(t));
+        let (Some(source), Some(target)) = (source, target) else {
+            continue;
+        };
+        let Some((path, _)) = project.find_requirement(source) else {
+            continue;
+        };
+        if let Some(requirement) = project
+            .topic_mut(&path)
+            .and_then(|t| t.requirements.get_mut(source))
+        {
+            requirement.links.push(target.clone());
+        }
+    }
+}
+
+/// Reads a ReqIF document, with headings as topics and relations between requirements as links
+///
+/// Attributes without a counterpart in `attributes` are kept as `Name: value` additional information.
+pub fn from_reqif(xml: &str, attributes: &AttributeMap) -> anyhow::Result<Project> {
+    let root = Element::parse(xml)?;
+    let Some(reqif) = root.child("REQ-IF") else {
+        anyhow::bail!("Not a ReqIF document, the root element is not REQ-IF");
+    };
+    let content = reqif
+        .child("CORE-CONTENT")
+        .and_then(|c| c.child("REQ-IF-CONTENT"))
+        .ok_or_else(|| anyhow::anyhow!("The ReqIF document has no content"))?;
+    let title = reqif
+        .text_at(&["THE-HEADER", "REQ-IF-HEADER", "TITLE"])
+        .unwrap_or_default();
+    let mut project = empty_project(title);
+    if let Some(version) = reqif
+        .text_at(&["THE-HEADER", "REQ-IF-HEADER", "COMMENT"])
+        .and_then(|c| c.strip_prefix("Version "))
+        .and_then(|v| v.parse().ok())
+    {
+        project.version = version;
+    }
+    let objects = spec_objects(content);
+    let mut ids = HashMap::new();
+    for specification in content
+        .child("SPECIFICATIONS")
+        .into_iter()
+        .flat_map(|s| s.children("SPECIFICATION"))
+    {
+        if project.name.is_empty() {
+            if let Some(name) = specification.attributes.get("LONG-NAME") {
+                project.name.clone_from(name);
+            }
+        }
+        if project.description.is_empty() {
+            if let Some(description) = specification.attributes.get("DESC") {
+                project.description.clone_from(description);
+            }
+        }
+        if let Some(children) = specification.child("CHILDREN") {
+            read_hierarchy(
+                children,
+                &objects,
+                attributes,
+                &mut project.topics,
+                None,
+                &mut ids,
+            );
+        }
+    }
+    read_links(content, &ids, &mut project);
+    Ok(project)
+}
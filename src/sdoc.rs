@@ -0,0 +1,258 @@
+use indexmap::IndexMap;
+
+use crate::import::{self, empty_project};
+use crate::{Project, Requirement, Topic};
+
+/// Writes a field, as a multi-line block if needed
+fn field(name: &str, value: &str) -> Vec<String> {
+    let value = value.trim();
+    if value.contains('\n') {
+        let mut lines = vec![format!("{name}: >>>")];
+        lines.extend(value.lines().map(str::to_string));
+        lines.push("<<<".to_string());
+        lines
+    } else {
+        vec![format!("{name}: {value}")]
+    }
+}
+
+fn sdoc_topics(topics: &IndexMap<String, Topic>, output: &mut Vec<String>) {
+    for (id, topic) in topics {
+        output.push("[SECTION]".to_string());
+        output.extend(field("UID", id));
+        output.extend(field("TITLE", &topic.name));
+        output.push(String::new());
+        for (id, requirement) in &topic.requirements {
+            output.push("[REQUIREMENT]".to_string());
+            output.extend(field("UID", id));
+            if !requirement.links.is_empty() {
+                output.push("RELATIONS:".to_string());
+                for link in &requirement.links {
+                    output.push("- TYPE: Parent".to_string());
+                    output.push(format!("  VALUE: {}", link.trim()));
+                }
+            }
+            output.extend(field("TITLE", &requirement.name));
+            output.extend(field("STATEMENT", &requirement.description));
+            for info in &requirement.additional_info {
+                output.extend(field("COMMENT", info));
+            }
+            output.push(String::new());
+        }
+        sdoc_topics(&topic.subtopics, output);
+        output.push("[/SECTION]".to_string());
+        output.push(String::new());
+    }
+}
+
+/// Renders the project as a StrictDoc document, with topics as sections and links as parent relations
+#[must_use]
+pub fn to_sdoc(project: &Project) -> String {
+    let mut output = vec!["[DOCUMENT]".to_string()];
+    output.extend(field("TITLE", &project.name));
+    output.push(format!("VERSION: {}", project.version));
+    output.push(String::new());
+    if !project.description.trim().is_empty() {
+        output.push("[FREETEXT]".to_string());
+        output.push(project.description.trim().to_string());
+        output.push("[/FREETEXT]".to_string());
+        output.push(String::new());
+    }
+    sdoc_topics(&project.topics, &mut output);
+    output.join("\n")
+}
+
+/// A node of a StrictDoc document with its fields in order
+#[derive(Default)]
+struct Node {
+    kind: String,
+    fields: Vec<(String, String)>,
+    /// Relations as type and target UID
+    relations: Vec<(String, String)>,
+}
+
+impl Node {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.trim())
+            .filter(|v| !v.is_empty())
+    }
+}
+
+fn add_node(node: Node, project: &mut Project, path: &mut Vec<String>, sections: &mut usize) {
+    match node.kind.as_str() {
+        "DOCUMENT" => {
+            if let Some(title) = node.get("TITLE") {
+                project.name = title.to_string();
+            }
+            if let Some(version) = node.get("VERSION") {
+                if let Ok(version) = version.parse() {
+                    project.version = version;
+                }
+            }
+        }
+        "SECTION" => {
+            *sections += 1;
+            let id = node
+                .get("UID")
+                .map_or_else(|| format!("SECTION-{sections}"), str::to_string);
+            let topics = match project.topic_mut(path) {
+                Some(parent) => &mut parent.subtopics,
+                None => &mut project.topics,
+            };
+            topics.insert(
+                id.clone(),
+                Topic {
+                    name: node.get("TITLE").unwrap_or_default().to_string(),
+                    requirements: IndexMap::new(),
+                    subtopics: IndexMap::new(),
+                    classification: None,
+                },
+            );
+            path.push(id);
+        }
+        "TEXT" if project.topics.is_empty() => {
+            if let Some(statement) = node.get("STATEMENT") {
+                project.description = statement.to_string();
+            }
+        }
+        "TEXT" => {}
+        _ => {
+            let Some(id) = node.get("UID") else {
+                tracing::warn!("Skipping a {} without UID", node.kind);
+                return;
+            };
+            let description = node
+                .get("STATEMENT")
+                .or(node.get("CONTENT"))
+                .unwrap_or_default()
+                .to_string();
+            let mut additional_info = Vec::new();
+            for (name, value) in &node.fields {
+                match name.as_str() {
+                    "UID" | "TITLE" | "STATEMENT" | "CONTENT" => {}
+                    "COMMENT" => additional_info.push(value.trim().to_string()),
+                    // Keep fields that have no counterpart
+                    name => additional_info.push(format!(
+                        "{}{}: {}",
+                        &name[..1],
+                        name[1..].to_lowercase(),
+                        value.trim()
+                    )),
+                }
+            }
+            let requirement = Requirement {
+                name: node
+                    .get("TITLE")
+                    .map_or_else(|| import::name_from_text(&description), str::to_string),
+                description,
+                additional_info,
+                links: node
+                    .relations
+                    .iter()
+                    .filter(|(typ, _)| typ == "Parent" || typ == "Child")
+                    .map(|(_, target)| target.clone())
+                    .collect(),
+                classification: None,
+                priority: None,
+                state: None,
+            };
+            let topic = match project.topic_mut(path) {
+                Some(topic) => topic,
+                None => import::topic_by_names(&mut project.topics, &["General"])
+                    .expect("Path is not empty"),
+            };
+            topic.requirements.insert(id.to_string(), requirement);
+        }
+    }
+}
+
+/// Reads a StrictDoc document
+///
+/// Sections become topics, requirements keep their UID as ID, parent and child relations
+/// become links and fields without a counterpart are kept as additional information.
+pub fn from_sdoc(sdoc: &str) -> anyhow::Result<Project> {
+    let mut project = empty_project("");
+    let mut path: Vec<String> = Vec::new();
+    let mut sections = 0;
+    let mut node: Option<Node> = None;
+    let mut lines = sdoc.lines().enumerate();
+    while let Some((number, line)) = lines.next() {
+        let trimmed = line.trim();
+        // Newer versions of StrictDoc write `[[SECTION]]` instead of `[SECTION]`
+        let tag = trimmed
+            .strip_prefix("[[")
+            .and_then(|t| t.strip_suffix("]]"))
+            .or_else(|| trimmed.strip_prefix('[').and_then(|t| t.strip_suffix(']')));
+        if let Some(tag) = tag {
+            if let Some(node) = node.take() {
+                add_node(node, &mut project, &mut path, &mut sections);
+            }
+            match tag {
+                "/SECTION" => {
+                    path.pop();
+                }
+                "FREETEXT" => {
+                    let text: Vec<_> = lines
+                        .by_ref()
+                        .map(|(_, l)| l)
+                        .take_while(|l| l.trim() != "[/FREETEXT]")
+                        .collect();
+                    if path.is_empty() && project.description.is_empty() {
+                        project.description = text.join("\n").trim().to_string();
+                    }
+                }
+                tag => {
+                    node = Some(Node {
+                        kind: tag.to_string(),
+                        ..Node::default()
+                    })
+                }
+            }
+            continue;
+        }
+        let Some(current) = node.as_mut() else {
+            continue;
+        };
+        if trimmed.is_empty() {
+            let node = node.take().expect("Node is set");
+            add_node(node, &mut project, &mut path, &mut sections);
+            continue;
+        }
+        if let Some(relation) = trimmed.strip_prefix("- TYPE:") {
+            current
+                .relations
+                .push((relation.trim().to_string(), String::new()));
+            continue;
+        }
+        if let Some(value) = trimmed.strip_prefix("VALUE:") {
+            if let Some(relation) = current.relations.last_mut() {
+                relation.1 = value.trim().to_string();
+            }
+            continue;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            anyhow::bail!("Line {}: expected a field, found '{trimmed}'", number + 1);
+        };
+        if value.trim() == ">>>" {
+            let text: Vec<_> = lines
+                .by_ref()
+                .map(|(_, l)| l)
+                .take_while(|l| l.trim() != "<<<")
+                .collect();
+            current
+                .fields
+                .push((name.trim().to_string(), text.join("\n")));
+        } else if !matches!(name.trim(), "RELATIONS" | "REFS") {
+            current
+                .fields
+                .push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    if let Some(node) = node.take() {
+        add_node(node, &mut project, &mut path, &mut sections);
+    }
+    Ok(project)
+}
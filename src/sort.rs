@@ -0,0 +1,73 @@
+use std::cmp::Ordering;
+
+use clap::ValueEnum;
+use indexmap::IndexMap;
+
+use crate::{Project, Topic};
+
+/// What topics and requirements are ordered by
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Id,
+    Name,
+}
+
+fn chunks(value: &str) -> impl Iterator<Item = &str> {
+    let mut rest = value;
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let is_digit = first.is_ascii_digit();
+        let end = rest
+            .find(|c: char| c.is_ascii_digit() != is_digit)
+            .unwrap_or(rest.len());
+        let (chunk, remaining) = rest.split_at(end);
+        rest = remaining;
+        Some(chunk)
+    })
+}
+
+/// Compares strings so that embedded numbers are ordered by value, e.g. `REQ-2 < REQ-10`
+#[must_use]
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chunks = chunks(a);
+    let mut b_chunks = chunks(b);
+    loop {
+        let ordering = match (a_chunks.next(), b_chunks.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u128>(), b.parse::<u128>()) {
+                (Ok(a_num), Ok(b_num)) => a_num.cmp(&b_num).then_with(|| a.cmp(b)),
+                _ => a.cmp(b),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+fn sort_topics(topics: &mut IndexMap<String, Topic>, by: SortKey) {
+    match by {
+        SortKey::Id => topics.sort_by(|a, _, b, _| natural_cmp(a.trim(), b.trim())),
+        SortKey::Name => {
+            topics.sort_by(|_, a, _, b| natural_cmp(a.name.trim(), b.name.trim()));
+        }
+    }
+    for topic in topics.values_mut() {
+        match by {
+            SortKey::Id => topic
+                .requirements
+                .sort_by(|a, _, b, _| natural_cmp(a.trim(), b.trim())),
+            SortKey::Name => topic
+                .requirements
+                .sort_by(|_, a, _, b| natural_cmp(a.name.trim(), b.name.trim())),
+        }
+        sort_topics(&mut topic.subtopics, by);
+    }
+}
+
+/// Sorts all topics and requirements in place
+pub fn sort(project: &mut Project, by: SortKey) {
+    sort_topics(&mut project.topics, by);
+}
@@ -0,0 +1,60 @@
+use clap::ValueEnum;
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::{Priority, Project, Topic};
+
+#[derive(Debug, Default, Serialize)]
+pub struct Stats {
+    pub topics: usize,
+    pub requirements: usize,
+    pub definitions: usize,
+    pub config_defaults: usize,
+    /// Number of requirements directly inside each topic, keyed by the topic path
+    pub requirements_per_topic: IndexMap<String, usize>,
+    /// Number of requirements per nesting depth, top-level topics have depth 1
+    pub requirements_per_depth: IndexMap<usize, usize>,
+    /// Number of requirements per priority from low to critical, `none` counts those without one
+    pub requirements_per_priority: IndexMap<String, usize>,
+}
+
+fn collect(stats: &mut Stats, topics: &IndexMap<String, Topic>, path: &str, depth: usize) {
+    for (id, topic) in topics {
+        let path = if path.is_empty() {
+            id.trim().to_string()
+        } else {
+            format!("{path}/{}", id.trim())
+        };
+        stats.topics += 1;
+        stats.requirements += topic.requirements.len();
+        *stats.requirements_per_depth.entry(depth).or_default() += topic.requirements.len();
+        for requirement in topic.requirements.values() {
+            let priority = requirement
+                .priority
+                .map_or_else(|| "none".to_string(), |p| p.to_string());
+            *stats.requirements_per_priority.entry(priority).or_default() += 1;
+        }
+        stats
+            .requirements_per_topic
+            .insert(path.clone(), topic.requirements.len());
+        collect(stats, &topic.subtopics, &path, depth + 1);
+    }
+}
+
+#[must_use]
+pub fn stats(project: &Project) -> Stats {
+    let mut stats = Stats {
+        definitions: project.definitions.len(),
+        config_defaults: project.config_defaults.len(),
+        requirements_per_priority: Priority::value_variants()
+            .iter()
+            .map(Priority::to_string)
+            .chain(["none".to_string()])
+            .map(|priority| (priority, 0))
+            .collect(),
+        ..Default::default()
+    };
+    collect(&mut stats, &project.topics, "", 1);
+    stats.requirements_per_depth.sort_keys();
+    stats
+}
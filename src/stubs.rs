@@ -0,0 +1,164 @@
+use clap::ValueEnum;
+
+use crate::{Project, RequirementRef};
+
+/// The languages test stubs can be generated for
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Pytest,
+}
+
+/// Turns an ID and name into a lowercase identifier, e.g. `req_1_1_output_json_schema`
+#[must_use]
+pub fn identifier(id: &str, name: &str) -> String {
+    let mut identifier = String::new();
+    for c in format!("{id} {name}").chars() {
+        if c.is_ascii_alphanumeric() {
+            identifier.push(c.to_ascii_lowercase());
+        } else if !identifier.ends_with('_') {
+            identifier.push('_');
+        }
+    }
+    let identifier = identifier.trim_matches('_');
+    if identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("r_{identifier}")
+    } else {
+        identifier.to_string()
+    }
+}
+
+fn comment_lines(prefix: &str, text: &str) -> Vec<String> {
+    text.trim()
+        .lines()
+        .map(|l| format!("{prefix} {}", l.trim()).trim_end().to_string())
+        .collect()
+}
+
+fn rust_stub(output: &mut Vec<String>, r: RequirementRef) {
+    let id = r.id;
+    output.push(format!("/// {id} - {}", r.requirement.name.trim()));
+    output.push("///".to_string());
+    output.extend(comment_lines("///", &r.requirement.description));
+    for info in &r.requirement.additional_info {
+        output.push(format!("/// - {}", info.trim()));
+    }
+    output.push("#[test]".to_string());
+    output.push("#[ignore = \"not implemented yet\"]".to_string());
+    output.push(format!("fn {}() {{", identifier(id, &r.requirement.name)));
+    output.push(format!("    verify({id:?}, || {{"));
+    output.push(format!("        todo!({:?})", format!("Verify {id}")));
+    output.push("    });".to_string());
+    output.push("}".to_string());
+    output.push(String::new());
+}
+
+/// Generates one ignored `#[test]` per requirement that reports its result in the format `check` expects
+#[must_use]
+pub fn rust(project: &Project) -> String {
+    let mut output = vec![
+        format!(
+            "//! Tests for the requirements of {} {}",
+            project.name.trim(),
+            project.version
+        ),
+        "//!".to_string(),
+        "//! Generated by `req stubs rust`. Every test prints `<ID>: passed` or".to_string(),
+        "//! `<ID>: failed - <reason>`, run them with `cargo test -- --nocapture`".to_string(),
+        "//! and pass the output to `req check`.".to_string(),
+        String::new(),
+        "/// Runs a requirement check and prints its result for `req check`".to_string(),
+        "fn verify(id: &str, check: impl FnOnce() -> Result<(), String>) {".to_string(),
+        "    match check() {".to_string(),
+        "        Ok(()) => println!(\"{id}: passed\"),".to_string(),
+        "        Err(e) => {".to_string(),
+        "            println!(\"{id}: failed - {e}\");".to_string(),
+        "            panic!(\"{id} failed: {e}\");".to_string(),
+        "        }".to_string(),
+        "    }".to_string(),
+        "}".to_string(),
+        String::new(),
+    ];
+    project.visit_requirements(|r| rust_stub(&mut output, r));
+    output.join("\n")
+}
+
+fn python_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn pytest_stub(output: &mut Vec<String>, r: RequirementRef) {
+    let id = python_string(r.id);
+    output.push(String::new());
+    output.push(String::new());
+    output.push(format!("@pytest.mark.requirement({id})"));
+    output.push(format!(
+        "@pytest.mark.parametrize(\"requirement_id\", [{id}])"
+    ));
+    output.push(format!(
+        "def test_{}(requirement_id):",
+        identifier(r.id, &r.requirement.name)
+    ));
+    output.push(format!(
+        "    \"\"\"{} - {}",
+        r.id,
+        r.requirement.name.trim().replace("\"\"\"", "'''")
+    ));
+    output.push(String::new());
+    output.extend(comment_lines(
+        "   ",
+        &r.requirement.description.replace("\"\"\"", "'''"),
+    ));
+    for info in &r.requirement.additional_info {
+        output.push(format!("    - {}", info.trim().replace("\"\"\"", "'''")));
+    }
+    output.push("    \"\"\"".to_string());
+    output.push("    with verifies(requirement_id):".to_string());
+    output.push("        pytest.skip(\"not implemented yet\")".to_string());
+}
+
+/// Generates one skipped pytest test per requirement that reports its result in the format `check` expects
+#[must_use]
+pub fn pytest(project: &Project) -> String {
+    let mut output = vec![
+        format!(
+            "\"\"\"Tests for the requirements of {} {}",
+            project.name.trim(),
+            project.version
+        ),
+        String::new(),
+        "Generated by `req stubs pytest`. Every test prints `<ID>: passed` or".to_string(),
+        "`<ID>: failed - <reason>`, run them with `pytest -s` and pass the output".to_string(),
+        "to `req check`. Register the `requirement` marker in your pytest configuration:"
+            .to_string(),
+        String::new(),
+        "    markers = requirement(id): the requirement verified by the test".to_string(),
+        "\"\"\"".to_string(),
+        String::new(),
+        "from contextlib import contextmanager".to_string(),
+        String::new(),
+        "import pytest".to_string(),
+        String::new(),
+        String::new(),
+        "@contextmanager".to_string(),
+        "def verifies(requirement_id):".to_string(),
+        "    \"\"\"Prints the result of the enclosed check for `req check`\"\"\"".to_string(),
+        "    try:".to_string(),
+        "        yield".to_string(),
+        "    except Exception as e:".to_string(),
+        "        print(f\"{requirement_id}: failed - {e}\")".to_string(),
+        "        raise".to_string(),
+        "    print(f\"{requirement_id}: passed\")".to_string(),
+    ];
+    project.visit_requirements(|r| pytest_stub(&mut output, r));
+    output.join("\n")
+}
+
+/// Generates test stubs in `language`
+#[must_use]
+pub fn generate(project: &Project, language: Language) -> String {
+    match language {
+        Language::Rust => rust(project),
+        Language::Pytest => pytest(project),
+    }
+}
@@ -0,0 +1,439 @@
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{Project, State, Topic};
+
+/// The label every synchronized issue gets, so they can be told apart from other issues
+pub const LABEL: &str = "requirement";
+
+/// The issue a requirement is tracked by
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    /// The ID of the requirement
+    pub id: String,
+    pub title: String,
+    pub body: String,
+    pub labels: Vec<String>,
+}
+
+/// An issue as it exists in the issue tracker
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteIssue {
+    pub number: u64,
+    /// `open` or `closed`
+    pub state: String,
+    pub title: String,
+    pub body: String,
+    pub labels: Vec<String>,
+}
+
+impl RemoteIssue {
+    /// The ID of the requirement the issue was created for
+    #[must_use]
+    pub fn requirement_id(&self) -> Option<&str> {
+        let (_, rest) = self.body.split_once("<!-- req:")?;
+        Some(rest.split_once("-->")?.0.trim())
+    }
+
+    /// The state of the requirement, open unless the issue is closed
+    #[must_use]
+    pub fn requirement_state(&self) -> State {
+        if self.state == "closed" {
+            State::Closed
+        } else {
+            State::Open
+        }
+    }
+}
+
+fn marker(id: &str) -> String {
+    format!("<!-- req:{id} -->")
+}
+
+/// Builds one issue per requirement, labelled with the names of the topics containing it
+#[must_use]
+pub fn issues(project: &Project) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    project.visit_requirements(|r| {
+        let mut body = vec![r.requirement.description.trim().to_string()];
+        if !r.requirement.additional_info.is_empty() {
+            body.push(String::new());
+            for info in &r.requirement.additional_info {
+                body.push(format!("- {}", info.trim()));
+            }
+        }
+        if !r.requirement.links.is_empty() {
+            let links: Vec<_> = r.requirement.links.iter().map(|l| l.trim()).collect();
+            body.push(String::new());
+            body.push(format!("Related: {}", links.join(", ")));
+        }
+        body.push(String::new());
+        body.push(marker(r.id));
+        let mut labels = vec![LABEL.to_string()];
+        for depth in 1..=r.topic_path.len() {
+            if let Some(topic) = project.topic(&r.topic_path[..depth]) {
+                labels.push(topic.name.trim().to_string());
+            }
+        }
+        issues.push(Issue {
+            id: r.id.to_string(),
+            title: format!("{}: {}", r.id, r.requirement.name.trim()),
+            body: body.join("\n"),
+            labels,
+        });
+    });
+    issues
+}
+
+/// What has to happen to bring an issue in line with its requirement
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    Create(Issue),
+    Update(u64, Issue),
+    Unchanged(u64, Issue),
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Action::Create(issue) => write!(f, "{}: create issue", issue.id),
+            Action::Update(number, issue) => write!(f, "{}: update issue #{number}", issue.id),
+            Action::Unchanged(number, issue) => {
+                write!(f, "{}: issue #{number} unchanged", issue.id)
+            }
+        }
+    }
+}
+
+fn is_current(issue: &Issue, remote: &RemoteIssue) -> bool {
+    let mut labels = issue.labels.clone();
+    let mut remote_labels = remote.labels.clone();
+    labels.sort_unstable();
+    remote_labels.sort_unstable();
+    issue.title == remote.title
+        && issue.body.trim() == remote.body.trim()
+        && labels == remote_labels
+}
+
+/// Matches the issues to the existing ones by requirement ID and decides what to do with each
+///
+/// Labels are compared in the form the tracker stores them in.
+#[must_use]
+pub fn plan(tracker: &impl Tracker, issues: Vec<Issue>, remote: &[RemoteIssue]) -> Vec<Action> {
+    issues
+        .into_iter()
+        .map(|mut issue| {
+            issue.labels = issue.labels.iter().map(|l| tracker.label(l)).collect();
+            match remote
+                .iter()
+                .find(|r| r.requirement_id() == Some(&issue.id))
+            {
+                None => Action::Create(issue),
+                Some(existing) if is_current(&issue, existing) => {
+                    Action::Unchanged(existing.number, issue)
+                }
+                Some(existing) => Action::Update(existing.number, issue),
+            }
+        })
+        .collect()
+}
+
+/// An issue tracker requirements can be synchronized with
+pub trait Tracker {
+    /// Lists all issues carrying [`LABEL`], open and closed
+    fn issues(&self) -> anyhow::Result<Vec<RemoteIssue>>;
+    /// Creates an issue and returns its number
+    fn create(&self, issue: &Issue) -> anyhow::Result<u64>;
+    fn update(&self, number: u64, issue: &Issue) -> anyhow::Result<()>;
+    /// A label in the form the tracker stores it
+    fn label(&self, name: &str) -> String {
+        name.to_string()
+    }
+}
+
+/// Carries out the planned actions and describes what was done
+pub fn apply(tracker: &impl Tracker, actions: &[Action]) -> anyhow::Result<Vec<String>> {
+    let mut output = Vec::new();
+    for action in actions {
+        match action {
+            Action::Create(issue) => {
+                let number = tracker.create(issue)?;
+                output.push(format!("{}: created issue #{number}", issue.id));
+            }
+            Action::Update(number, issue) => {
+                tracker.update(*number, issue)?;
+                output.push(format!("{}: updated issue #{number}", issue.id));
+            }
+            Action::Unchanged(..) => {}
+        }
+    }
+    Ok(output)
+}
+
+/// Pairs every requirement with its issue, if it has one
+#[must_use]
+pub fn status(project: &Project, remote: &[RemoteIssue]) -> Vec<(String, Option<RemoteIssue>)> {
+    let mut status = Vec::new();
+    project.visit_requirements(|r| {
+        let issue = remote.iter().find(|i| i.requirement_id() == Some(r.id));
+        status.push((r.id.to_string(), issue.cloned()));
+    });
+    status
+}
+
+/// Sets the state of every requirement with an issue to the one of its issue, returning the
+/// IDs of the requirements that changed
+pub fn read_state(topics: &mut IndexMap<String, Topic>, remote: &[RemoteIssue]) -> Vec<String> {
+    let mut changed = Vec::new();
+    for topic in topics.values_mut() {
+        for (id, requirement) in &mut topic.requirements {
+            let Some(issue) = remote
+                .iter()
+                .find(|i| i.requirement_id() == Some(id.trim()))
+            else {
+                continue;
+            };
+            let state = Some(issue.requirement_state());
+            if requirement.state != state {
+                requirement.state = state;
+                changed.push(id.trim().to_string());
+            }
+        }
+        changed.extend(read_state(&mut topic.subtopics, remote));
+    }
+    changed
+}
+
+/// The number of issues requested at once
+const PAGE_SIZE: usize = 100;
+
+pub(crate) fn agent() -> ureq::Agent {
+    ureq::Agent::config_builder()
+        .timeout_global(Some(std::time::Duration::from_secs(30)))
+        .build()
+        .into()
+}
+
+#[derive(Deserialize)]
+struct GitHubLabel {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct GitHubIssue {
+    number: u64,
+    state: String,
+    title: String,
+    body: Option<String>,
+    labels: Vec<GitHubLabel>,
+    /// Set if the issue is a pull request
+    pull_request: Option<serde_json::Value>,
+}
+
+/// The issues of a GitHub repository, accessed through the REST API
+pub struct GitHub {
+    agent: ureq::Agent,
+    api_url: String,
+    repo: String,
+    token: String,
+}
+
+impl GitHub {
+    /// Connects to the repository `owner/name` of the API at `api_url`, e.g. `https://api.github.com`
+    #[must_use]
+    pub fn new(api_url: &str, repo: &str, token: &str) -> GitHub {
+        GitHub {
+            agent: agent(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+            repo: repo.to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/repos/{}/issues{path}", self.api_url, self.repo)
+    }
+
+    fn send(
+        &self,
+        request: ureq::RequestBuilder<ureq::typestate::WithBody>,
+        issue: &Issue,
+    ) -> anyhow::Result<GitHubIssue> {
+        let body = serde_json::json!({
+            "title": issue.title,
+            "body": issue.body,
+            "labels": issue.labels,
+        });
+        let response = request
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Accept", "application/vnd.github+json")
+            .content_type("application/json")
+            .send(body.to_string())?;
+        Ok(serde_json::from_str(
+            &response.into_body().read_to_string()?,
+        )?)
+    }
+}
+
+impl Tracker for GitHub {
+    fn issues(&self) -> anyhow::Result<Vec<RemoteIssue>> {
+        let mut issues = Vec::new();
+        for page in 1.. {
+            let response = self
+                .agent
+                .get(self.url(""))
+                .query("state", "all")
+                .query("labels", LABEL)
+                .query("per_page", PAGE_SIZE.to_string())
+                .query("page", page.to_string())
+                .header("Authorization", format!("Bearer {}", self.token))
+                .header("Accept", "application/vnd.github+json")
+                .call()
+                .map_err(|e| anyhow::anyhow!("Failed to list the issues of {}: {e}", self.repo))?;
+            let page: Vec<GitHubIssue> =
+                serde_json::from_str(&response.into_body().read_to_string()?)?;
+            let last = page.len() < PAGE_SIZE;
+            issues.extend(
+                page.into_iter()
+                    .filter(|i| i.pull_request.is_none())
+                    .map(|i| RemoteIssue {
+                        number: i.number,
+                        state: i.state,
+                        title: i.title,
+                        body: i.body.unwrap_or_default(),
+                        labels: i.labels.into_iter().map(|l| l.name).collect(),
+                    }),
+            );
+            if last {
+                break;
+            }
+        }
+        tracing::info!("Found {} issues in {}", issues.len(), self.repo);
+        Ok(issues)
+    }
+
+    fn create(&self, issue: &Issue) -> anyhow::Result<u64> {
+        let created = self
+            .send(self.agent.post(self.url("")), issue)
+            .map_err(|e| anyhow::anyhow!("Failed to create the issue of {}: {e}", issue.id))?;
+        Ok(created.number)
+    }
+
+    fn update(&self, number: u64, issue: &Issue) -> anyhow::Result<()> {
+        self.send(self.agent.patch(self.url(&format!("/{number}"))), issue)
+            .map_err(|e| anyhow::anyhow!("Failed to update issue #{number}: {e}"))?;
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct GitLabIssue {
+    iid: u64,
+    state: String,
+    title: String,
+    description: Option<String>,
+    labels: Vec<String>,
+}
+
+/// The issues of a GitLab project, accessed through the REST API
+pub struct GitLab {
+    agent: ureq::Agent,
+    api_url: String,
+    project: String,
+    token: String,
+}
+
+impl GitLab {
+    /// Connects to the project `group/name` or numeric ID of the API at `api_url`, e.g. `https://gitlab.com/api/v4`
+    #[must_use]
+    pub fn new(api_url: &str, project: &str, token: &str) -> GitLab {
+        GitLab {
+            agent: agent(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+            project: project.to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        let project = self.project.replace('/', "%2F");
+        format!("{}/projects/{project}/issues{path}", self.api_url)
+    }
+
+    fn send(
+        &self,
+        request: ureq::RequestBuilder<ureq::typestate::WithBody>,
+        issue: &Issue,
+    ) -> anyhow::Result<GitLabIssue> {
+        let labels: Vec<_> = issue.labels.iter().map(|l| self.label(l)).collect();
+        let body = serde_json::json!({
+            "title": issue.title,
+            "description": issue.body,
+            "labels": labels.join(","),
+        });
+        let response = request
+            .header("PRIVATE-TOKEN", &self.token)
+            .content_type("application/json")
+            .send(body.to_string())?;
+        Ok(serde_json::from_str(
+            &response.into_body().read_to_string()?,
+        )?)
+    }
+}
+
+impl Tracker for GitLab {
+    fn issues(&self) -> anyhow::Result<Vec<RemoteIssue>> {
+        let mut issues = Vec::new();
+        for page in 1.. {
+            let response = self
+                .agent
+                .get(self.url(""))
+                .query("state", "all")
+                .query("labels", LABEL)
+                .query("per_page", PAGE_SIZE.to_string())
+                .query("page", page.to_string())
+                .header("PRIVATE-TOKEN", &self.token)
+                .call()
+                .map_err(|e| {
+                    anyhow::anyhow!("Failed to list the issues of {}: {e}", self.project)
+                })?;
+            let page: Vec<GitLabIssue> =
+                serde_json::from_str(&response.into_body().read_to_string()?)?;
+            let last = page.len() < PAGE_SIZE;
+            issues.extend(page.into_iter().map(|i| RemoteIssue {
+                number: i.iid,
+                state: if i.state == "opened" {
+                    "open".to_string()
+                } else {
+                    i.state
+                },
+                title: i.title,
+                body: i.description.unwrap_or_default(),
+                labels: i.labels,
+            }));
+            if last {
+                break;
+            }
+        }
+        tracing::info!("Found {} issues in {}", issues.len(), self.project);
+        Ok(issues)
+    }
+
+    fn create(&self, issue: &Issue) -> anyhow::Result<u64> {
+        let created = self
+            .send(self.agent.post(self.url("")), issue)
+            .map_err(|e| anyhow::anyhow!("Failed to create the issue of {}: {e}", issue.id))?;
+        Ok(created.iid)
+    }
+
+    fn update(&self, number: u64, issue: &Issue) -> anyhow::Result<()> {
+        self.send(self.agent.put(self.url(&format!("/{number}"))), issue)
+            .map_err(|e| anyhow::anyhow!("Failed to update issue #{number}: {e}"))?;
+        Ok(())
+    }
+
+    /// GitLab takes labels as a comma separated list, so commas in labels become spaces
+    fn label(&self, name: &str) -> String {
+        name.replace(',', " ")
+    }
+}
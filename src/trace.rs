@@ -0,0 +1,373 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::{reproducible, Project};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Passed,
+    Failed,
+    Untested,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Status::Passed => "passed",
+            Status::Failed => "failed",
+            Status::Untested => "untested",
+        })
+    }
+}
+
+/// A line in a file mentioning a requirement
+#[derive(Debug, Clone, Serialize)]
+pub struct Reference {
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+/// A test result line for a requirement
+#[derive(Debug, Clone, Serialize)]
+pub struct Evidence {
+    pub file: PathBuf,
+    pub line: usize,
+    pub passed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Trace {
+    pub id: String,
+    pub name: String,
+    pub topic_path: Vec<String>,
+    pub status: Status,
+    pub tests: Vec<Evidence>,
+    pub code: Vec<Reference>,
+    /// Gherkin scenarios tagged with the requirement
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub scenarios: Vec<Reference>,
+}
+
+fn is_id_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_'
+}
+
+/// Returns the positions after each mention of `id` in `text` that is not part of a longer ID
+pub fn mentions<'a>(text: &'a str, id: &'a str) -> impl Iterator<Item = usize> + 'a {
+    text.match_indices(id).filter_map(move |(start, _)| {
+        let end = start + id.len();
+        let before = text[..start].chars().next_back();
+        let mut after = text[end..].chars();
+        let next = after.next();
+        let continues = match next {
+            Some('.') => after.next().is_some_and(|c| c.is_alphanumeric()),
+            Some(c) => is_id_char(c),
+            None => false,
+        };
+        (!before.is_some_and(is_id_char) && !continues).then_some(end)
+    })
+}
+
+/// Whether mentions of `id` are found by `Mentions`, which is the case for IDs consisting of ID
+/// characters with single dots between them
+fn is_plain(id: &str) -> bool {
+    let mut chars = id.chars().peekable();
+    chars.peek().is_some_and(|c| is_id_char(*c))
+        && std::iter::from_fn(|| chars.next().map(|c| (c, chars.peek().copied())))
+            .all(|(c, next)| is_id_char(c) || (c == '.' && next.is_some_and(char::is_alphanumeric)))
+}
+
+/// Finds the mentions of many IDs in one pass over each line, instead of searching every line
+/// for every ID
+struct Mentions<'a> {
+    plain: HashSet<&'a str>,
+    other: Vec<&'a str>,
+}
+
+impl<'a> Mentions<'a> {
+    fn new(ids: &'a [String]) -> Mentions<'a> {
+        let (plain, other): (Vec<_>, Vec<_>) =
+            ids.iter().map(String::as_str).partition(|id| is_plain(id));
+        Mentions {
+            plain: plain.into_iter().collect(),
+            other,
+        }
+    }
+
+    /// Calls `found` with each ID mentioned in `line` and the position after the mention, in the
+    /// order of the mentions for each ID
+    fn find(&self, line: &str, mut found: impl FnMut(&'a str, usize)) {
+        // A mention of a plain ID spans to the end of a run of ID characters and dots followed
+        // by alphanumeric characters, starting at the beginning of the run or after a dot
+        let chars: Vec<_> = line.char_indices().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            if !is_id_char(chars[i].1) {
+                i += 1;
+                continue;
+            }
+            let mut starts = vec![chars[i].0];
+            while let Some((_, c)) = chars.get(i) {
+                if is_id_char(*c) {
+                    i += 1;
+                } else if let Some((next, _)) = chars
+                    .get(i + 1)
+                    .filter(|(_, n)| *c == '.' && n.is_alphanumeric())
+                {
+                    starts.push(*next);
+                    i += 1;
+                } else {
+                    break;
+                }
+            }
+            let end = chars.get(i).map_or(line.len(), |(index, _)| *index);
+            for start in starts {
+                if let Some(id) = self.plain.get(&line[start..end]) {
+                    found(id, end);
+                }
+            }
+        }
+        for id in &self.other {
+            for end in mentions(line, id) {
+                found(id, end);
+            }
+        }
+    }
+}
+
+/// Lists all files in `paths`, descending into directories but skipping hidden entries
+pub fn files(paths: &[PathBuf]) -> anyhow::Result<Vec<PathBuf>> {
+    fn walk(path: &Path, files: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+        if path.is_dir() {
+            let mut entries: Vec<_> = std::fs::read_dir(path)?
+                .map(|e| e.map(|e| e.path()))
+                .collect::<Result<_, _>>()?;
+            entries.sort();
+            for entry in entries {
+                let hidden = entry
+                    .file_name()
+                    .is_some_and(|n| n.to_string_lossy().starts_with('.'));
+                if !hidden {
+                    walk(&entry, files)?;
+                }
+            }
+        } else {
+            files.push(path.to_path_buf());
+        }
+        Ok(())
+    }
+    let mut files = Vec::new();
+    for path in paths {
+        walk(path, &mut files)?;
+    }
+    Ok(files)
+}
+
+/// Reads all text files, skipping files that are not valid UTF-8
+fn read_text_files(paths: &[PathBuf]) -> anyhow::Result<Vec<(PathBuf, String)>> {
+    Ok(files(paths)?
+        .into_iter()
+        .filter_map(|f| match std::fs::read_to_string(&f) {
+            Ok(content) => {
+                tracing::debug!("Scanning {}", f.display());
+                Some((f, content))
+            }
+            Err(e) => {
+                tracing::debug!("Skipping {}: {e}", f.display());
+                None
+            }
+        })
+        .collect())
+}
+
+/// Collects test evidence from `tests` and references from `sources` for every requirement
+pub fn trace(
+    project: &Project,
+    tests: &[PathBuf],
+    sources: &[PathBuf],
+) -> anyhow::Result<Vec<Trace>> {
+    let start = std::time::Instant::now();
+    let tests = read_text_files(tests)?;
+    let sources = read_text_files(sources)?;
+    tracing::info!(
+        "Read {} test and {} source files in {:?}",
+        tests.len(),
+        sources.len(),
+        start.elapsed()
+    );
+    let start = std::time::Instant::now();
+    let mut ids = Vec::new();
+    project.visit_requirements(|r| ids.push(r.id.to_string()));
+    let finder = Mentions::new(&ids);
+    let mut all_evidence: HashMap<&str, Vec<Evidence>> = HashMap::new();
+    for (file, content) in &tests {
+        for (index, line) in content.lines().enumerate() {
+            finder.find(line, |id, end| {
+                let rest = &line[end..];
+                let passed = if rest.starts_with(": passed") {
+                    true
+                } else if rest.starts_with(": failed") {
+                    false
+                } else {
+                    return;
+                };
+                all_evidence.entry(id).or_default().push(Evidence {
+                    file: file.clone(),
+                    line: index + 1,
+                    passed,
+                });
+            });
+        }
+    }
+    let mut all_code: HashMap<&str, Vec<Reference>> = HashMap::new();
+    for (file, content) in &sources {
+        for (index, line) in content.lines().enumerate() {
+            finder.find(line, |id, _| {
+                let code = all_code.entry(id).or_default();
+                // One reference per line, even if it mentions the requirement several times
+                if !code
+                    .last()
+                    .is_some_and(|c| c.line == index + 1 && c.file == *file)
+                {
+                    code.push(Reference {
+                        file: file.clone(),
+                        line: index + 1,
+                    });
+                }
+            });
+        }
+    }
+    let mut traces = Vec::new();
+    project.visit_requirements(|r| {
+        let evidence = all_evidence.get(r.id).cloned().unwrap_or_default();
+        let code = all_code.get(r.id).cloned().unwrap_or_default();
+        tracing::debug!(
+            "{}: {} evidence lines, {} code references",
+            r.id,
+            evidence.len(),
+            code.len()
+        );
+        let status = if evidence.iter().any(|e| !e.passed) {
+            Status::Failed
+        } else if evidence.is_empty() {
+            Status::Untested
+        } else {
+            Status::Passed
+        };
+        traces.push(Trace {
+            id: r.id.to_string(),
+            name: r.requirement.name.trim().to_string(),
+            topic_path: r.topic_path.iter().map(|p| p.to_string()).collect(),
+            status,
+            tests: evidence,
+            code,
+            scenarios: Vec::new(),
+        });
+    });
+    tracing::info!(
+        "Traced {} requirements in {:?}",
+        traces.len(),
+        start.elapsed()
+    );
+    Ok(traces)
+}
+
+/// Adds the scenarios tagged with each requirement to its trace
+pub fn add_scenarios(traces: &mut [Trace], scenarios: &[crate::gherkin::Scenario]) {
+    for trace in traces {
+        trace.scenarios = scenarios
+            .iter()
+            .filter(|s| s.covers(&trace.id))
+            .map(|s| Reference {
+                file: s.file.clone(),
+                line: s.line,
+            })
+            .collect();
+    }
+}
+
+fn locations<'a>(locations: impl Iterator<Item = (&'a Path, usize)>) -> Vec<String> {
+    locations
+        .map(|(file, line)| format!("{}:{line}", reproducible::portable_path(file)))
+        .collect()
+}
+
+fn has_scenarios(traces: &[Trace]) -> bool {
+    traces.iter().any(|t| !t.scenarios.is_empty())
+}
+
+/// Renders the traceability matrix, with a column for scenarios if any requirement has one
+#[must_use]
+pub fn to_markdown(project: &Project, traces: &[Trace]) -> String {
+    let scenarios = has_scenarios(traces);
+    let mut output = vec![
+        format!("# Traceability - {}", project.name.trim()),
+        String::new(),
+        if scenarios {
+            "| Requirement | Topic | Status | Tests | Scenarios | Code |".to_string()
+        } else {
+            "| Requirement | Topic | Status | Tests | Code |".to_string()
+        },
+        if scenarios {
+            "| --- | --- | --- | --- | --- | --- |".to_string()
+        } else {
+            "| --- | --- | --- | --- | --- |".to_string()
+        },
+    ];
+    for trace in traces {
+        let mut columns = vec![
+            format!("_{}_ - {}", trace.id, trace.name),
+            trace.topic_path.join("/"),
+            trace.status.to_string(),
+            locations(trace.tests.iter().map(|e| (e.file.as_path(), e.line))).join("<br>"),
+        ];
+        if scenarios {
+            columns.push(
+                locations(trace.scenarios.iter().map(|r| (r.file.as_path(), r.line))).join("<br>"),
+            );
+        }
+        columns.push(locations(trace.code.iter().map(|r| (r.file.as_path(), r.line))).join("<br>"));
+        output.push(format!("| {} |", columns.join(" | ")));
+    }
+    output.join("\n")
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[must_use]
+pub fn to_csv(traces: &[Trace]) -> String {
+    let scenarios = has_scenarios(traces);
+    let mut output = vec![if scenarios {
+        "id,name,topic,status,tests,scenarios,code".to_string()
+    } else {
+        "id,name,topic,status,tests,code".to_string()
+    }];
+    for trace in traces {
+        let mut fields = vec![
+            trace.id.clone(),
+            trace.name.clone(),
+            trace.topic_path.join("/"),
+            trace.status.to_string(),
+            locations(trace.tests.iter().map(|e| (e.file.as_path(), e.line))).join(";"),
+        ];
+        if scenarios {
+            fields.push(
+                locations(trace.scenarios.iter().map(|r| (r.file.as_path(), r.line))).join(";"),
+            );
+        }
+        fields.push(locations(trace.code.iter().map(|r| (r.file.as_path(), r.line))).join(";"));
+        let fields: Vec<_> = fields.iter().map(|f| csv_field(f)).collect();
+        output.push(fields.join(","));
+    }
+    output.join("\n") + "\n"
+}
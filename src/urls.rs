@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use regex::Regex;
+
+use crate::Project;
+
+/// A URL and where in the project it was found
+#[derive(Debug, Clone)]
+pub struct Url {
+    pub url: String,
+    pub location: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeadUrl {
+    pub url: Url,
+    pub error: String,
+}
+
+fn find_urls(re: &Regex, text: &str, location: &str, urls: &mut Vec<Url>) {
+    for m in re.find_iter(text) {
+        urls.push(Url {
+            url: m
+                .as_str()
+                .trim_end_matches(['.', ',', ';', ':', '!', '?'])
+                .to_string(),
+            location: location.to_string(),
+        });
+    }
+}
+
+/// Extracts all URLs from descriptions, additional information and definitions
+#[must_use]
+pub fn extract(project: &Project) -> Vec<Url> {
+    let re = Regex::new(r#"https?://[^\s<>()\[\]"'`]+"#).expect("Valid regex");
+    let mut urls = Vec::new();
+    find_urls(&re, &project.description, "description", &mut urls);
+    project.visit_requirements(|r| {
+        find_urls(&re, &r.requirement.description, r.id, &mut urls);
+        for info in &r.requirement.additional_info {
+            find_urls(&re, info, r.id, &mut urls);
+        }
+    });
+    for definition in &project.definitions {
+        let location = definition.name.trim();
+        find_urls(&re, &definition.value, location, &mut urls);
+        for info in &definition.additional_info {
+            find_urls(&re, info, location, &mut urls);
+        }
+    }
+    for default in &project.config_defaults {
+        if let Some(hint) = &default.hint {
+            find_urls(&re, hint, default.name.trim(), &mut urls);
+        }
+    }
+    urls
+}
+
+fn check_url(agent: &ureq::Agent, url: &str) -> Result<(), String> {
+    match agent.head(url).call() {
+        Ok(_) => Ok(()),
+        // Some servers do not support HEAD requests
+        Err(ureq::Error::StatusCode(405 | 501)) => {
+            agent.get(url).call().map(|_| ()).map_err(|e| e.to_string())
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Requests every distinct URL with up to `concurrency` parallel requests and returns the ones that failed
+#[must_use]
+pub fn check(urls: &[Url], concurrency: usize, timeout: Duration) -> Vec<DeadUrl> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(timeout))
+        .build()
+        .into();
+    let mut distinct: Vec<&str> = urls.iter().map(|u| u.url.as_str()).collect();
+    distinct.sort_unstable();
+    distinct.dedup();
+    let next = AtomicUsize::new(0);
+    let errors = Mutex::new(HashMap::new());
+    std::thread::scope(|scope| {
+        for _ in 0..concurrency.max(1) {
+            scope.spawn(|| {
+                while let Some(url) = distinct.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    if let Err(error) = check_url(&agent, url) {
+                        errors.lock().expect("Not poisoned").insert(*url, error);
+                    }
+                }
+            });
+        }
+    });
+    let errors = errors.into_inner().expect("Not poisoned");
+    urls.iter()
+        .filter_map(|url| {
+            errors.get(url.url.as_str()).map(|error| DeadUrl {
+                url: url.clone(),
+                error: error.clone(),
+            })
+        })
+        .collect()
+}
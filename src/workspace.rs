@@ -0,0 +1,63 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+/// The file names a workspace is looked up under, in order of precedence
+pub const FILE_NAMES: [&str; 3] = [
+    "easyreq-workspace.toml",
+    "easyreq-workspace.yml",
+    "easyreq-workspace.yaml",
+];
+
+/// A project that is part of a workspace
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Member {
+    /// The requirements file of the project
+    pub requirements: PathBuf,
+    /// Test output files of the project, used by `check --workspace`
+    #[serde(default)]
+    pub test_results: Vec<PathBuf>,
+}
+
+/// Several requirement projects that are documented and checked together
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Workspace {
+    pub name: String,
+    pub projects: Vec<Member>,
+}
+
+impl Workspace {
+    /// Reads a workspace file and makes its paths relative to the current directory
+    pub fn read(path: &Path) -> anyhow::Result<Workspace> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", path.display()))?;
+        let parsed = if path.extension().is_some_and(|e| e == "toml") {
+            toml::from_str(&content).map_err(anyhow::Error::from)
+        } else {
+            serde_yaml::from_str(&content).map_err(anyhow::Error::from)
+        };
+        let mut workspace: Workspace =
+            parsed.map_err(|e| anyhow::anyhow!("Invalid workspace {}: {e}", path.display()))?;
+        let base = path.parent().unwrap_or(Path::new(""));
+        for member in &mut workspace.projects {
+            member.requirements = base.join(&member.requirements);
+            for test_result in &mut member.test_results {
+                *test_result = base.join(&*test_result);
+            }
+        }
+        Ok(workspace)
+    }
+}
+
+/// Finds the workspace file in `start` or the closest parent directory containing one
+#[must_use]
+pub fn find(start: &Path) -> Option<PathBuf> {
+    start.ancestors().find_map(|dir| {
+        FILE_NAMES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    })
+}
@@ -0,0 +1,16 @@
+//! The REST API must find requirements by the IDs in their URLs.
+
+use req::{api, demo_project};
+
+#[test]
+fn ids_in_urls_keep_plus_signs() {
+    let mut project = demo_project();
+    let requirements = &mut project.topics["TOPIC-1"].requirements;
+    let requirement = requirements["REQ-1.1"].clone();
+    requirements.insert("REQ+1".to_string(), requirement);
+    for url in ["/requirements/REQ+1", "/requirements/REQ%2B1"] {
+        let response = api::handle(&project, "GET", url, &[], |_| String::new());
+        assert_eq!(response.status, 200, "{url}: {}", response.body);
+        assert!(response.body.contains("\"REQ+1\""), "{}", response.body);
+    }
+}
@@ -0,0 +1,25 @@
+//! Archiving moves requirements out of their topics but keeps their IDs taken.
+
+mod common;
+
+use std::path::Path;
+
+use common::{project, req, run, temp_dir, write_project};
+use req::format;
+
+#[test]
+fn archive_accepts_id_patterns() {
+    let dir = temp_dir("archive-patterns");
+    let requirements = write_project(&dir, &project());
+    let requirements = requirements.to_str().unwrap();
+    let output = req(&["archive", "REQ-3.x", "-r", requirements]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("REQ-07*"));
+    run(&["archive", "REQ-3.*", "REQ-3.?", "-r", requirements]);
+    let project = format::load(Path::new(requirements)).unwrap();
+    for id in ["REQ-3.1", "REQ-3.2", "REQ-3.3"] {
+        assert!(project.is_archived(id), "{id}");
+    }
+    assert!(!project.is_archived("REQ-2.1"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
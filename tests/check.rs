@@ -0,0 +1,74 @@
+//! The check report counts a requirement as passed or failed if its ID ends the text before
+//! `: passed` or `: failed` on any line, and lists the errors of the lines starting with it,
+//! whatever order the test output files are given in.
+
+mod common;
+
+use std::path::PathBuf;
+
+use common::{project, run, temp_dir, write_project};
+use regex::Regex;
+use req::{check, demo_project};
+
+fn report(test_results: &str) -> Vec<String> {
+    let allowed = [Regex::new("REQ-.*").unwrap()];
+    check::report(&demo_project(), &[test_results.to_string()], &allowed, 1)
+}
+
+#[test]
+fn ids_ending_the_text_before_a_result_count() {
+    let output = report("xREQ-3.2: passed\ntest REQ-3.3: failed - ignored\n");
+    assert!(output.contains(&"- _REQ-3.2_ - Pretty Print to HTML: :white_check_mark:".to_string()));
+    let failed = output
+        .iter()
+        .position(|l| l == "- _REQ-3.3_ - Analyze Test Output: :x:")
+        .expect("REQ-3.3 failed");
+    assert!(!output[failed + 1].starts_with("  - "), "{output:?}");
+}
+
+#[test]
+fn errors_come_from_lines_starting_with_the_id() {
+    let output = report("REQ-1.1: failed - wrong schema\nREQ-1.2: passed\n");
+    let failed = output
+        .iter()
+        .position(|l| l == "- _REQ-1.1_ - Output Json Schema: :x:")
+        .expect("REQ-1.1 failed");
+    assert_eq!(output[failed + 1], "  - wrong schema");
+    assert!(output.contains(&"- _REQ-1.2_ - Demo Data: :white_check_mark:".to_string()));
+    assert!(
+        output.contains(&"- _REQ-2.1_ - Parsing From Multiple Data Formats: :warning:".to_string())
+    );
+}
+
+#[test]
+fn check_report_does_not_depend_on_file_order() {
+    let dir = temp_dir("check");
+    let requirements = write_project(&dir, &project());
+    std::fs::write(dir.join("a.txt"), "REQ-1.1: failed - wrong\n").unwrap();
+    std::fs::write(
+        dir.join("b.txt"),
+        "REQ-1.1: passed\nREQ-1.1: failed - too slow\n",
+    )
+    .unwrap();
+    let path = |p: PathBuf| p.to_str().unwrap().to_string();
+    let (requirements, a, b) = (
+        path(requirements),
+        path(dir.join("a.txt")),
+        path(dir.join("b.txt")),
+    );
+    let sorted_lines = |output: Vec<u8>| {
+        let mut lines: Vec<_> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect();
+        lines.sort();
+        lines
+    };
+    let forward = sorted_lines(run(&["check", &requirements, &a, &b]));
+    let backward = sorted_lines(run(&["check", &requirements, &b, &a]));
+    assert!(forward.iter().any(|l| l.contains("wrong")));
+    assert!(forward.iter().any(|l| l.contains("too slow")));
+    assert_eq!(forward, backward);
+    std::fs::remove_dir_all(dir).unwrap();
+}
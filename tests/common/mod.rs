@@ -0,0 +1,94 @@
+//! Fixtures shared by the integration tests, each test file uses some of them
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use req::format::{self, Format};
+use req::{demo_project, Project};
+
+/// An empty directory for a test, unique per test process and `name`
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("req-test-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("Can create temporary directory");
+    dir
+}
+
+/// The `req` binary, to add arguments, environment variables or a working directory to
+pub fn command() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_req"))
+}
+
+/// Runs `req` with `args`, whether it succeeds or not
+pub fn req(args: &[&str]) -> Output {
+    command().args(args).output().expect("Can run req")
+}
+
+/// Runs `req` with `args` and returns its stdout, failing the test if it fails
+pub fn run(args: &[&str]) -> Vec<u8> {
+    let output = req(args);
+    assert!(
+        output.status.success(),
+        "req {} failed: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output.stdout
+}
+
+/// The demo project with links, additional information and an archived requirement
+pub fn project() -> Project {
+    let mut project = demo_project();
+    let topic = project.topics.get_mut("TOPIC-3").expect("Demo has TOPIC-3");
+    let requirement = topic
+        .requirements
+        .get_mut("REQ-3.1")
+        .expect("Demo has REQ-3.1");
+    requirement.links = vec!["REQ-1.1".to_string(), "REQ-2.1".to_string()];
+    requirement
+        .additional_info
+        .push("Headings keep their level".to_string());
+    project.archive_requirement("REQ-1.2");
+    format::canonicalize(&mut project);
+    project
+}
+
+/// Writes `project` to `requirements.yml` in `dir` and returns its path
+pub fn write_project(dir: &Path, project: &Project) -> PathBuf {
+    let requirements = dir.join("requirements.yml");
+    std::fs::write(&requirements, Format::Yaml.serialize(project).unwrap()).unwrap();
+    requirements
+}
+
+/// The fields of a project file without its topics and includes
+pub const ROOT: &str = "name: Req
+version: 1.0.0
+description: Included
+topics: {}
+";
+
+/// A project whose only topic is defined in an included file, which links to REQ-1
+pub fn split_project(name: &str) -> (PathBuf, PathBuf, PathBuf) {
+    let dir = temp_dir(name);
+    let root = dir.join("requirements.yml");
+    std::fs::write(
+        &root,
+        format!(
+            "{}includes:\n  - topics/a.yml\n",
+            ROOT.replace(
+                "topics: {}",
+                "topics:\n  T0:\n    name: Root\n    requirements:\n      REQ-1:\n        name: One\n        description: D\n"
+            )
+        ),
+    )
+    .unwrap();
+    std::fs::create_dir_all(dir.join("topics")).unwrap();
+    let included = dir.join("topics/a.yml");
+    std::fs::write(
+        &included,
+        "topics:\n  T1:\n    name: Included\n    requirements:\n      REQ-2:\n        name: Two\n        description: D\n        links:\n          - REQ-1\n",
+    )
+    .unwrap();
+    (dir, root, included)
+}
@@ -0,0 +1,43 @@
+//! The project configuration supplies defaults, but must never get in the way of getting help
+//! or of setting it up.
+
+mod common;
+
+use common::{command, temp_dir};
+
+#[test]
+fn init_writes_nothing_if_the_config_exists() {
+    let dir = temp_dir("init");
+    let requirements = dir.join("requirements.yml");
+    std::fs::write(dir.join(".easyreq.toml"), "").unwrap();
+    let output = command()
+        .args(["init", "--name", "P", "--config", "--output"])
+        .arg(&requirements)
+        .output()
+        .expect("Can run req");
+    assert!(!output.status.success());
+    assert!(!requirements.exists());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn an_invalid_config_only_fails_commands_using_it() {
+    let dir = temp_dir("invalid-config");
+    std::fs::write(dir.join(".easyreq.toml"), "requirements = [").unwrap();
+    let succeeds = |args: &[&str]| {
+        command()
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .expect("Can run req")
+            .status
+            .success()
+    };
+    assert!(succeeds(&["--help"]));
+    assert!(succeeds(&["--version"]));
+    assert!(succeeds(&["markdown", "--help"]));
+    assert!(succeeds(&["completions", "bash"]));
+    assert!(succeeds(&["schema"]));
+    assert!(!succeeds(&["markdown"]));
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,274 @@
+//! Commands editing requirement files must only change the lines they edit, and in a project
+//! split into several files they see all of them and change the file that defines what they
+//! edit.
+
+mod common;
+
+use common::{req, run, split_project, temp_dir, ROOT};
+use req::format;
+
+/// A hand-written file with comments, indented lists, a literal block and blank lines
+const HAND_WRITTEN: &str = r#"# Requirements of the tool
+name: Req
+version: 1.0.0
+description: |
+  Written by hand.
+topics:
+  TOPIC-1:
+    name: Output Data
+    requirements:
+      # Checked by the schema tests
+      "REQ-1.1":
+        name: Output Json Schema
+        description: The tool must print a JSON schema
+        additional_info:
+          - Draft 7
+
+  TOPIC-2:
+    name: Reading
+    requirements:
+      REQ-2.1:
+        name: Parsing
+        description: 'The tool must read YAML:'
+      REQ-2.2:
+        name: Validation
+        description: >-
+          The tool must report
+          invalid files
+"#;
+
+/// The lines a unified diff adds and removes, without its headers
+fn changed_lines(diff: &[u8]) -> Vec<String> {
+    String::from_utf8(diff.to_vec())
+        .unwrap()
+        .lines()
+        .filter(|l| !l.starts_with("+++") && !l.starts_with("---"))
+        .filter(|l| l.starts_with(['+', '-']))
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn edits_keep_the_formatting() {
+    let dir = temp_dir("edits");
+    let requirements = dir.join("requirements.yml");
+    std::fs::write(&requirements, HAND_WRITTEN).unwrap();
+    let requirements = requirements.to_str().unwrap();
+    let added = run(&[
+        "add",
+        "REQ-1.2",
+        requirements,
+        "--topic",
+        "TOPIC-1",
+        "--name",
+        "Demo Data",
+        "--description",
+        "The tool should print demo data",
+        "--additional-info",
+        "As YAML",
+        "--dry-run",
+    ]);
+    assert_eq!(
+        changed_lines(&added),
+        [
+            "+      REQ-1.2:",
+            "+        name: Demo Data",
+            "+        description: The tool should print demo data",
+            "+        additional_info:",
+            "+          - As YAML",
+        ]
+    );
+    let removed = run(&["rm", "REQ-2.1", requirements, "--dry-run"]);
+    assert_eq!(
+        changed_lines(&removed),
+        [
+            "-      REQ-2.1:",
+            "-        name: Parsing",
+            "-        description: 'The tool must read YAML:'",
+        ]
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn edits_handle_non_ascii_keys() {
+    let dir = temp_dir("non-ascii");
+    let requirements = dir.join("requirements.yml");
+    let add = |requirements: &str| {
+        run(&[
+            "add",
+            "X-1",
+            requirements,
+            "--topic",
+            "T",
+            "--name",
+            "X",
+            "--description",
+            "Y",
+            "--dry-run",
+        ])
+    };
+    std::fs::write(
+        &requirements,
+        "name: P\nversion: 1.0.0\ndescription: D\ntopics:\n  T:\n    name: Topic\n    requirements:\n      # Kept\n      Ré:\n        name: N\n        description: D\n",
+    )
+    .unwrap();
+    assert_eq!(
+        changed_lines(&add(requirements.to_str().unwrap())),
+        ["+      X-1:", "+        name: X", "+        description: Y"]
+    );
+    // Explicit keys are not edited in place, the file is rewritten instead
+    std::fs::write(
+        &requirements,
+        "name: P\nversion: 1.0.0\ndescription: D\ntopics:\n  T:\n    name: Topic\n    requirements:\n      ? Ré\n      : name: N\n        description: D\n",
+    )
+    .unwrap();
+    let added = changed_lines(&add(requirements.to_str().unwrap()));
+    assert!(added.contains(&"+      Ré:".to_string()), "{added:?}");
+    assert!(added.contains(&"+      X-1:".to_string()), "{added:?}");
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn edits_parse_files_by_their_extension() {
+    let dir = temp_dir("edit-errors");
+    let requirements = dir.join("requirements.yml");
+    std::fs::write(&requirements, "name: P\ndescription: D\ntopics: {}\n").unwrap();
+    let output = req(&["rm", "REQ-1", requirements.to_str().unwrap()]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("as YAML"), "{stderr}");
+    assert!(stderr.contains("missing field `version`"), "{stderr}");
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn add_writes_into_the_file_defining_the_topic() {
+    let (dir, root, included) = split_project("add");
+    let root_content = std::fs::read_to_string(&root).unwrap();
+    let path = root.to_str().unwrap();
+    let args = [
+        "add",
+        "REQ-3",
+        path,
+        "--topic",
+        "T1",
+        "--name",
+        "Three",
+        "--description",
+        "D",
+    ];
+    assert!(req(&args).status.success());
+    assert_eq!(std::fs::read_to_string(&root).unwrap(), root_content);
+    let project = format::load(&root).unwrap();
+    assert_eq!(project.find_requirement("REQ-3").unwrap().0, ["T1"]);
+    assert!(std::fs::read_to_string(&included)
+        .unwrap()
+        .contains("REQ-3:"));
+    let duplicate = [
+        "add",
+        "REQ-2",
+        path,
+        "--topic",
+        "T0",
+        "--name",
+        "X",
+        "--description",
+        "D",
+    ];
+    assert!(!req(&duplicate).status.success());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn links_from_included_files_block_removing_and_archiving() {
+    let (dir, root, included) = split_project("links");
+    let path = root.to_str().unwrap();
+    for args in [
+        vec!["rm", "REQ-1", path],
+        vec!["archive", "REQ-1", "-r", path],
+    ] {
+        let output = req(&args);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("REQ-2"), "{stderr}");
+    }
+    assert!(req(&["archive", "REQ-2", "-r", path]).status.success());
+    let project = format::load(&root).unwrap();
+    assert!(project.is_archived("REQ-2"));
+    assert!(std::fs::read_to_string(&included)
+        .unwrap()
+        .contains("archive:"));
+    assert!(req(&["rm", "REQ-1", path]).status.success());
+    assert!(format::load(&root)
+        .unwrap()
+        .find_requirement("REQ-1")
+        .is_none());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn removing_a_requirement_keeps_a_topic_with_the_same_id() {
+    let dir = temp_dir("rm-same-id");
+    let root = dir.join("a.yml");
+    let topics = "topics:\n  T:\n    name: T\n    requirements:\n      REQ-1:\n        name: One\n        description: D\n";
+    let root_content = ROOT.replace("topics: {}\n", topics) + "includes:\n  - b.yml\n";
+    std::fs::write(&root, root_content).unwrap();
+    let included = dir.join("b.yml");
+    let content = "topics:\n  REQ-1:\n    name: Odd\n    requirements:\n      REQ-2:\n        name: Two\n        description: D\n";
+    std::fs::write(&included, content).unwrap();
+    assert!(req(&["rm", "REQ-1", root.to_str().unwrap()])
+        .status
+        .success());
+    assert_eq!(std::fs::read_to_string(&included).unwrap(), content);
+    let project = format::load(&root).unwrap();
+    assert!(project.find_requirement("REQ-1").is_none());
+    assert!(project.find_requirement("REQ-2").is_some());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn fmt_keeps_comments_and_formats_included_files() {
+    let dir = temp_dir("fmt");
+    let root = dir.join("requirements.yml");
+    let root_content = format!("# The project\n{ROOT}includes:\n  - part.yml\n");
+    std::fs::write(&root, &root_content).unwrap();
+    let part = dir.join("part.yml");
+    std::fs::write(
+        &part,
+        "# Written by hand\ntopics:\n  T:\n    name: \"  Padded \"\n",
+    )
+    .unwrap();
+    run(&["fmt", root.to_str().unwrap(), part.to_str().unwrap()]);
+    assert_eq!(std::fs::read_to_string(&root).unwrap(), root_content);
+    assert_eq!(
+        std::fs::read_to_string(&part).unwrap(),
+        "# Written by hand\ntopics:\n  T:\n    name: Padded\n"
+    );
+    run(&[
+        "fmt",
+        "--check",
+        root.to_str().unwrap(),
+        part.to_str().unwrap(),
+    ]);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn fmt_refuses_to_drop_comments() {
+    let dir = temp_dir("fmt-comments");
+    let inline = dir.join("inline.yml");
+    let inline_content = "topics:\n  T:\n    name: \" T \" # Kept\n";
+    let toml = dir.join("requirements.toml");
+    let toml_content =
+        "# The project\nname = \" Req \"\nversion = \"1.0.0\"\ndescription = \"D\"\n";
+    for (path, content) in [(&inline, inline_content), (&toml, toml_content)] {
+        std::fs::write(path, content).unwrap();
+        let output = req(&["fmt", path.to_str().unwrap()]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("would drop the comment"), "{stderr}");
+        assert_eq!(std::fs::read_to_string(path).unwrap(), content);
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,151 @@
+//! Projects can be split into files that include each other, but a file must never end up
+//! including itself, and `split` must neither lose an include nor write outside its directory.
+
+mod common;
+
+use std::path::Path;
+
+use common::{req, run, temp_dir, ROOT};
+use req::format::{self, ParseError};
+
+/// Loading and listing the files of `root` both fail with the circular chain
+fn assert_circular(root: &Path, chain: &str) {
+    for error in [
+        format::load(root).unwrap_err(),
+        format::source_files(root).unwrap_err(),
+    ] {
+        let error = error
+            .downcast::<ParseError>()
+            .expect("Cycles are parse errors");
+        assert!(
+            error.message().contains(chain),
+            "{} does not name {chain}",
+            error.message()
+        );
+        assert!(error.offset().is_some(), "The include is located");
+    }
+}
+
+#[test]
+fn a_file_including_itself_fails() {
+    let dir = temp_dir("self");
+    let root = dir.join("requirements.yml");
+    std::fs::write(&root, format!("{ROOT}includes:\n  - requirements.yml\n")).unwrap();
+    let chain = format!("{} -> {}", root.display(), root.display());
+    assert_circular(&root, &chain);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn files_including_each_other_fail() {
+    let dir = temp_dir("mutual");
+    let root = dir.join("requirements.yml");
+    std::fs::write(&root, format!("{ROOT}includes:\n  - a.yml\n")).unwrap();
+    std::fs::write(dir.join("a.yml"), "includes:\n  - b.yml\n").unwrap();
+    std::fs::write(dir.join("b.yml"), "includes:\n  - a.yml\n").unwrap();
+    let chain = format!(
+        "{} -> {} -> {}",
+        dir.join("a.yml").display(),
+        dir.join("b.yml").display(),
+        dir.join("a.yml").display()
+    );
+    assert_circular(&root, &chain);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn a_file_included_twice_without_a_cycle_loads() {
+    let dir = temp_dir("diamond");
+    let root = dir.join("requirements.yml");
+    std::fs::write(&root, format!("{ROOT}includes:\n  - a.yml\n  - b.yml\n")).unwrap();
+    std::fs::write(dir.join("a.yml"), "includes:\n  - c.yml\n").unwrap();
+    std::fs::write(dir.join("b.yml"), "includes:\n  - c.yml\n").unwrap();
+    std::fs::write(dir.join("c.yml"), "definitions: []\n").unwrap();
+    format::load(&root).unwrap();
+    assert_eq!(format::source_files(&root).unwrap().len(), 5);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn an_included_classification_redacts_the_merged_topic() {
+    let dir = temp_dir("classified");
+    let root = dir.join("requirements.yml");
+    let topics = "topics:\n  T:\n    name: T\n    requirements:\n      REQ-1:\n        name: Open\n        description: Visible\n";
+    std::fs::write(
+        &root,
+        ROOT.replace("topics: {}\n", topics) + "includes:\n  - secret.yml\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("secret.yml"),
+        "topics:\n  T:\n    name: T\n    classification: secret\n    requirements:\n      REQ-2:\n        name: Hidden\n        description: Launch codes\n",
+    )
+    .unwrap();
+    let output = run(&[
+        "markdown",
+        root.to_str().unwrap(),
+        "--max-classification",
+        "public",
+    ]);
+    let markdown = String::from_utf8(output).unwrap();
+    assert!(markdown.contains("REDACTED"), "{markdown}");
+    for secret in ["Open", "Visible", "Hidden", "Launch codes"] {
+        assert!(!markdown.contains(secret), "{markdown}");
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn split_rebases_includes_and_keeps_topics_inside_the_directory() {
+    let dir = temp_dir("split");
+    std::fs::create_dir_all(dir.join("src/parts")).unwrap();
+    let root = dir.join("src/requirements.yml");
+    let topics = "topics:\n  ../up:\n    name: Up\n    requirements:\n      REQ-1:\n        name: One\n        description: D\n";
+    std::fs::write(
+        &root,
+        ROOT.replace("topics: {}\n", topics) + "includes:\n  - parts/a.yml\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("src/parts/a.yml"),
+        "topics:\n  A:\n    name: A\n    requirements:\n      REQ-2:\n        name: Two\n        description: D\n",
+    )
+    .unwrap();
+    let out_dir = dir.join("out");
+    run(&[
+        "split",
+        root.to_str().unwrap(),
+        "-o",
+        out_dir.to_str().unwrap(),
+    ]);
+    assert!(out_dir.join("___up.yml").exists());
+    assert!(!dir.join("up.yml").exists());
+    let project = format::load(&out_dir.join("requirements.yml")).unwrap();
+    assert_eq!(project.find_requirement("REQ-1").unwrap().0, ["../up"]);
+    assert_eq!(project.find_requirement("REQ-2").unwrap().0, ["A"]);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn split_refuses_topics_sharing_a_file() {
+    let dir = temp_dir("split-collision");
+    let root = dir.join("requirements.yml");
+    let out_dir = dir.join("out");
+    for topics in [
+        "topics:\n  a/b:\n    name: A\n  a_b:\n    name: B\n",
+        "topics:\n  Requirements:\n    name: R\n",
+    ] {
+        std::fs::write(&root, ROOT.replace("topics: {}\n", topics)).unwrap();
+        let output = req(&[
+            "split",
+            root.to_str().unwrap(),
+            "-o",
+            out_dir.to_str().unwrap(),
+        ]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("would be written to"), "{stderr}");
+        assert!(!out_dir.join("requirements.yml").exists());
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
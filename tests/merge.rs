@@ -0,0 +1,125 @@
+//! Merging projects must report every requirement that would end up defined twice or
+//! differently, and comparing them must notice every change.
+
+use req::merge::{self, Conflict};
+use req::{demo_project, diff, Classification, Priority, Project};
+
+fn project_with(topic: &str, id: &str, name: &str) -> Project {
+    let mut project = demo_project();
+    let mut template = project.topics["TOPIC-1"].clone();
+    let mut requirement = template.requirements["REQ-1.1"].clone();
+    requirement.name = name.to_string();
+    template.requirements.clear();
+    template.subtopics.clear();
+    template.requirements.insert(id.to_string(), requirement);
+    project.topics.clear();
+    project.topics.insert(topic.to_string(), template);
+    project
+}
+
+#[test]
+fn merge_reports_different_content() {
+    let mut base = project_with("TOPIC-A", "REQ-1", "First");
+    let conflicts = merge::merge(&mut base, project_with("TOPIC-A", "REQ-1", "Second"));
+    assert_eq!(
+        conflicts,
+        [Conflict::Requirement {
+            id: "REQ-1".to_string()
+        }]
+    );
+    let conflicts = merge::merge(&mut base, project_with("TOPIC-A", " REQ-1 ", "First"));
+    assert!(conflicts.is_empty());
+    assert_eq!(base.topics["TOPIC-A"].requirements.len(), 1);
+}
+
+#[test]
+fn merge_reports_different_priority_and_classification() {
+    let mut base = project_with("TOPIC-A", "REQ-1", "First");
+    let mut other = project_with("TOPIC-A", "REQ-1", "First");
+    other.topics["TOPIC-A"].requirements["REQ-1"].priority = Some(Priority::High);
+    assert_eq!(merge::merge(&mut base, other.clone()).len(), 1);
+    other.topics["TOPIC-A"].requirements["REQ-1"].priority = None;
+    other.topics["TOPIC-A"].requirements["REQ-1"].classification = Some(Classification::Secret);
+    assert_eq!(merge::merge(&mut base, other).len(), 1);
+}
+
+#[test]
+fn diff_finds_changed_priority_and_classification() {
+    let old = project_with("TOPIC-A", "REQ-1", "First");
+    let mut new = old.clone();
+    assert!(diff::diff(&old, &new).is_empty());
+    new.topics["TOPIC-A"].requirements["REQ-1"].priority = Some(Priority::Low);
+    let changes = diff::diff(&old, &new);
+    assert_eq!(changes.modified.len(), 1);
+    assert!(diff::changelog(&old, &new, &changes).contains("_REQ-1_ - First"));
+    new.topics["TOPIC-A"].requirements["REQ-1"].priority = None;
+    new.topics["TOPIC-A"].requirements["REQ-1"].classification = Some(Classification::Internal);
+    assert_eq!(diff::diff(&old, &new).modified.len(), 1);
+}
+
+#[test]
+fn merge_reports_the_same_id_in_another_topic() {
+    let mut base = project_with("TOPIC-A", "REQ-1", "First");
+    let conflicts = merge::merge(&mut base, project_with("TOPIC-B", " REQ-1", "First"));
+    assert_eq!(
+        conflicts,
+        [Conflict::Location {
+            id: "REQ-1".to_string(),
+            first: "topic 'TOPIC-A'".to_string(),
+            second: "topic 'TOPIC-B'".to_string(),
+        }]
+    );
+    assert!(base.topics["TOPIC-B"].requirements.is_empty());
+
+    let mut archived = project_with("TOPIC-A", "REQ-2", "Other");
+    archived.archive_requirement("REQ-2");
+    archived.archive.insert(
+        "REQ-1".to_string(),
+        base.topics["TOPIC-A"].requirements["REQ-1"].clone(),
+    );
+    let conflicts = merge::merge(&mut base, archived);
+    assert_eq!(
+        conflicts,
+        [Conflict::Location {
+            id: "REQ-1".to_string(),
+            first: "topic 'TOPIC-A'".to_string(),
+            second: "the archive".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn merge_matches_topics_by_trimmed_id() {
+    let mut base = project_with("TOPIC-A", "REQ-1", "First");
+    let conflicts = merge::merge(&mut base, project_with(" TOPIC-A ", "REQ-2", "Second"));
+    assert!(conflicts.is_empty(), "{conflicts:?}");
+    assert_eq!(base.topics.len(), 1);
+    assert_eq!(base.topics["TOPIC-A"].requirements.len(), 2);
+    let mut other = project_with(" TOPIC-A", "REQ-3", "Third");
+    other.topics[" TOPIC-A"].name = "Another name".to_string();
+    assert_eq!(
+        merge::merge(&mut base, other),
+        [Conflict::Topic {
+            id: "TOPIC-A".to_string()
+        }]
+    );
+}
+
+#[test]
+fn merge_keeps_the_stricter_topic_classification() {
+    let mut base = project_with("TOPIC-A", "REQ-1", "First");
+    let mut other = project_with("TOPIC-A", "REQ-2", "Second");
+    other.topics["TOPIC-A"].classification = Some(Classification::Secret);
+    assert!(merge::merge(&mut base, other).is_empty());
+    assert_eq!(
+        base.topics["TOPIC-A"].classification,
+        Some(Classification::Secret)
+    );
+    let mut other = project_with("TOPIC-A", "REQ-3", "Third");
+    other.topics["TOPIC-A"].classification = Some(Classification::Public);
+    assert!(merge::merge(&mut base, other).is_empty());
+    assert_eq!(
+        base.topics["TOPIC-A"].classification,
+        Some(Classification::Secret)
+    );
+}
@@ -0,0 +1,56 @@
+//! Renumbering gives every requirement a new, unique ID and keeps all links pointing at the
+//! same requirements.
+
+mod common;
+
+use common::{command, project, req, split_project, temp_dir, write_project};
+use req::{format, reid};
+
+#[test]
+fn reid_never_gives_two_requirements_the_same_id() {
+    let mut project = project();
+    assert!(reid::mapping(&project, "REQ-", 1, 0).is_err());
+    let dir = temp_dir("step");
+    let requirements = write_project(&dir, &project);
+    let content = std::fs::read_to_string(&requirements).unwrap();
+    let output = command()
+        .args(["reid", "--step", "0"])
+        .current_dir(&dir)
+        .output()
+        .expect("Can run req");
+    assert!(!output.status.success());
+    assert_eq!(std::fs::read_to_string(&requirements).unwrap(), content);
+    std::fs::remove_dir_all(dir).unwrap();
+    let duplicate = project.topics["TOPIC-1"].requirements["REQ-1.1"].clone();
+    project.topics["TOPIC-2"]
+        .requirements
+        .insert("REQ-1.1".to_string(), duplicate);
+    assert!(reid::mapping(&project, "REQ-", 1, 1).is_err());
+}
+
+#[test]
+fn reid_updates_links_of_archived_requirements() {
+    let mut project = project();
+    project.archive["REQ-1.2"].links = vec!["REQ-1.1".to_string()];
+    let mapping = reid::mapping(&project, "R-", 1, 1).unwrap();
+    reid::apply(&mut project, &mapping);
+    assert_eq!(project.archive["REQ-1.2"].links, ["R-1"]);
+    assert_eq!(
+        project.topics["TOPIC-3"].requirements["R-3"].links,
+        ["R-1", "R-2"]
+    );
+}
+
+#[test]
+fn reid_renames_links_in_included_files() {
+    let (dir, root, included) = split_project("reid");
+    assert!(req(&["reid", root.to_str().unwrap(), "--prefix", "R-"])
+        .status
+        .success());
+    let project = format::load(&root).unwrap();
+    let (_, requirement) = project.find_requirement("R-2").unwrap();
+    assert_eq!(requirement.links, ["R-1"]);
+    assert!(project.find_requirement("R-1").is_some());
+    assert!(!std::fs::read_to_string(&included).unwrap().contains("REQ-"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,37 @@
+//! A release bundles all documents with a manifest of what it contains and what it lacks.
+
+mod common;
+
+use common::{project, run, temp_dir, write_project};
+
+#[test]
+fn release_renders_the_pdf_or_lists_it_as_omitted() {
+    let dir = temp_dir("release");
+    let requirements = write_project(&dir, &project());
+    let out = dir.join("out");
+    let (requirements, out_dir) = (requirements.to_str().unwrap(), out.to_str().unwrap());
+    let manifest = || -> serde_json::Value {
+        serde_json::from_str(&std::fs::read_to_string(out.join("manifest.json")).unwrap()).unwrap()
+    };
+    run(&["release", requirements, "--out-dir", out_dir]);
+    assert_eq!(
+        manifest()["omitted"],
+        serde_json::json!(["requirements.pdf"])
+    );
+    assert!(manifest()["files"].get("requirements.pdf").is_none());
+    // Copying stands in for a renderer, the PDF is then the HTML
+    run(&[
+        "release",
+        requirements,
+        "--out-dir",
+        out_dir,
+        "--pdf-command",
+        "cp",
+    ]);
+    assert!(manifest().get("omitted").is_none());
+    assert_eq!(
+        manifest()["files"]["requirements.pdf"],
+        manifest()["files"]["requirements.html"]
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
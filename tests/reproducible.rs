@@ -0,0 +1,58 @@
+//! Generated files embed SOURCE_DATE_EPOCH or the Unix epoch as their time, never the time
+//! they were generated at unless asked to.
+
+mod common;
+
+use common::{command, project, run, temp_dir, write_project};
+
+#[test]
+fn source_date_epoch_sets_timestamps() {
+    let dir = temp_dir("epoch");
+    let requirements = write_project(&dir, &project());
+    let output = command()
+        .args(["export", "reqif", requirements.to_str().unwrap()])
+        .env("SOURCE_DATE_EPOCH", "1700000000")
+        .output()
+        .expect("Can run req");
+    let xml = String::from_utf8(output.stdout).unwrap();
+    assert!(xml.contains("<CREATION-TIME>2023-11-14T22:13:20Z</CREATION-TIME>"));
+    assert!(!xml.contains("1970-01-01"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn reproducible_rejects_an_invalid_source_date_epoch() {
+    let schema = |args: &[&str]| {
+        command()
+            .args(args)
+            .env("SOURCE_DATE_EPOCH", "yesterday")
+            .output()
+            .expect("Can run req")
+            .status
+            .success()
+    };
+    assert!(schema(&["schema"]));
+    assert!(!schema(&["schema", "--reproducible"]));
+}
+
+#[test]
+fn generated_files_embed_the_epoch_by_default() {
+    let dir = temp_dir("default-epoch");
+    let requirements = write_project(&dir, &project());
+    let (requirements, out) = (
+        requirements.to_str().unwrap().to_string(),
+        dir.join("out").to_str().unwrap().to_string(),
+    );
+    let xml = String::from_utf8(run(&["export", "reqif", &requirements])).unwrap();
+    assert!(xml.contains("<CREATION-TIME>1970-01-01T00:00:00Z</CREATION-TIME>"));
+    run(&["certpack", &requirements, "--out-dir", &out]);
+    let archive = std::fs::read_dir(&out)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.extension().is_some_and(|e| e == "tar"))
+        .expect("Certpack writes an archive");
+    let archive = std::fs::read(archive).unwrap();
+    // The modification time of the first entry, as zero-padded octal
+    assert_eq!(&archive[136..147], b"00000000000");
+    std::fs::remove_dir_all(dir).unwrap();
+}
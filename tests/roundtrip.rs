@@ -0,0 +1,192 @@
+//! Every emitter must produce the same bytes for the same input, in every run, and the
+//! formats requirements are read from must give back what was written to them.
+
+mod common;
+
+use std::path::Path;
+
+use common::{project, run, temp_dir, write_project};
+use req::format::{self, Format};
+use req::{doorstop, import, reqif, sdoc, Priority, Project};
+
+fn canonical(mut project: Project) -> Project {
+    format::canonicalize(&mut project);
+    project
+}
+
+#[test]
+fn text_formats_round_trip() {
+    let project = project();
+    for format in [Format::Yaml, Format::Json, Format::Toml, Format::Rsn] {
+        let serialized = format.serialize(&project).unwrap();
+        let parsed: Project = format.parse(&serialized).unwrap();
+        assert_eq!(
+            canonical(parsed.clone()),
+            project,
+            "{format:?} changed the project"
+        );
+        assert_eq!(
+            format.serialize(&parsed).unwrap(),
+            serialized,
+            "{format:?} is not byte-stable"
+        );
+    }
+}
+
+#[test]
+fn sdoc_round_trips() {
+    let mut project = project();
+    // StrictDoc has no archive
+    project.archive.clear();
+    let sdoc = sdoc::to_sdoc(&project);
+    let parsed = sdoc::from_sdoc(&sdoc).unwrap();
+    assert_eq!(canonical(parsed.clone()), project);
+    assert_eq!(sdoc::to_sdoc(&parsed), sdoc);
+}
+
+#[test]
+fn reqif_round_trips() {
+    let mut project = project();
+    // ReqIF documents have no archive
+    project.archive.clear();
+    let attributes = reqif::AttributeMap::preset(reqif::Preset::Generic);
+    let xml = reqif::to_reqif(&project, &attributes, 0);
+    let parsed = reqif::from_reqif(&xml, &attributes).unwrap();
+    assert_eq!(canonical(parsed.clone()), project);
+    assert_eq!(reqif::to_reqif(&parsed, &attributes, 0), xml);
+}
+
+#[test]
+fn doorstop_round_trips() {
+    let project = project();
+    let dir = temp_dir("doorstop");
+    let written = doorstop::write(&project, &dir, "REQ", "", 3).unwrap();
+    let mut parsed = doorstop::read(&dir).unwrap();
+    // Doorstop documents have no name, version or description
+    parsed.name = project.name.clone();
+    parsed.version = project.version.clone();
+    parsed.description = project.description.clone();
+    assert_eq!(canonical(parsed.clone()), project);
+    let contents: Vec<_> = written.iter().map(|f| std::fs::read(f).unwrap()).collect();
+    let rewritten = doorstop::write(&parsed, &dir, "REQ", "", 3).unwrap();
+    assert_eq!(rewritten, written);
+    for (file, content) in rewritten.iter().zip(contents) {
+        assert_eq!(
+            std::fs::read(file).unwrap(),
+            content,
+            "{} changed",
+            file.display()
+        );
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+/// Runs every emitter twice in separate processes without any flags, which would differ if
+/// output depended on hash map order, timestamps or the order files are found in
+#[test]
+fn emitters_are_byte_stable_across_runs() {
+    let dir = temp_dir("emitters");
+    let requirements = write_project(&dir, &project());
+    let tests = dir.join("tests");
+    std::fs::create_dir_all(&tests).unwrap();
+    std::fs::write(tests.join("b.txt"), "REQ-1.1: failed - too slow\n").unwrap();
+    std::fs::write(
+        tests.join("a.txt"),
+        "REQ-1.1: failed - wrong\nREQ-2.1: passed\n",
+    )
+    .unwrap();
+    let path = |p: &Path| p.to_str().unwrap().to_string();
+    let (requirements, tests) = (path(&requirements), path(&tests));
+    let a = path(&dir.join("tests/a.txt"));
+    let b = path(&dir.join("tests/b.txt"));
+    let out = path(&dir.join("out"));
+    let commands: Vec<Vec<&str>> = vec![
+        vec!["markdown", &requirements],
+        vec!["html", &requirements],
+        vec!["schema"],
+        vec!["convert", &requirements, "--to", "json"],
+        vec!["check", &requirements, &a, &b],
+        vec![
+            "trace",
+            &requirements,
+            "--tests",
+            &tests,
+            "--format",
+            "json",
+        ],
+        vec!["stats", &requirements, "--format", "json"],
+        vec!["fingerprint", &requirements, "--format", "json"],
+        vec!["export", "reqif", &requirements],
+        vec!["export", "sdoc", &requirements],
+        vec!["export", "jsonl", &requirements, "--chunk-size", "20"],
+        vec![
+            "certpack",
+            &requirements,
+            "--out-dir",
+            &out,
+            "--tests",
+            &tests,
+        ],
+    ];
+    for args in commands {
+        assert_eq!(
+            run(&args),
+            run(&args),
+            "req {} is not byte-stable",
+            args.join(" ")
+        );
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn only_whole_key_words_are_highlighted() {
+    let dir = temp_dir("keywords");
+    let requirements = dir.join("requirements.yml");
+    let mut project = project();
+    project.topics["TOPIC-1"].requirements["REQ-1.1"].description =
+        "The mayor must not wade into shallow water and may swim".to_string();
+    std::fs::write(&requirements, Format::Yaml.serialize(&project).unwrap()).unwrap();
+    let markdown = String::from_utf8(run(&["markdown", requirements.to_str().unwrap()])).unwrap();
+    assert!(
+        markdown.contains("The mayor **_MUST NOT_** wade into shallow water and **_MAY_** swim")
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn markdown_round_trips_priority_and_links() {
+    let dir = temp_dir("markdown");
+    let requirements = dir.join("requirements.yml");
+    let mut project = project();
+    project.topics["TOPIC-3"].requirements["REQ-3.1"].priority = Some(Priority::High);
+    project.topics["TOPIC-1"].requirements["REQ-1.1"].priority = Some(Priority::Critical);
+    // Information that looks like the fields stays information
+    project.topics["TOPIC-1"].requirements["REQ-1.1"]
+        .additional_info
+        .extend([
+            "Priority: agreed with the customer in the kickoff".to_string(),
+            "Links: see the wiki page".to_string(),
+            "**Priority:** high".to_string(),
+            "\\ and _emphasis_".to_string(),
+        ]);
+    std::fs::write(&requirements, Format::Yaml.serialize(&project).unwrap()).unwrap();
+    let markdown = String::from_utf8(run(&["markdown", requirements.to_str().unwrap()])).unwrap();
+    let parsed = import::from_markdown(&markdown).unwrap();
+    let mut requirements = Vec::new();
+    project.visit_requirements(|r| requirements.push((r.id.to_string(), r.requirement.clone())));
+    for (id, requirement) in requirements {
+        let (_, imported) = parsed
+            .find_requirement(&id)
+            .expect("Markdown has every requirement");
+        assert_eq!(imported.priority, requirement.priority, "{id}");
+        assert_eq!(imported.links, requirement.links, "{id}");
+        assert_eq!(
+            imported.additional_info, requirement.additional_info,
+            "{id}"
+        );
+    }
+    let invalid = markdown.replace("  - **Priority:** critical", "  - **Priority:** urgent");
+    assert!(import::from_markdown(&invalid).is_err());
+    std::fs::remove_dir_all(dir).unwrap();
+}
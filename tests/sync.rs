@@ -0,0 +1,63 @@
+//! Synchronizing must leave issues alone whose content the tracker already has, and read the
+//! state of every issue back into its requirement.
+
+use req::sync::{self, Action, GitLab, Issue, RemoteIssue};
+use req::{demo_project, State};
+
+#[test]
+fn gitlab_labels_with_commas_stay_unchanged() {
+    let tracker = GitLab::new(
+        "https://gitlab.example.com/api/v4",
+        "group/project",
+        "token",
+    );
+    let body = "Some requirement\n\n<!-- req:REQ-1 -->".to_string();
+    let issue = Issue {
+        id: "REQ-1".to_string(),
+        title: "REQ-1: Some requirement".to_string(),
+        body: body.clone(),
+        labels: vec![sync::LABEL.to_string(), "Topic, with a comma".to_string()],
+    };
+    // GitLab stored the label with the comma replaced when the issue was created
+    let remote = RemoteIssue {
+        number: 7,
+        state: "opened".to_string(),
+        title: issue.title.clone(),
+        body,
+        labels: vec!["Topic  with a comma".to_string(), sync::LABEL.to_string()],
+    };
+    let actions = sync::plan(&tracker, vec![issue], &[remote]);
+    assert!(
+        matches!(actions.as_slice(), [Action::Unchanged(7, _)]),
+        "{actions:?}"
+    );
+}
+
+fn remote(number: u64, state: &str, id: &str) -> RemoteIssue {
+    RemoteIssue {
+        number,
+        state: state.to_string(),
+        title: id.to_string(),
+        body: format!("Text\n\n<!-- req:{id} -->"),
+        labels: vec![sync::LABEL.to_string()],
+    }
+}
+
+#[test]
+fn issue_states_are_read_into_requirements() {
+    let mut project = demo_project();
+    // GitHub calls open issues `open`, GitLab `opened`
+    let remote = [
+        remote(1, "closed", "REQ-1.1"),
+        remote(2, "opened", "REQ-3.1"),
+        remote(3, "open", "REQ-2.1"),
+    ];
+    let changed = sync::read_state(&mut project.topics, &remote);
+    assert_eq!(changed, ["REQ-1.1", "REQ-2.1", "REQ-3.1"]);
+    let state = |id: &str| project.find_requirement(id).unwrap().1.state;
+    assert_eq!(state("REQ-1.1"), Some(State::Closed));
+    assert_eq!(state("REQ-2.1"), Some(State::Open));
+    assert_eq!(state("REQ-3.1"), Some(State::Open));
+    assert_eq!(state("REQ-1.2"), None);
+    assert!(sync::read_state(&mut project.topics, &remote).is_empty());
+}